@@ -0,0 +1,1017 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{ArgGroup, Parser, Subcommand, ValueEnum};
+use serde::{Deserialize, Serialize};
+
+use skills_manager_core::backup::{export_backup, import_backup, preview_backup, ImportStrategy};
+use skills_manager_core::diff::{diff_skill, DiffAgainst, FileChange};
+use skills_manager_core::distributor::sync_one_skill;
+use skills_manager_core::installer::{inspect_source, install_from_source, install_skill};
+use skills_manager_core::journal::{recover_store, RecoveryReport};
+use skills_manager_core::lint::{lint_skill, lint_store, Severity};
+use skills_manager_core::lockfile::{
+    export_lock, install_from_lock, read_lock_file, write_lock_file, LOCKFILE_NAME,
+};
+use skills_manager_core::manifest::load_manifest;
+use skills_manager_core::models::{AgentInfo, Skill};
+use skills_manager_core::plan::{
+    plan_sync_all_skills_distribution, plan_sync_all_to_manager_store, plan_uninstall_skill,
+    PlannedAction, PlannedOperation,
+};
+use skills_manager_core::progress::NoopProgress;
+use skills_manager_core::scanner::{
+    detect_untracked_skills, pull_skill_from_agent, sync_all_to_manager_store,
+};
+use skills_manager_core::status::get_distribution_status;
+use skills_manager_core::store::{list_skills, uninstall_skill};
+use skills_manager_core::trash::{empty_trash, list_trash, purge_expired_trash, restore_skill};
+use skills_manager_core::updater::{check_updates, update_skills};
+use skills_manager_core::utils::{ensure_dir, expand_tilde, manager_store_root, write_file_atomic};
+
+const DEFAULT_CONFIG_PATH: &str = "~/.config/skillsm/config.json";
+const DEFAULT_STORAGE_PATH: &str = "~/.skillsm";
+
+#[derive(Debug, Parser)]
+#[command(name = "skillsm", version, about = "Headless skills manager")]
+struct Cli {
+    /// Config file (defaults to $SKILLSM_CONFIG, then ~/.config/skillsm/config.json)
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+    /// Override the manager store path from the config file
+    #[arg(long, global = true)]
+    store: Option<String>,
+    /// Print machine-readable JSON instead of human output
+    #[arg(long, global = true)]
+    json: bool,
+    #[command(subcommand)]
+    command: CliCommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum CliCommand {
+    /// Install a skill from a GitHub repo, git URL or .zip URL into the store
+    Install {
+        url: String,
+        /// Install only the skill at this subpath of a skill pack (repeatable)
+        #[arg(long = "skill")]
+        skills: Vec<String>,
+    },
+    /// List the skills a GitHub repo, git URL or .zip URL contains, without installing
+    Inspect { url: String },
+    /// List skills in the manager store and the agents they are distributed to
+    List,
+    /// Distribute a skill to the given agents
+    Enable {
+        skill: String,
+        #[arg(required = true)]
+        agents: Vec<String>,
+        /// Print the planned operations without touching disk
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Remove a skill from the given agents
+    Disable {
+        skill: String,
+        #[arg(required = true)]
+        agents: Vec<String>,
+        /// Print the planned operations without touching disk
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Import every skill found in agent directories into the store
+    Sync {
+        /// Print the planned operations without touching disk
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Report skills in agent directories that are not in the store
+    Scan,
+    /// Compare every agent copy of every skill with the store
+    Status,
+    /// Check SKILL.md files against the rules agents enforce
+    Lint {
+        /// Skill to lint; every store skill when omitted
+        skill: Option<String>,
+        /// Write the available fixes back to SKILL.md
+        #[arg(long)]
+        fix: bool,
+    },
+    /// Copy an agent's edited copy of a skill back into the store and redistribute it
+    Pull { skill: String, agent: String },
+    /// Show how an agent copy or another store skill differs from a store skill
+    #[command(group(ArgGroup::new("against").required(true).args(["agent", "with"])))]
+    Diff {
+        skill: String,
+        /// Agent whose copy is compared with the store
+        #[arg(long)]
+        agent: Option<String>,
+        /// Another store skill to compare with
+        #[arg(long)]
+        with: Option<String>,
+    },
+    /// List skills whose git or zip source has moved past the installed revision
+    Outdated,
+    /// Re-fetch skills from their sources and redistribute them
+    Update {
+        /// Skills to update; every outdated skill when omitted
+        skills: Vec<String>,
+    },
+    /// Write a lockfile pinning every store skill to its source, revision and content hash
+    Lock {
+        #[arg(long, default_value = LOCKFILE_NAME)]
+        output: PathBuf,
+    },
+    /// Install and distribute the skills of a lockfile, verifying their hashes
+    InstallLock {
+        #[arg(default_value = LOCKFILE_NAME)]
+        path: PathBuf,
+    },
+    /// Archive every store skill, the skill records and the agent config into one zip file
+    Backup { path: PathBuf },
+    /// Restore a backup archive into the store
+    Restore {
+        path: PathBuf,
+        /// What to do with skills that already exist in the store
+        #[arg(long, value_enum, default_value_t = RestoreStrategy::Skip)]
+        strategy: RestoreStrategy,
+        /// List the skills and conflicts without writing anything
+        #[arg(long)]
+        preview: bool,
+    },
+    /// Move a skill from the store and every agent directory into the trash
+    Uninstall {
+        skill: String,
+        /// Print the planned operations without touching disk
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// List, restore or delete uninstalled skills
+    Trash {
+        #[command(subcommand)]
+        action: TrashAction,
+    },
+    /// Settle operations an interrupted run left behind and sweep stale staging dirs
+    Recover,
+}
+
+impl CliCommand {
+    /// Whether the command writes to the store or agent directories, and so
+    /// first settles whatever an interrupted earlier run left behind.
+    fn writes(&self) -> bool {
+        match self {
+            CliCommand::Enable { dry_run, .. }
+            | CliCommand::Disable { dry_run, .. }
+            | CliCommand::Sync { dry_run }
+            | CliCommand::Uninstall { dry_run, .. } => !dry_run,
+            CliCommand::Restore { preview, .. } => !preview,
+            CliCommand::Lint { fix, .. } => *fix,
+            CliCommand::Trash { action } => !matches!(action, TrashAction::List),
+            CliCommand::Install { .. }
+            | CliCommand::Pull { .. }
+            | CliCommand::Update { .. }
+            | CliCommand::InstallLock { .. } => true,
+            CliCommand::Inspect { .. }
+            | CliCommand::List
+            | CliCommand::Scan
+            | CliCommand::Status
+            | CliCommand::Diff { .. }
+            | CliCommand::Outdated
+            | CliCommand::Lock { .. }
+            | CliCommand::Backup { .. }
+            | CliCommand::Recover => false,
+        }
+    }
+}
+
+#[derive(Debug, Subcommand)]
+enum TrashAction {
+    /// List trash entries, oldest first
+    List,
+    /// Put a trashed skill and its agent copies back where they were
+    Restore { id: String },
+    /// Permanently delete every trash entry
+    Empty,
+    /// Permanently delete entries trashed more than the given number of days ago
+    Purge {
+        #[arg(long, default_value_t = 15)]
+        days: u32,
+    },
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum RestoreStrategy {
+    Skip,
+    Overwrite,
+    KeepBoth,
+}
+
+impl From<RestoreStrategy> for ImportStrategy {
+    fn from(strategy: RestoreStrategy) -> Self {
+        match strategy {
+            RestoreStrategy::Skip => ImportStrategy::Skip,
+            RestoreStrategy::Overwrite => ImportStrategy::Overwrite,
+            RestoreStrategy::KeepBoth => ImportStrategy::KeepBoth,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CliConfig {
+    #[serde(default = "default_storage_path")]
+    storage_path: String,
+    #[serde(default)]
+    agents: Vec<AgentInfo>,
+}
+
+impl Default for CliConfig {
+    fn default() -> Self {
+        Self {
+            storage_path: default_storage_path(),
+            agents: vec![],
+        }
+    }
+}
+
+fn default_storage_path() -> String {
+    DEFAULT_STORAGE_PATH.to_string()
+}
+
+fn resolve_config_path(explicit: Option<&Path>) -> (PathBuf, bool) {
+    if let Some(path) = explicit {
+        return (path.to_path_buf(), true);
+    }
+    if let Ok(env_path) = std::env::var("SKILLSM_CONFIG") {
+        if !env_path.trim().is_empty() {
+            return (expand_tilde(&env_path), true);
+        }
+    }
+    (expand_tilde(DEFAULT_CONFIG_PATH), false)
+}
+
+fn load_config(path: &Path, required: bool) -> Result<CliConfig, String> {
+    if !path.exists() {
+        if required {
+            return Err(format!("Config file not found: {}", path.display()));
+        }
+        return Ok(CliConfig::default());
+    }
+
+    let text = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read config {}: {e}", path.display()))?;
+    serde_json::from_str(&text)
+        .map_err(|e| format!("Failed to parse config {}: {e}", path.display()))
+}
+
+fn save_config(path: &Path, config: &CliConfig) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        ensure_dir(parent)?;
+    }
+    let text = serde_json::to_string_pretty(config)
+        .map_err(|e| format!("Failed to serialize config: {e}"))?;
+    write_file_atomic(path, text.as_bytes())
+}
+
+fn find_agent<'a>(config: &'a CliConfig, id: &str) -> Result<&'a AgentInfo, String> {
+    config
+        .agents
+        .iter()
+        .find(|agent| agent.id == id)
+        .ok_or_else(|| format!("Unknown agent id: {id}"))
+}
+
+fn require_store_skill(store_root: &Path, skill_name: &str) -> Result<Skill, String> {
+    load_manifest(store_root)?
+        .get(skill_name)
+        .cloned()
+        .ok_or_else(|| format!("Skill not found in store: {skill_name}"))
+}
+
+/// The skill record with `agent_ids` added to or removed from its agents.
+fn toggled_skill(
+    config: &CliConfig,
+    skill_name: &str,
+    agent_ids: &[String],
+    enable: bool,
+) -> Result<Skill, String> {
+    for id in agent_ids {
+        find_agent(config, id)?;
+    }
+
+    let store_root = manager_store_root(&config.storage_path)?;
+    let mut skill = require_store_skill(&store_root, skill_name)?;
+    if enable {
+        for id in agent_ids {
+            if !skill.enabled_agents.contains(id) {
+                skill.enabled_agents.push(id.clone());
+            }
+        }
+    } else {
+        skill.enabled_agents.retain(|id| !agent_ids.contains(id));
+    }
+    Ok(skill)
+}
+
+fn set_distribution(
+    config: &CliConfig,
+    skill_name: &str,
+    agent_ids: &[String],
+    enable: bool,
+) -> Result<Skill, String> {
+    let store_root = manager_store_root(&config.storage_path)?;
+    let skill = toggled_skill(config, skill_name, agent_ids, enable)?;
+    let enabled = skill.enabled_agents;
+
+    let report = sync_one_skill(&store_root, &skill.name, &enabled, &config.agents)?;
+    for blocked in &report.blocked {
+        eprintln!(
+            "warning: not distributed to {}: {} breaks {}",
+            blocked.agent_id,
+            blocked.skill,
+            blocked.rules.join(", ")
+        );
+    }
+    require_store_skill(&store_root, &skill.name)
+}
+
+fn print_json<T: Serialize>(value: &T) -> Result<(), String> {
+    let text = serde_json::to_string_pretty(value)
+        .map_err(|e| format!("Failed to serialize output: {e}"))?;
+    println!("{text}");
+    Ok(())
+}
+
+fn plan_distribution(
+    config: &CliConfig,
+    skill_name: &str,
+    agent_ids: &[String],
+    enable: bool,
+    json: bool,
+) -> Result<(), String> {
+    let skill = toggled_skill(config, skill_name, agent_ids, enable)?;
+    let ops = plan_sync_all_skills_distribution(
+        std::slice::from_ref(&skill),
+        &config.agents,
+        &config.storage_path,
+    )?;
+    if json {
+        return print_json(&ops);
+    }
+    print_plan(&ops);
+    Ok(())
+}
+
+/// Prints `ops` like a terraform plan, one operation per line and a summary.
+fn print_plan(ops: &[PlannedOperation]) {
+    if ops.is_empty() {
+        println!("No changes.");
+        return;
+    }
+    let (mut add, mut change, mut remove) = (0, 0, 0);
+    for op in ops {
+        let sign = match op.action {
+            PlannedAction::Copy | PlannedAction::Link => {
+                add += 1;
+                '+'
+            }
+            PlannedAction::Replace | PlannedAction::RewriteFrontmatter => {
+                change += 1;
+                '~'
+            }
+            PlannedAction::Remove => {
+                remove += 1;
+                '-'
+            }
+        };
+        println!(
+            "  {sign} {:<19} {}: {}  ({})",
+            op.action.as_str(),
+            op.skill,
+            op.target,
+            op.reason
+        );
+    }
+    println!("\nPlan: {add} to add, {change} to change, {remove} to remove.");
+}
+
+fn print_skill_line(skill: &Skill) {
+    if skill.enabled_agents.is_empty() {
+        println!("{}", skill.name);
+    } else {
+        println!("{}  [{}]", skill.name, skill.enabled_agents.join(", "));
+    }
+}
+
+fn short_revision(revision: Option<&str>) -> &str {
+    match revision {
+        Some(revision) => &revision[..revision.len().min(12)],
+        None => "-",
+    }
+}
+
+fn execute(cli: Cli) -> Result<(), String> {
+    let (config_path, required) = resolve_config_path(cli.config.as_deref());
+    let mut config = load_config(&config_path, required)?;
+    if let Some(store) = cli.store {
+        config.storage_path = store;
+    }
+    // Settle whatever an interrupted earlier run left behind, as the app does on start.
+    let store_root = expand_tilde(&config.storage_path);
+    if cli.command.writes() && store_root.is_dir() {
+        for failure in recover_store(&store_root, &config.agents)?.failed {
+            eprintln!("warning: could not recover {failure}");
+        }
+    }
+
+    match cli.command {
+        CliCommand::Install { url, skills } if skills.is_empty() => {
+            let skill = install_skill(&url, &config.storage_path)?;
+            if cli.json {
+                return print_json(&skill);
+            }
+            println!("Installed {}", skill.name);
+        }
+        CliCommand::Install { url, skills } => {
+            let installed =
+                install_from_source(&url, &skills, &config.storage_path, &NoopProgress)?;
+            if cli.json {
+                return print_json(&installed);
+            }
+            for skill in &installed {
+                println!("Installed {}", skill.name);
+            }
+        }
+        CliCommand::Inspect { url } => {
+            let inspection = inspect_source(&url, &config.storage_path, &NoopProgress)?;
+            if cli.json {
+                return print_json(&inspection);
+            }
+            for skill in &inspection.skills {
+                let subpath = if skill.subpath.is_empty() {
+                    "."
+                } else {
+                    &skill.subpath
+                };
+                let description = skill
+                    .metadata
+                    .as_ref()
+                    .and_then(|meta| meta.description.as_deref())
+                    .unwrap_or("");
+                println!("{subpath}\t{}\t{description}", skill.name);
+            }
+        }
+        CliCommand::Outdated => {
+            let report = check_updates(&config.storage_path, &NoopProgress)?;
+            if cli.json {
+                return print_json(&report);
+            }
+            for failure in &report.failed {
+                eprintln!(
+                    "warning: could not check {}: {}",
+                    failure.name, failure.error
+                );
+            }
+            if report.outdated.is_empty() {
+                println!("All skills are up to date");
+            }
+            for update in &report.outdated {
+                println!(
+                    "{}\t{} -> {}",
+                    update.name,
+                    short_revision(update.old_revision.as_deref()),
+                    short_revision(Some(&update.new_revision))
+                );
+            }
+        }
+        CliCommand::Update { skills } => {
+            let ids: Vec<String> = if skills.is_empty() {
+                let report = check_updates(&config.storage_path, &NoopProgress)?;
+                for failure in &report.failed {
+                    eprintln!(
+                        "warning: could not check {}: {}",
+                        failure.name, failure.error
+                    );
+                }
+                report
+                    .outdated
+                    .into_iter()
+                    .map(|update| update.id)
+                    .collect()
+            } else {
+                let store_root = manager_store_root(&config.storage_path)?;
+                skills
+                    .iter()
+                    .map(|name| require_store_skill(&store_root, name).map(|skill| skill.id))
+                    .collect::<Result<_, _>>()?
+            };
+            let updated = update_skills(&ids, &config.agents, &config.storage_path, &NoopProgress)?;
+            if cli.json {
+                return print_json(&updated);
+            }
+            if updated.is_empty() {
+                println!("All skills are up to date");
+            }
+            for skill in &updated {
+                println!("Updated {}", skill.name);
+            }
+        }
+        CliCommand::Lock { output } => {
+            let export = export_lock(&config.storage_path)?;
+            write_lock_file(&output, &export.lock)?;
+            if cli.json {
+                return print_json(&export);
+            }
+            for name in &export.skipped {
+                eprintln!("warning: {name} has no source and was left out");
+            }
+            println!(
+                "Locked {} skill(s) in {}",
+                export.lock.skills.len(),
+                output.display()
+            );
+        }
+        CliCommand::InstallLock { path } => {
+            let lock = read_lock_file(&path)?;
+            let report =
+                install_from_lock(&lock, &config.agents, &config.storage_path, &NoopProgress)?;
+            if cli.json {
+                return print_json(&report);
+            }
+            for name in &report.installed {
+                println!("Installed {name}");
+            }
+            for name in &report.unchanged {
+                println!("Unchanged {name}");
+            }
+            for mismatch in &report.mismatched {
+                eprintln!(
+                    "mismatch: {} {:?} expected {}, got {}",
+                    mismatch.name,
+                    mismatch.kind,
+                    mismatch.expected,
+                    mismatch.actual.as_deref().unwrap_or("nothing")
+                );
+            }
+            for failure in &report.failed {
+                eprintln!("failed: {}: {}", failure.name, failure.error);
+            }
+            if !report.mismatched.is_empty() || !report.failed.is_empty() {
+                return Err(format!(
+                    "{} skill(s) did not match the lock, {} failed",
+                    report.mismatched.len(),
+                    report.failed.len()
+                ));
+            }
+        }
+        CliCommand::Backup { path } => {
+            let summary = export_backup(&path, &config.agents, &config.storage_path)?;
+            if cli.json {
+                return print_json(&summary);
+            }
+            println!(
+                "Backed up {} skill(s), {} file(s) to {}",
+                summary.skills,
+                summary.files,
+                path.display()
+            );
+        }
+        CliCommand::Restore {
+            path,
+            preview: true,
+            ..
+        } => {
+            let preview = preview_backup(&path, &config.storage_path)?;
+            if cli.json {
+                return print_json(&preview);
+            }
+            for name in &preview.skills {
+                if preview.conflicts.contains(name) {
+                    println!("{name}\tconflict");
+                } else {
+                    println!("{name}\tnew");
+                }
+            }
+        }
+        CliCommand::Restore { path, strategy, .. } => {
+            let report = import_backup(&path, strategy.into(), &config.storage_path)?;
+            // A fresh machine has no agents configured yet; take the backed-up ones.
+            let restore_agents = config.agents.is_empty() && !report.agents.is_empty();
+            if restore_agents {
+                // Reload so a `--store` override is not written back.
+                let mut saved = load_config(&config_path, false)?;
+                saved.agents = report.agents.clone();
+                save_config(&config_path, &saved)?;
+            }
+            if cli.json {
+                return print_json(&report);
+            }
+            for name in &report.imported {
+                println!("Imported {name}");
+            }
+            for name in &report.overwritten {
+                println!("Overwrote {name}");
+            }
+            for renamed in &report.renamed {
+                println!("Imported {} as {}", renamed.from, renamed.to);
+            }
+            for name in &report.skipped {
+                println!("Skipped {name}");
+            }
+            if restore_agents {
+                println!(
+                    "Restored {} agent(s) into {}",
+                    report.agents.len(),
+                    config_path.display()
+                );
+            }
+        }
+        CliCommand::List => {
+            let skills = list_skills(&config.storage_path)?;
+            if cli.json {
+                return print_json(&skills);
+            }
+            for skill in &skills {
+                print_skill_line(skill);
+            }
+        }
+        CliCommand::Enable {
+            skill,
+            agents,
+            dry_run: true,
+        } => return plan_distribution(&config, &skill, &agents, true, cli.json),
+        CliCommand::Disable {
+            skill,
+            agents,
+            dry_run: true,
+        } => return plan_distribution(&config, &skill, &agents, false, cli.json),
+        CliCommand::Enable { skill, agents, .. } => {
+            let skill = set_distribution(&config, &skill, &agents, true)?;
+            if cli.json {
+                return print_json(&skill);
+            }
+            print_skill_line(&skill);
+        }
+        CliCommand::Disable { skill, agents, .. } => {
+            let skill = set_distribution(&config, &skill, &agents, false)?;
+            if cli.json {
+                return print_json(&skill);
+            }
+            print_skill_line(&skill);
+        }
+        CliCommand::Sync { dry_run: true } => {
+            let ops = plan_sync_all_to_manager_store(&config.agents, &config.storage_path)?;
+            if cli.json {
+                return print_json(&ops);
+            }
+            print_plan(&ops);
+        }
+        CliCommand::Sync { .. } => {
+            let skills =
+                sync_all_to_manager_store(&config.agents, &config.storage_path, &NoopProgress)?;
+            if cli.json {
+                return print_json(&skills);
+            }
+            println!("Store now holds {} skill(s)", skills.len());
+        }
+        CliCommand::Scan => {
+            let detected = detect_untracked_skills(&config.agents, &config.storage_path)?;
+            if cli.json {
+                return print_json(&detected);
+            }
+            if detected.is_empty() {
+                println!("No untracked skills found");
+            }
+            for skill in &detected {
+                println!("{}  [{}]", skill.name, skill.source_agent_names.join(", "));
+            }
+        }
+        CliCommand::Status => {
+            let rows = get_distribution_status(&config.agents, &config.storage_path)?;
+            if cli.json {
+                return print_json(&rows);
+            }
+            for row in &rows {
+                let cells: Vec<String> = row
+                    .agents
+                    .iter()
+                    .map(|cell| format!("{}: {}", cell.agent_id, cell.state.as_str()))
+                    .collect();
+                println!("{}  [{}]", row.skill, cells.join(", "));
+            }
+        }
+        CliCommand::Lint { skill, fix } => {
+            let reports = match skill {
+                Some(skill) => vec![lint_skill(&skill, &config.storage_path, fix)?],
+                None => lint_store(&config.storage_path, fix)?,
+            };
+            let errors = reports.iter().filter(|report| report.has_errors()).count();
+            if cli.json {
+                print_json(&reports)?;
+            } else {
+                for report in &reports {
+                    if !report.fixed.is_empty() {
+                        println!("{}: fixed {}", report.skill, report.fixed.join(", "));
+                    }
+                    for d in &report.diagnostics {
+                        let line = d.line.map(|l| format!(":{l}")).unwrap_or_default();
+                        let severity = match d.severity {
+                            Severity::Error => "error",
+                            Severity::Warning => "warning",
+                        };
+                        let hint = if d.fixable { " (fixable)" } else { "" };
+                        println!(
+                            "{}/{}{line}: {severity} [{}] {}{hint}",
+                            report.skill, d.file, d.rule, d.message
+                        );
+                    }
+                }
+            }
+            if errors > 0 {
+                return Err(format!("{errors} skill(s) have lint errors"));
+            }
+        }
+        CliCommand::Pull { skill, agent } => {
+            let report =
+                pull_skill_from_agent(&skill, &agent, &config.agents, &config.storage_path)?;
+            if cli.json {
+                return print_json(&report);
+            }
+            match &report.snapshot {
+                Some(snapshot) => println!(
+                    "Pulled {} from {agent} (previous version saved to {snapshot})",
+                    report.skill.name
+                ),
+                None => println!("{} already matches {agent}", report.skill.name),
+            }
+        }
+        CliCommand::Diff { skill, agent, with } => {
+            let against = match (agent, with) {
+                (Some(agent), _) => DiffAgainst::Agent(agent),
+                (None, Some(other)) => DiffAgainst::Skill(other),
+                (None, None) => return Err("pass --agent or --with".to_string()),
+            };
+            let diff = diff_skill(&skill, &against, &config.agents, &config.storage_path)?;
+            if cli.json {
+                return print_json(&diff);
+            }
+            if diff.is_empty() {
+                println!("No differences");
+            }
+            for file in &diff.files {
+                if let Some(unified) = &file.unified {
+                    print!("{unified}");
+                    continue;
+                }
+                let marker = match file.change {
+                    FileChange::Added => "added",
+                    FileChange::Removed => "removed",
+                    FileChange::Modified => "modified",
+                };
+                let size =
+                    |size: Option<u64>| size.map_or("-".to_string(), |s| format!("{s} bytes"));
+                if let Some(binary) = &file.binary {
+                    println!(
+                        "Binary file {} {marker} ({} -> {})",
+                        file.path,
+                        size(binary.old_size),
+                        size(binary.new_size)
+                    );
+                }
+            }
+        }
+        CliCommand::Uninstall {
+            skill,
+            dry_run: true,
+        } => {
+            let store_root = manager_store_root(&config.storage_path)?;
+            let name = require_store_skill(&store_root, &skill)?.name;
+            let ops = plan_uninstall_skill(&name, &config.agents, &config.storage_path)?;
+            if cli.json {
+                return print_json(&ops);
+            }
+            print_plan(&ops);
+        }
+        CliCommand::Uninstall { skill, .. } => {
+            let store_root = manager_store_root(&config.storage_path)?;
+            let name = require_store_skill(&store_root, &skill)?.name;
+            let entry = uninstall_skill(&name, &config.agents, &config.storage_path)?;
+            if cli.json {
+                return print_json(&entry);
+            }
+            println!("Moved {name} to trash ({})", entry.id);
+        }
+        CliCommand::Trash {
+            action: TrashAction::List,
+        } => {
+            let entries = list_trash(&config.storage_path)?;
+            if cli.json {
+                return print_json(&entries);
+            }
+            for entry in &entries {
+                println!("{}\t{}\t{}", entry.id, entry.name, entry.deleted_at);
+            }
+        }
+        CliCommand::Trash {
+            action: TrashAction::Restore { id },
+        } => {
+            let report = restore_skill(&id, &config.storage_path)?;
+            if cli.json {
+                return print_json(&report);
+            }
+            println!("Restored {}", report.skill.name);
+            for copy in &report.skipped {
+                println!(
+                    "Kept in trash: {} ({} is taken)",
+                    copy.agent_id, copy.original
+                );
+            }
+        }
+        CliCommand::Trash {
+            action: TrashAction::Empty,
+        } => {
+            let count = empty_trash(&config.storage_path)?;
+            if cli.json {
+                return print_json(&serde_json::json!({ "deleted": count }));
+            }
+            println!("Deleted {count} trash entry(ies)");
+        }
+        CliCommand::Trash {
+            action: TrashAction::Purge { days },
+        } => {
+            let purged = purge_expired_trash(days, &config.storage_path)?;
+            if cli.json {
+                return print_json(&purged);
+            }
+            for id in &purged {
+                println!("Deleted {id}");
+            }
+        }
+        CliCommand::Recover => {
+            let report = if store_root.is_dir() {
+                recover_store(&store_root, &config.agents)?
+            } else {
+                RecoveryReport::default()
+            };
+            if cli.json {
+                return print_json(&report);
+            }
+            for operation in &report.rolled_back {
+                println!("Rolled back {operation}");
+            }
+            for operation in &report.completed {
+                println!("Completed {operation}");
+            }
+            for name in &report.removed_temp_dirs {
+                println!("Removed {name}");
+            }
+            for failure in &report.failed {
+                eprintln!("warning: could not recover {failure}");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match execute(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use skills_manager_core::models::DistributionMode;
+    use skills_manager_core::utils::{ensure_dir, generate_id};
+
+    fn temp_test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("skills-manager-{name}-{}", generate_id()));
+        ensure_dir(&dir).expect("create temp dir");
+        dir
+    }
+
+    fn write_skill(root: &Path, name: &str) {
+        let dir = root.join(name);
+        ensure_dir(&dir).expect("create skill dir");
+        fs::write(dir.join("SKILL.md"), format!("---\nname: {name}\n---\n"))
+            .expect("write SKILL.md");
+    }
+
+    fn config_for(tmp: &Path) -> CliConfig {
+        let agent_root = tmp.join("codex");
+        ensure_dir(&agent_root).unwrap();
+        CliConfig {
+            storage_path: tmp.join("store").to_string_lossy().to_string(),
+            agents: vec![AgentInfo {
+                id: "codex".to_string(),
+                name: "Codex".to_string(),
+                default_path: agent_root.to_string_lossy().to_string(),
+                current_path: agent_root.to_string_lossy().to_string(),
+                enabled: true,
+                icon: "codex".to_string(),
+                distribution_mode: DistributionMode::Copy,
+            }],
+        }
+    }
+
+    #[test]
+    fn load_config_defaults_when_optional_file_is_missing() {
+        let tmp = temp_test_dir("cli-config-missing");
+        let config = load_config(&tmp.join("config.json"), false).unwrap();
+        assert_eq!(config.storage_path, DEFAULT_STORAGE_PATH);
+        assert!(config.agents.is_empty());
+
+        let err = load_config(&tmp.join("config.json"), true).unwrap_err();
+        assert!(err.contains("Config file not found"));
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn load_config_reads_agents_and_storage_path() {
+        let tmp = temp_test_dir("cli-config-read");
+        let path = tmp.join("config.json");
+        fs::write(
+            &path,
+            r#"{
+                "storagePath": "/tmp/store",
+                "agents": [{ "id": "codex", "name": "Codex", "defaultPath": "~/.codex/skills", "enabled": true }]
+            }"#,
+        )
+        .unwrap();
+
+        let config = load_config(&path, true).unwrap();
+        assert_eq!(config.storage_path, "/tmp/store");
+        assert_eq!(config.agents.len(), 1);
+        assert_eq!(config.agents[0].current_path, "");
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn enable_and_disable_update_agent_dirs_and_manifest() {
+        let tmp = temp_test_dir("cli-enable-disable");
+        let config = config_for(&tmp);
+        write_skill(&tmp.join("store"), "alpha");
+
+        let enabled = set_distribution(&config, "alpha", &["codex".to_string()], true).unwrap();
+        assert_eq!(enabled.enabled_agents, vec!["codex".to_string()]);
+        assert!(tmp.join("codex").join("alpha").join("SKILL.md").exists());
+
+        let listed = list_skills(&config.storage_path).unwrap();
+        assert_eq!(listed[0].enabled_agents, vec!["codex".to_string()]);
+
+        let disabled = set_distribution(&config, "alpha", &["codex".to_string()], false).unwrap();
+        assert!(disabled.enabled_agents.is_empty());
+        assert!(!tmp.join("codex").join("alpha").exists());
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn only_commands_that_write_recover_the_store() {
+        let tmp = temp_test_dir("cli-recover");
+        let config_path = tmp.join("config.json");
+        save_config(&config_path, &config_for(&tmp)).unwrap();
+        write_skill(&tmp.join("store"), "alpha");
+        let leftover = tmp.join("store").join(".tmp-install-orphan");
+        ensure_dir(&leftover).unwrap();
+
+        let run = |args: &[&str]| {
+            let mut argv = vec!["skillsm", "--config", config_path.to_str().unwrap()];
+            argv.extend_from_slice(args);
+            execute(Cli::parse_from(argv)).unwrap();
+        };
+        for args in [
+            &["status"][..],
+            &["sync", "--dry-run"],
+            &["uninstall", "alpha", "--dry-run"],
+        ] {
+            run(args);
+            assert!(leftover.exists(), "{args:?} recovered the store");
+        }
+        run(&["recover"]);
+        assert!(!leftover.exists());
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn set_distribution_rejects_unknown_agents() {
+        let tmp = temp_test_dir("cli-unknown-agent");
+        let config = config_for(&tmp);
+        write_skill(&tmp.join("store"), "alpha");
+
+        let err = set_distribution(&config, "alpha", &["nope".to_string()], true).unwrap_err();
+        assert!(err.contains("Unknown agent id"));
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+}
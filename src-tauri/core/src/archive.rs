@@ -0,0 +1,331 @@
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Component, Path, PathBuf};
+
+use zip::ZipArchive;
+
+use crate::utils::ensure_dir;
+
+/// Caps applied while extracting an untrusted archive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExtractLimits {
+    /// Total bytes written across all entries, counted as they are decompressed.
+    pub max_total_bytes: u64,
+    pub max_entries: usize,
+}
+
+impl Default for ExtractLimits {
+    fn default() -> Self {
+        Self {
+            max_total_bytes: 256 * 1024 * 1024,
+            max_entries: 10_000,
+        }
+    }
+}
+
+/// Turns an entry name into a relative path made of normal components only.
+fn entry_rel_path(name: &str) -> Result<PathBuf, String> {
+    let name = name.replace('\\', "/");
+    if name.starts_with('/') {
+        return Err(format!("Archive entry has an absolute path: {name}"));
+    }
+    let mut rel = PathBuf::new();
+    for part in name.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => return Err(format!("Archive entry escapes the destination: {name}")),
+            _ if part.contains(':') => {
+                return Err(format!("Archive entry has an absolute path: {name}"))
+            }
+            _ => rel.push(part),
+        }
+    }
+    if rel.as_os_str().is_empty() {
+        return Err(format!("Archive entry has an empty path: {name}"));
+    }
+    Ok(rel)
+}
+
+/// Accepts a symlink target only when it climbs with leading `..` and then
+/// descends, so it resolves the same way lexically and on disk and stays
+/// inside the destination.
+fn check_link_target(rel: &Path, target: &str) -> Result<(), String> {
+    let escape = || {
+        format!(
+            "Archive symlink {} points outside the destination: {target}",
+            rel.display()
+        )
+    };
+    let target_path = Path::new(target);
+    if target.is_empty()
+        || target.starts_with('/')
+        || target.starts_with('\\')
+        || target.contains(':')
+    {
+        return Err(escape());
+    }
+    let mut depth = rel.components().count() - 1;
+    let mut descending = false;
+    for component in target_path.components() {
+        match component {
+            Component::ParentDir if !descending => {
+                depth = depth.checked_sub(1).ok_or_else(escape)?;
+            }
+            Component::CurDir => {}
+            Component::Normal(_) => descending = true,
+            _ => return Err(escape()),
+        }
+    }
+    Ok(())
+}
+
+/// Refuses to write through a symlink created by an earlier entry.
+fn check_no_link_on_path(dest: &Path, rel: &Path) -> Result<(), String> {
+    let mut current = dest.to_path_buf();
+    for component in rel.components() {
+        current.push(component);
+        if fs::symlink_metadata(&current).is_ok_and(|meta| meta.file_type().is_symlink()) {
+            return Err(format!(
+                "Archive entry {} passes through a symlink",
+                rel.display()
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn create_symlink(target: &str, path: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, path)
+}
+
+#[cfg(windows)]
+fn create_symlink(target: &str, path: &Path) -> io::Result<()> {
+    std::os::windows::fs::symlink_file(target, path)
+}
+
+#[cfg(unix)]
+fn apply_mode(path: &Path, mode: Option<u32>) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+    let Some(mode) = mode else {
+        return Ok(());
+    };
+    // Keep the execute bits, never setuid/setgid or world-writable.
+    let mode = if mode & 0o111 != 0 { 0o755 } else { 0o644 };
+    fs::set_permissions(path, fs::Permissions::from_mode(mode))
+        .map_err(|e| format!("Failed to set permissions on {}: {e}", path.display()))
+}
+
+#[cfg(not(unix))]
+fn apply_mode(_path: &Path, _mode: Option<u32>) -> Result<(), String> {
+    Ok(())
+}
+
+/// Extracts the zip at `archive` into `dest`, rejecting entries that would land
+/// outside it and stopping once `limits` are exceeded.
+pub fn extract_zip(archive: &Path, dest: &Path, limits: &ExtractLimits) -> Result<(), String> {
+    let file = fs::File::open(archive)
+        .map_err(|e| format!("Failed to open archive {}: {e}", archive.display()))?;
+    let mut zip = ZipArchive::new(file)
+        .map_err(|e| format!("Failed to read archive {}: {e}", archive.display()))?;
+    if zip.len() > limits.max_entries {
+        return Err(format!(
+            "Archive has {} entries, more than the limit of {}",
+            zip.len(),
+            limits.max_entries
+        ));
+    }
+    ensure_dir(dest)?;
+
+    let mut written: u64 = 0;
+    for index in 0..zip.len() {
+        let mut entry = zip
+            .by_index(index)
+            .map_err(|e| format!("Failed to read archive entry #{index}: {e}"))?;
+        let rel = entry_rel_path(entry.name())?;
+        check_no_link_on_path(dest, &rel)?;
+        let out = dest.join(&rel);
+
+        if entry.is_dir() {
+            ensure_dir(&out)?;
+            continue;
+        }
+        if let Some(parent) = out.parent() {
+            ensure_dir(parent)?;
+        }
+
+        let remaining = limits.max_total_bytes - written;
+        let too_large = || {
+            format!(
+                "Archive expands beyond the limit of {} bytes",
+                limits.max_total_bytes
+            )
+        };
+        if entry.size() > remaining {
+            return Err(too_large());
+        }
+
+        if entry.is_symlink() {
+            let mut target = String::new();
+            (&mut entry)
+                .take(4096)
+                .read_to_string(&mut target)
+                .map_err(|e| format!("Failed to read symlink {}: {e}", rel.display()))?;
+            check_link_target(&rel, &target)?;
+            create_symlink(&target, &out)
+                .map_err(|e| format!("Failed to create symlink {}: {e}", out.display()))?;
+            continue;
+        }
+
+        let mut file = fs::File::create(&out)
+            .map_err(|e| format!("Failed to create {}: {e}", out.display()))?;
+        // Header sizes can lie, so count what actually comes out of the decoder.
+        let copied = io::copy(&mut (&mut entry).take(remaining + 1), &mut file)
+            .map_err(|e| format!("Failed to extract {}: {e}", rel.display()))?;
+        if copied > remaining {
+            return Err(too_large());
+        }
+        written += copied;
+        apply_mode(&out, entry.unix_mode())?;
+    }
+    Ok(())
+}
+
+/// Returns the only top-level directory of an extracted archive, or `dir`
+/// itself when there is not exactly one.
+pub fn unwrap_single_top_dir(dir: &Path) -> Result<PathBuf, String> {
+    let mut top_dirs: Vec<PathBuf> = vec![];
+    for entry in fs::read_dir(dir).map_err(|e| format!("Failed to read extract dir: {e}"))? {
+        let entry = entry.map_err(|e| format!("Failed to read entry: {e}"))?;
+        let p = entry.path();
+        if p.is_dir() {
+            top_dirs.push(p);
+        }
+    }
+
+    Ok(if top_dirs.len() == 1 {
+        top_dirs.remove(0)
+    } else {
+        dir.to_path_buf()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::generate_id;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    enum Entry<'a> {
+        File(&'a str, &'a [u8]),
+        Link(&'a str, &'a str),
+    }
+
+    fn temp_test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("skills-manager-{name}-{}", generate_id()));
+        ensure_dir(&dir).expect("create temp dir");
+        dir
+    }
+
+    fn write_zip(path: &Path, entries: &[Entry]) {
+        let mut zip = ZipWriter::new(fs::File::create(path).unwrap());
+        let options = SimpleFileOptions::default();
+        for entry in entries {
+            match entry {
+                Entry::File(name, bytes) => {
+                    zip.start_file(*name, options).unwrap();
+                    zip.write_all(bytes).unwrap();
+                }
+                Entry::Link(name, target) => zip.add_symlink(*name, *target, options).unwrap(),
+            }
+        }
+        zip.finish().unwrap();
+    }
+
+    fn extract(tmp: &Path, entries: &[Entry], limits: &ExtractLimits) -> Result<(), String> {
+        let archive = tmp.join(format!("{}.zip", generate_id()));
+        write_zip(&archive, entries);
+        extract_zip(&archive, &tmp.join("out"), limits)
+    }
+
+    #[test]
+    fn extract_zip_unpacks_and_unwraps_single_top_dir() {
+        let tmp = temp_test_dir("zip-ok");
+        let entries = [
+            Entry::File("repo-main/SKILL.md", b"# demo\n"),
+            Entry::File("repo-main/scripts/run.sh", b"echo hi\n"),
+            Entry::Link("repo-main/docs", "scripts"),
+        ];
+        extract(&tmp, &entries, &ExtractLimits::default()).unwrap();
+
+        let root = unwrap_single_top_dir(&tmp.join("out")).unwrap();
+        assert_eq!(root, tmp.join("out").join("repo-main"));
+        assert_eq!(
+            fs::read_to_string(root.join("SKILL.md")).unwrap(),
+            "# demo\n"
+        );
+        assert!(root.join("docs").join("run.sh").is_file());
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn extract_zip_rejects_malicious_archives() {
+        let tmp = temp_test_dir("zip-evil");
+        let limits = ExtractLimits::default();
+        let cases: Vec<(Vec<Entry>, &str)> = vec![
+            (vec![Entry::File("../evil.txt", b"x")], "escapes"),
+            (vec![Entry::File("a/../../evil.txt", b"x")], "escapes"),
+            (vec![Entry::File("/tmp/evil.txt", b"x")], "absolute"),
+            (vec![Entry::Link("a/link", "/etc")], "outside"),
+            (vec![Entry::Link("a/link", "../..")], "outside"),
+            (
+                vec![Entry::Link("up", "."), Entry::Link("a/l", "../up/..")],
+                "outside",
+            ),
+            (
+                vec![Entry::Link("a", "."), Entry::File("a/evil.txt", b"x")],
+                "through a symlink",
+            ),
+        ];
+        for (entries, expected) in cases {
+            let _ = fs::remove_dir_all(tmp.join("out"));
+            let err = extract(&tmp, &entries, &limits).unwrap_err();
+            assert!(err.contains(expected), "{err}");
+        }
+        assert!(!tmp.join("evil.txt").exists());
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn extract_zip_enforces_size_and_entry_limits() {
+        let tmp = temp_test_dir("zip-bomb");
+        let zeros = vec![0u8; 64 * 1024];
+        let limits = ExtractLimits {
+            max_total_bytes: 100 * 1024,
+            max_entries: 3,
+        };
+
+        let err = extract(
+            &tmp,
+            &[Entry::File("a", &zeros), Entry::File("b", &zeros)],
+            &limits,
+        )
+        .unwrap_err();
+        assert!(err.contains("beyond the limit"), "{err}");
+
+        let many = [
+            Entry::File("1", b""),
+            Entry::File("2", b""),
+            Entry::File("3", b""),
+            Entry::File("4", b""),
+        ];
+        let err = extract(&tmp, &many, &limits).unwrap_err();
+        assert!(err.contains("more than the limit"), "{err}");
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+}
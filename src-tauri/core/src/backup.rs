@@ -0,0 +1,501 @@
+use std::fs;
+use std::io::{Read, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+use crate::archive::{extract_zip, ExtractLimits};
+use crate::digest::{digest_dir, DigestEntry};
+use crate::journal::{Journal, JournalStep};
+use crate::manifest::{load_manifest, update_manifest};
+use crate::models::{AgentInfo, Skill};
+use crate::settings::{load_settings, save_settings, settings_path, StoreSettings};
+use crate::store::snapshot_skill;
+use crate::utils::{
+    copy_dir_all, ensure_dir, expand_tilde, generate_id, manager_store_root, now_iso,
+    safe_skill_dir_name, unique_skill_dir_name,
+};
+
+pub const BACKUP_VERSION: u32 = 1;
+const BACKUP_META_FILE: &str = "backup.json";
+const BACKUP_SKILLS_DIR: &str = "skills";
+
+/// A whole store may be far larger than a single downloaded skill.
+const BACKUP_LIMITS: ExtractLimits = ExtractLimits {
+    max_total_bytes: 4 * 1024 * 1024 * 1024,
+    max_entries: 200_000,
+};
+
+/// `backup.json` at the root of a backup archive. Skill directories sit next
+/// to it under `skills/<name>/`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupMeta {
+    pub version: u32,
+    pub created_at: String,
+    #[serde(default)]
+    pub skills: Vec<Skill>,
+    #[serde(default)]
+    pub agents: Vec<AgentInfo>,
+    #[serde(default)]
+    pub settings: StoreSettings,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportStrategy {
+    /// Keep the store copy and ignore the backup one.
+    Skip,
+    /// Replace the store copy, snapshotting it first.
+    Overwrite,
+    /// Import the backup copy under a new name next to the store copy.
+    KeepBoth,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupSummary {
+    pub skills: usize,
+    pub files: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupPreview {
+    pub created_at: String,
+    pub skills: Vec<String>,
+    /// Skills of the backup that already exist in the store.
+    pub conflicts: Vec<String>,
+    pub agents: Vec<AgentInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenamedSkill {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+    pub imported: Vec<String>,
+    pub overwritten: Vec<String>,
+    pub renamed: Vec<RenamedSkill>,
+    pub skipped: Vec<String>,
+    /// The agent configuration saved in the backup, for the caller to apply.
+    pub agents: Vec<AgentInfo>,
+}
+
+#[cfg(unix)]
+fn zip_options(mode: u32) -> SimpleFileOptions {
+    SimpleFileOptions::default().unix_permissions(mode)
+}
+
+#[cfg(not(unix))]
+fn zip_options(_mode: u32) -> SimpleFileOptions {
+    SimpleFileOptions::default()
+}
+
+fn write_backup(
+    path: &Path,
+    store_root: &Path,
+    meta: &BackupMeta,
+) -> Result<BackupSummary, String> {
+    let file =
+        fs::File::create(path).map_err(|e| format!("Failed to create {}: {e}", path.display()))?;
+    let mut zip = ZipWriter::new(file);
+    let zip_err = |e: zip::result::ZipError| format!("Failed to write {}: {e}", path.display());
+    let io_err = |e: std::io::Error| format!("Failed to write {}: {e}", path.display());
+
+    let text = serde_json::to_string_pretty(meta)
+        .map_err(|e| format!("Failed to serialize backup metadata: {e}"))?;
+    zip.start_file(BACKUP_META_FILE, zip_options(0o644))
+        .map_err(zip_err)?;
+    zip.write_all(text.as_bytes()).map_err(io_err)?;
+
+    let mut files = 0;
+    for skill in &meta.skills {
+        let dir = store_root.join(&skill.name);
+        let prefix = format!("{BACKUP_SKILLS_DIR}/{}", skill.name);
+        zip.add_directory(format!("{prefix}/"), zip_options(0o755))
+            .map_err(zip_err)?;
+        // Walk the digest so symlinks are followed the same way copies do.
+        for (rel, entry) in digest_dir(&dir)?.entries {
+            match entry {
+                DigestEntry::Dir => zip
+                    .add_directory(format!("{prefix}/{rel}/"), zip_options(0o755))
+                    .map_err(zip_err)?,
+                DigestEntry::File { mode, .. } => {
+                    let bytes = fs::read(dir.join(&rel))
+                        .map_err(|e| format!("Failed to read {}: {e}", dir.join(&rel).display()))?;
+                    zip.start_file(format!("{prefix}/{rel}"), zip_options(mode))
+                        .map_err(zip_err)?;
+                    zip.write_all(&bytes).map_err(io_err)?;
+                    files += 1;
+                }
+                DigestEntry::Link => {}
+            }
+        }
+    }
+    zip.finish().map_err(zip_err)?;
+    Ok(BackupSummary {
+        skills: meta.skills.len(),
+        files,
+    })
+}
+
+/// Writes every store skill directory, the skill records, `agents` and the
+/// store settings into a single zip archive at `path`.
+pub fn export_backup(
+    path: &Path,
+    agents: &[AgentInfo],
+    storage_path: &str,
+) -> Result<BackupSummary, String> {
+    let store_root = manager_store_root(storage_path)?;
+    let skills: Vec<Skill> = load_manifest(&store_root)?
+        .skills
+        .into_iter()
+        .filter(|skill| store_root.join(&skill.name).is_dir())
+        .collect();
+    let meta = BackupMeta {
+        version: BACKUP_VERSION,
+        created_at: now_iso(),
+        skills,
+        agents: agents.to_vec(),
+        settings: load_settings(&store_root)?,
+    };
+
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        ensure_dir(parent)?;
+    }
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let tmp = path.with_file_name(format!(".{file_name}.tmp-{}", generate_id()));
+    let summary = write_backup(&tmp, &store_root, &meta).and_then(|summary| {
+        fs::rename(&tmp, path).map_err(|e| format!("Failed to replace {}: {e}", path.display()))?;
+        Ok(summary)
+    });
+    if summary.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    summary
+}
+
+/// Reads `backup.json` without extracting anything else.
+fn read_backup_meta(path: &Path) -> Result<BackupMeta, String> {
+    let file =
+        fs::File::open(path).map_err(|e| format!("Failed to open {}: {e}", path.display()))?;
+    let mut zip = ZipArchive::new(file)
+        .map_err(|e| format!("Failed to read backup {}: {e}", path.display()))?;
+    let mut text = String::new();
+    zip.by_name(BACKUP_META_FILE)
+        .map_err(|e| format!("{} is not a skills backup: {e}", path.display()))?
+        .read_to_string(&mut text)
+        .map_err(|e| format!("Failed to read {BACKUP_META_FILE}: {e}"))?;
+    let meta: BackupMeta = serde_json::from_str(&text)
+        .map_err(|e| format!("Failed to parse {BACKUP_META_FILE}: {e}"))?;
+    if meta.version > BACKUP_VERSION {
+        return Err(format!(
+            "Backup {} has version {}, newer than the supported {BACKUP_VERSION}",
+            path.display(),
+            meta.version
+        ));
+    }
+    for skill in &meta.skills {
+        if safe_skill_dir_name(&skill.name) != skill.name || skill.name.starts_with('.') {
+            return Err(format!(
+                "Backup holds an invalid skill name: {}",
+                skill.name
+            ));
+        }
+    }
+    Ok(meta)
+}
+
+fn is_conflict(store_root: &Path, skill: &str, recorded: &[Skill]) -> bool {
+    store_root.join(skill).exists() || recorded.iter().any(|s| s.name == skill)
+}
+
+/// Lists what [`import_backup`] would restore and which skills already exist
+/// in the store, without writing anything.
+pub fn preview_backup(path: &Path, storage_path: &str) -> Result<BackupPreview, String> {
+    // Unlike `manager_store_root`, never creates the store.
+    let store_root = expand_tilde(storage_path.trim());
+    let meta = read_backup_meta(path)?;
+    let recorded = if store_root.is_dir() {
+        load_manifest(&store_root)?.skills
+    } else {
+        vec![]
+    };
+    let skills: Vec<String> = meta.skills.iter().map(|skill| skill.name.clone()).collect();
+    Ok(BackupPreview {
+        created_at: meta.created_at,
+        conflicts: skills
+            .iter()
+            .filter(|name| is_conflict(&store_root, name, &recorded))
+            .cloned()
+            .collect(),
+        skills,
+        agents: meta.agents,
+    })
+}
+
+fn move_into_store(journal: &mut Journal, src: &Path, dest: &Path) -> Result<(), String> {
+    journal.log(JournalStep::Create {
+        path: dest.to_path_buf(),
+    })?;
+    if fs::rename(src, dest).is_err() {
+        copy_dir_all(src, dest)?;
+    }
+    Ok(())
+}
+
+/// Restores the skills of the backup at `path` into the store, resolving
+/// skills that already exist with `strategy`.
+///
+/// Imported records keep their enabled agents but are not distributed; a
+/// following sync puts them in place. Store settings are restored only when
+/// the store has none yet.
+pub fn import_backup(
+    path: &Path,
+    strategy: ImportStrategy,
+    storage_path: &str,
+) -> Result<ImportReport, String> {
+    let meta = read_backup_meta(path)?;
+    let store_root = manager_store_root(storage_path)?;
+    ensure_dir(&store_root)?;
+    let mut journal = Journal::begin(&store_root, "import")?;
+    let staging = journal.temp_dir("import")?;
+    let report = (|| {
+        extract_zip(path, &staging, &BACKUP_LIMITS)?;
+        let extracted = staging.join(BACKUP_SKILLS_DIR);
+        if let Some(missing) = meta
+            .skills
+            .iter()
+            .find(|skill| !extracted.join(&skill.name).is_dir())
+        {
+            return Err(format!("Backup is missing the files of {}", missing.name));
+        }
+
+        let recorded = load_manifest(&store_root)?.skills;
+        let mut report = ImportReport {
+            agents: meta.agents.clone(),
+            ..ImportReport::default()
+        };
+        let mut records = vec![];
+        for skill in &meta.skills {
+            let src = extracted.join(&skill.name);
+            let dest = store_root.join(&skill.name);
+            let mut record = Skill {
+                last_sync_digest: None,
+                ..skill.clone()
+            };
+            if !is_conflict(&store_root, &skill.name, &recorded) {
+                move_into_store(&mut journal, &src, &dest)?;
+                report.imported.push(skill.name.clone());
+            } else {
+                match strategy {
+                    ImportStrategy::Skip => {
+                        report.skipped.push(skill.name.clone());
+                        continue;
+                    }
+                    ImportStrategy::Overwrite => {
+                        if dest.is_dir() {
+                            snapshot_skill(&store_root, &skill.name)?;
+                        }
+                        journal.move_aside(&dest, "import")?;
+                        move_into_store(&mut journal, &src, &dest)?;
+                        report.overwritten.push(skill.name.clone());
+                    }
+                    ImportStrategy::KeepBoth => {
+                        let name = unique_skill_dir_name(&store_root, &skill.name);
+                        move_into_store(&mut journal, &src, &store_root.join(&name))?;
+                        record.id = generate_id();
+                        record.name = name.clone();
+                        // The store copy keeps the agent directories.
+                        record.enabled_agents = vec![];
+                        report.renamed.push(RenamedSkill {
+                            from: skill.name.clone(),
+                            to: name,
+                        });
+                    }
+                }
+            }
+            records.push(record);
+        }
+
+        for record in &records {
+            journal.log(JournalStep::Record {
+                name: record.name.clone(),
+                before: recorded
+                    .iter()
+                    .find(|skill| skill.name == record.name)
+                    .cloned()
+                    .map(Box::new),
+            })?;
+        }
+        update_manifest(&store_root, |manifest| {
+            for record in records {
+                manifest.upsert(record);
+            }
+        })?;
+        if !settings_path(&store_root).exists() {
+            save_settings(&store_root, &meta.settings)?;
+        }
+        Ok(report)
+    })()?;
+    journal.commit()?;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{CopyStrategy, DistributionMode};
+    use std::path::PathBuf;
+
+    fn temp_test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("skills-manager-{name}-{}", generate_id()));
+        ensure_dir(&dir).expect("create temp dir");
+        dir
+    }
+
+    fn write_skill(store_root: &Path, name: &str, body: &str) {
+        let dir = store_root.join(name);
+        ensure_dir(&dir.join("scripts")).unwrap();
+        fs::write(dir.join("SKILL.md"), format!("# {name}\n{body}\n")).unwrap();
+        fs::write(dir.join("scripts").join("run.sh"), "echo hi\n").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let script = dir.join("scripts").join("run.sh");
+            fs::set_permissions(script, fs::Permissions::from_mode(0o755)).unwrap();
+        }
+    }
+
+    fn agent() -> AgentInfo {
+        AgentInfo {
+            id: "claude".to_string(),
+            name: "Claude".to_string(),
+            default_path: "~/.claude/skills".to_string(),
+            current_path: "/custom/claude/skills".to_string(),
+            enabled: true,
+            icon: "claude".to_string(),
+            distribution_mode: DistributionMode::Symlink,
+        }
+    }
+
+    fn export_sample(tmp: &Path) -> (PathBuf, PathBuf) {
+        let store = tmp.join("old");
+        write_skill(&store, "alpha", "from backup");
+        update_manifest(&store, |manifest| {
+            let alpha = manifest.get_mut("alpha").unwrap();
+            alpha.source_url = Some("https://github.com/foo/alpha".to_string());
+            alpha.enabled_agents = vec!["claude".to_string()];
+        })
+        .unwrap();
+        save_settings(
+            &store,
+            &StoreSettings {
+                copy_strategy: CopyStrategy::Hardlink,
+                sync_concurrency: None,
+            },
+        )
+        .unwrap();
+        let backup = tmp.join("out").join("backup.zip");
+        let summary = export_backup(&backup, &[agent()], &store.to_string_lossy()).unwrap();
+        assert_eq!(
+            summary,
+            BackupSummary {
+                skills: 1,
+                files: 2
+            }
+        );
+        (store, backup)
+    }
+
+    #[test]
+    fn backup_round_trips_skills_records_agents_and_settings() {
+        let tmp = temp_test_dir("backup-roundtrip");
+        let (old, backup) = export_sample(&tmp);
+        let new = tmp.join("new");
+        let new_path = new.to_string_lossy().to_string();
+
+        let preview = preview_backup(&backup, &new_path).unwrap();
+        assert_eq!(preview.skills, vec!["alpha".to_string()]);
+        assert!(preview.conflicts.is_empty());
+        assert!(!new.exists());
+
+        let report = import_backup(&backup, ImportStrategy::Skip, &new_path).unwrap();
+        assert_eq!(report.imported, vec!["alpha".to_string()]);
+        assert_eq!(report.agents[0].current_path, "/custom/claude/skills");
+        assert_eq!(
+            digest_dir(&new.join("alpha")).unwrap(),
+            digest_dir(&old.join("alpha")).unwrap()
+        );
+        let alpha = load_manifest(&new).unwrap().get("alpha").cloned().unwrap();
+        assert_eq!(
+            alpha.source_url.as_deref(),
+            Some("https://github.com/foo/alpha")
+        );
+        assert_eq!(alpha.enabled_agents, vec!["claude".to_string()]);
+        assert_eq!(
+            load_settings(&new).unwrap().copy_strategy,
+            CopyStrategy::Hardlink
+        );
+        assert!(!fs::read_dir(&new).unwrap().any(|e| e
+            .unwrap()
+            .file_name()
+            .to_string_lossy()
+            .starts_with(".tmp-")));
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn import_backup_resolves_conflicts_by_strategy() {
+        let tmp = temp_test_dir("backup-conflicts");
+        let (_, backup) = export_sample(&tmp);
+        let store = tmp.join("store");
+        let store_path = store.to_string_lossy().to_string();
+        write_skill(&store, "alpha", "local edit");
+        let read_alpha =
+            |name: &str| fs::read_to_string(store.join(name).join("SKILL.md")).unwrap();
+
+        let preview = preview_backup(&backup, &store_path).unwrap();
+        assert_eq!(preview.conflicts, vec!["alpha".to_string()]);
+
+        let report = import_backup(&backup, ImportStrategy::Skip, &store_path).unwrap();
+        assert_eq!(report.skipped, vec!["alpha".to_string()]);
+        assert!(read_alpha("alpha").contains("local edit"));
+
+        let report = import_backup(&backup, ImportStrategy::KeepBoth, &store_path).unwrap();
+        assert_eq!(
+            report.renamed,
+            vec![RenamedSkill {
+                from: "alpha".to_string(),
+                to: "alpha-2".to_string()
+            }]
+        );
+        assert!(read_alpha("alpha").contains("local edit"));
+        assert!(read_alpha("alpha-2").contains("from backup"));
+        let manifest = load_manifest(&store).unwrap();
+        assert!(manifest.get("alpha-2").unwrap().enabled_agents.is_empty());
+
+        let report = import_backup(&backup, ImportStrategy::Overwrite, &store_path).unwrap();
+        assert_eq!(report.overwritten, vec!["alpha".to_string()]);
+        assert!(read_alpha("alpha").contains("from backup"));
+        assert!(store
+            .join(".skillsm")
+            .join("snapshots")
+            .join("alpha")
+            .is_dir());
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+}
@@ -0,0 +1,311 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use similar::TextDiff;
+
+use crate::digest::{digest_dir, DigestEntry};
+use crate::models::AgentInfo;
+use crate::utils::{agent_roots, manager_store_root, safe_skill_dir_name};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileChange {
+    Added,
+    Removed,
+    Modified,
+}
+
+/// Size and hash of both sides of a file that is not UTF-8 text.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BinarySummary {
+    pub old_size: Option<u64>,
+    pub new_size: Option<u64>,
+    pub old_sha256: Option<String>,
+    pub new_sha256: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileDiff {
+    pub path: String,
+    pub change: FileChange,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unified: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub binary: Option<BinarySummary>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirDiff {
+    pub old_path: String,
+    pub new_path: String,
+    pub files: Vec<FileDiff>,
+}
+
+impl DirDiff {
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
+/// What a store skill is compared with in [`diff_skill`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "id", rename_all = "camelCase")]
+pub enum DiffAgainst {
+    /// The copy in the first root of this agent that holds the skill.
+    Agent(String),
+    /// Another skill in the same store.
+    Skill(String),
+}
+
+fn read_text(bytes: &[u8]) -> Option<&str> {
+    if bytes.contains(&0) {
+        return None;
+    }
+    std::str::from_utf8(bytes).ok()
+}
+
+fn file_diff(
+    old_dir: &Path,
+    new_dir: &Path,
+    rel: &str,
+    change: FileChange,
+    old_sha256: Option<&String>,
+    new_sha256: Option<&String>,
+) -> Result<FileDiff, String> {
+    let read = |dir: &Path, present: bool| -> Result<Option<Vec<u8>>, String> {
+        if !present {
+            return Ok(None);
+        }
+        let path = dir.join(rel);
+        fs::read(&path)
+            .map(Some)
+            .map_err(|e| format!("Failed to read {}: {e}", path.display()))
+    };
+    let old = read(old_dir, old_sha256.is_some())?;
+    let new = read(new_dir, new_sha256.is_some())?;
+
+    let old_text = old.as_deref().map(read_text);
+    let new_text = new.as_deref().map(read_text);
+    let is_text = !matches!(old_text, Some(None)) && !matches!(new_text, Some(None));
+
+    if is_text {
+        let old_text = old_text.flatten().unwrap_or_default();
+        let new_text = new_text.flatten().unwrap_or_default();
+        let unified = TextDiff::from_lines(old_text, new_text)
+            .unified_diff()
+            .context_radius(3)
+            .header(&format!("a/{rel}"), &format!("b/{rel}"))
+            .to_string();
+        return Ok(FileDiff {
+            path: rel.to_string(),
+            change,
+            unified: Some(unified),
+            binary: None,
+        });
+    }
+
+    Ok(FileDiff {
+        path: rel.to_string(),
+        change,
+        unified: None,
+        binary: Some(BinarySummary {
+            old_size: old.as_ref().map(|b| b.len() as u64),
+            new_size: new.as_ref().map(|b| b.len() as u64),
+            old_sha256: old_sha256.cloned(),
+            new_sha256: new_sha256.cloned(),
+        }),
+    })
+}
+
+/// Compares two skill directories file by file. Symlinks are followed, and
+/// only content differences are reported.
+pub fn diff_dirs(old_dir: &Path, new_dir: &Path) -> Result<DirDiff, String> {
+    let old = digest_dir(old_dir)?;
+    let new = digest_dir(new_dir)?;
+
+    let sha = |entry: Option<&DigestEntry>| match entry {
+        Some(DigestEntry::File { sha256, .. }) => Some(sha256.clone()),
+        _ => None,
+    };
+
+    let mut paths: Vec<&String> = old.entries.keys().chain(new.entries.keys()).collect();
+    paths.sort();
+    paths.dedup();
+
+    let mut files = vec![];
+    for rel in paths {
+        let old_sha = sha(old.entries.get(rel));
+        let new_sha = sha(new.entries.get(rel));
+        let change = match (&old_sha, &new_sha) {
+            (None, None) => continue,
+            (Some(a), Some(b)) if a == b => continue,
+            (Some(_), Some(_)) => FileChange::Modified,
+            (None, Some(_)) => FileChange::Added,
+            (Some(_), None) => FileChange::Removed,
+        };
+        files.push(file_diff(
+            old_dir,
+            new_dir,
+            rel,
+            change,
+            old_sha.as_ref(),
+            new_sha.as_ref(),
+        )?);
+    }
+
+    Ok(DirDiff {
+        old_path: old_dir.to_string_lossy().to_string(),
+        new_path: new_dir.to_string_lossy().to_string(),
+        files,
+    })
+}
+
+fn resolve_against(
+    store_root: &Path,
+    skill_name: &str,
+    against: &DiffAgainst,
+    agents: &[AgentInfo],
+) -> Result<PathBuf, String> {
+    match against {
+        DiffAgainst::Skill(other) => {
+            let dir = store_root.join(safe_skill_dir_name(other));
+            if !dir.is_dir() {
+                return Err(format!("Skill not found in store: {other}"));
+            }
+            Ok(dir)
+        }
+        DiffAgainst::Agent(agent_id) => {
+            let agent = agents
+                .iter()
+                .find(|agent| &agent.id == agent_id)
+                .ok_or_else(|| format!("Unknown agent: {agent_id}"))?;
+            agent_roots(agent)
+                .into_iter()
+                .map(|root| root.join(safe_skill_dir_name(skill_name)))
+                .find(|dir| dir.is_dir())
+                .ok_or_else(|| format!("Agent {agent_id} has no copy of {skill_name}"))
+        }
+    }
+}
+
+/// Diffs the store copy of `skill_name` (old side) against an agent copy or
+/// another store skill (new side).
+pub fn diff_skill(
+    skill_name: &str,
+    against: &DiffAgainst,
+    agents: &[AgentInfo],
+    storage_path: &str,
+) -> Result<DirDiff, String> {
+    let store_root = manager_store_root(storage_path)?;
+    let store_dir = store_root.join(safe_skill_dir_name(skill_name));
+    if !store_dir.is_dir() {
+        return Err(format!("Skill not found in store: {skill_name}"));
+    }
+    let other = resolve_against(&store_root, skill_name, against, agents)?;
+    diff_dirs(&store_dir, &other)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::DistributionMode;
+    use crate::utils::{ensure_dir, generate_id};
+
+    fn temp_test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("skills-manager-{name}-{}", generate_id()));
+        ensure_dir(&dir).expect("create temp dir");
+        dir
+    }
+
+    #[test]
+    fn diff_dirs_reports_text_and_binary_changes() {
+        let tmp = temp_test_dir("diff-dirs");
+        let old = tmp.join("old");
+        let new = tmp.join("new");
+        ensure_dir(&old.join("assets")).unwrap();
+        ensure_dir(&new.join("assets")).unwrap();
+        fs::write(old.join("SKILL.md"), "# foo\nkeep\nold line\n").unwrap();
+        fs::write(new.join("SKILL.md"), "# foo\nkeep\nnew line\n").unwrap();
+        fs::write(old.join("same.md"), "same\n").unwrap();
+        fs::write(new.join("same.md"), "same\n").unwrap();
+        fs::write(old.join("gone.md"), "bye\n").unwrap();
+        fs::write(new.join("added.md"), "hi\n").unwrap();
+        fs::write(old.join("assets").join("logo.bin"), [0u8, 1, 2]).unwrap();
+        fs::write(new.join("assets").join("logo.bin"), [0u8, 1, 2, 3]).unwrap();
+
+        let diff = diff_dirs(&old, &new).unwrap();
+        let summary: Vec<(&str, FileChange)> = diff
+            .files
+            .iter()
+            .map(|f| (f.path.as_str(), f.change))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("SKILL.md", FileChange::Modified),
+                ("added.md", FileChange::Added),
+                ("assets/logo.bin", FileChange::Modified),
+                ("gone.md", FileChange::Removed),
+            ]
+        );
+
+        let skill_md = diff.files[0].unified.as_deref().unwrap();
+        assert!(skill_md.contains("--- a/SKILL.md"));
+        assert!(skill_md.contains("-old line\n+new line\n"));
+        assert!(diff.files[1].unified.as_deref().unwrap().contains("+hi\n"));
+
+        let binary = diff.files[2].binary.as_ref().unwrap();
+        assert!(diff.files[2].unified.is_none());
+        assert_eq!((binary.old_size, binary.new_size), (Some(3), Some(4)));
+        assert_ne!(binary.old_sha256, binary.new_sha256);
+
+        assert!(diff_dirs(&old, &old).unwrap().is_empty());
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn diff_skill_resolves_agent_copies_and_store_skills() {
+        let tmp = temp_test_dir("diff-skill");
+        let store = tmp.join("store");
+        let agent_root = tmp.join("agent");
+        for dir in [store.join("foo"), store.join("bar"), agent_root.join("foo")] {
+            ensure_dir(&dir).unwrap();
+            fs::write(dir.join("SKILL.md"), "# foo\n").unwrap();
+        }
+        fs::write(agent_root.join("foo").join("SKILL.md"), "# foo\nedited\n").unwrap();
+        let agents = vec![AgentInfo {
+            id: "claude".to_string(),
+            name: "Claude".to_string(),
+            default_path: agent_root.to_string_lossy().to_string(),
+            current_path: agent_root.to_string_lossy().to_string(),
+            enabled: true,
+            icon: "test".to_string(),
+            distribution_mode: DistributionMode::Copy,
+        }];
+        let storage_path = store.to_string_lossy().to_string();
+
+        let against_agent = DiffAgainst::Agent("claude".to_string());
+        let diff = diff_skill("foo", &against_agent, &agents, &storage_path).unwrap();
+        assert_eq!(diff.files.len(), 1);
+        assert!(diff.files[0]
+            .unified
+            .as_deref()
+            .unwrap()
+            .contains("+edited"));
+
+        let against_skill = DiffAgainst::Skill("bar".to_string());
+        assert!(diff_skill("foo", &against_skill, &agents, &storage_path)
+            .unwrap()
+            .is_empty());
+
+        let err = diff_skill("bar", &against_agent, &agents, &storage_path).unwrap_err();
+        assert!(err.contains("no copy of bar"));
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+}
@@ -0,0 +1,210 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+/// One entry of a digested tree, keyed by its `/`-separated path relative to the root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DigestEntry {
+    Dir,
+    File {
+        mode: u32,
+        sha256: String,
+    },
+    /// A symlink that was not followed. Never equal to anything in a followed tree.
+    Link,
+}
+
+/// Merkle digest of a directory: `root` changes whenever any relative path,
+/// file mode or file content below it changes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirDigest {
+    pub root: String,
+    pub entries: BTreeMap<String, DigestEntry>,
+}
+
+impl DirDigest {
+    /// Digest of the relative paths, file contents and executable bits only.
+    /// Unlike `root` it does not depend on the umask or the platform a tree was
+    /// written on.
+    pub fn content_root(&self) -> String {
+        let mut hasher = Sha256::new();
+        for (rel, entry) in &self.entries {
+            let (kind, sha256) = match entry {
+                DigestEntry::Dir => ("D", ""),
+                DigestEntry::File { mode, sha256 } if mode & 0o111 != 0 => ("X", sha256.as_str()),
+                DigestEntry::File { sha256, .. } => ("F", sha256.as_str()),
+                DigestEntry::Link => ("L", ""),
+            };
+            hasher.update(kind.as_bytes());
+            hasher.update(rel.as_bytes());
+            hasher.update([0]);
+            hasher.update(sha256.as_bytes());
+            hasher.update([b'\n']);
+        }
+        format!("{:x}", hasher.finalize())
+    }
+}
+
+/// Digests `dir`, dereferencing symlinks the same way `copy_dir_all` does.
+pub fn digest_dir(dir: &Path) -> Result<DirDigest, String> {
+    digest_dir_with(dir, true, &BTreeMap::new())
+}
+
+/// Digests `dir` as if every file in `overrides` held the given bytes instead.
+///
+/// With `follow_links` unset, symlinks are recorded as [`DigestEntry::Link`]
+/// so a distributed copy containing links never matches its source.
+pub fn digest_dir_with(
+    dir: &Path,
+    follow_links: bool,
+    overrides: &BTreeMap<String, Vec<u8>>,
+) -> Result<DirDigest, String> {
+    let mut entries = BTreeMap::new();
+    let mut stack = HashSet::new();
+    let root = digest_node(dir, "", follow_links, overrides, &mut stack, &mut entries)?;
+    Ok(DirDigest { root, entries })
+}
+
+fn join_rel(parent: &str, name: &str) -> String {
+    if parent.is_empty() {
+        name.to_string()
+    } else {
+        format!("{parent}/{name}")
+    }
+}
+
+fn hex_sha256(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+#[cfg(unix)]
+fn file_mode(meta: &fs::Metadata) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    meta.permissions().mode() & 0o777
+}
+
+#[cfg(not(unix))]
+fn file_mode(meta: &fs::Metadata) -> u32 {
+    if meta.permissions().readonly() {
+        0o444
+    } else {
+        0o644
+    }
+}
+
+fn digest_node(
+    dir: &Path,
+    rel: &str,
+    follow_links: bool,
+    overrides: &BTreeMap<String, Vec<u8>>,
+    stack: &mut HashSet<PathBuf>,
+    entries: &mut BTreeMap<String, DigestEntry>,
+) -> Result<String, String> {
+    let canon = fs::canonicalize(dir)
+        .map_err(|e| format!("Failed to canonicalize {}: {e}", dir.display()))?;
+    if !stack.insert(canon.clone()) {
+        return Err(format!("Symlink cycle detected at {}", dir.display()));
+    }
+
+    let mut children: Vec<(String, PathBuf)> = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read dir {}: {e}", dir.display()))?
+        .map(|entry| {
+            entry
+                .map(|e| (e.file_name().to_string_lossy().to_string(), e.path()))
+                .map_err(|e| format!("Failed to read entry: {e}"))
+        })
+        .collect::<Result<_, _>>()?;
+    children.sort();
+
+    let mut hasher = Sha256::new();
+    for (name, path) in children {
+        let child_rel = join_rel(rel, &name);
+        let link_meta = fs::symlink_metadata(&path)
+            .map_err(|e| format!("Failed to stat {}: {e}", path.display()))?;
+
+        let (kind, hash) = if link_meta.file_type().is_symlink() && !follow_links {
+            let target = fs::read_link(&path).unwrap_or_default();
+            entries.insert(child_rel, DigestEntry::Link);
+            ("L", hex_sha256(target.to_string_lossy().as_bytes()))
+        } else {
+            let meta = fs::metadata(&path)
+                .map_err(|e| format!("Failed to stat {}: {e}", path.display()))?;
+            if meta.is_dir() {
+                entries.insert(child_rel.clone(), DigestEntry::Dir);
+                let hash = digest_node(&path, &child_rel, follow_links, overrides, stack, entries)?;
+                ("D", hash)
+            } else if meta.is_file() {
+                let sha256 = match overrides.get(&child_rel) {
+                    Some(bytes) => hex_sha256(bytes),
+                    None => {
+                        let bytes = fs::read(&path)
+                            .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+                        hex_sha256(&bytes)
+                    }
+                };
+                let mode = file_mode(&meta);
+                let hash = hex_sha256(format!("{mode:o}:{sha256}").as_bytes());
+                entries.insert(child_rel, DigestEntry::File { mode, sha256 });
+                ("F", hash)
+            } else {
+                continue;
+            }
+        };
+
+        hasher.update(kind.as_bytes());
+        hasher.update(name.as_bytes());
+        hasher.update([0]);
+        hasher.update(hash.as_bytes());
+        hasher.update([b'\n']);
+    }
+
+    stack.remove(&canon);
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{ensure_dir, generate_id};
+
+    #[test]
+    fn digest_tracks_paths_and_contents() {
+        let root = std::env::temp_dir().join(format!("skills-manager-digest-{}", generate_id()));
+        let a = root.join("a");
+        let b = root.join("b");
+        for dir in [&a, &b] {
+            ensure_dir(&dir.join("nested")).unwrap();
+            fs::write(dir.join("SKILL.md"), "# skill\n").unwrap();
+            fs::write(dir.join("nested").join("x.txt"), "x\n").unwrap();
+        }
+
+        let da = digest_dir(&a).unwrap();
+        assert_eq!(da, digest_dir(&b).unwrap());
+        assert_eq!(da.entries.get("nested"), Some(&DigestEntry::Dir));
+        assert!(matches!(
+            da.entries.get("nested/x.txt"),
+            Some(DigestEntry::File { .. })
+        ));
+
+        fs::write(b.join("nested").join("x.txt"), "y\n").unwrap();
+        assert_ne!(da.root, digest_dir(&b).unwrap().root);
+
+        fs::write(b.join("nested").join("x.txt"), "x\n").unwrap();
+        fs::rename(
+            b.join("nested").join("x.txt"),
+            b.join("nested").join("z.txt"),
+        )
+        .unwrap();
+        assert_ne!(da.root, digest_dir(&b).unwrap().root);
+
+        let overrides = BTreeMap::from([("SKILL.md".to_string(), b"---\n".to_vec())]);
+        let with = digest_dir_with(&a, true, &overrides).unwrap();
+        assert_ne!(da.root, with.root);
+        fs::write(a.join("SKILL.md"), "---\n").unwrap();
+        assert_eq!(digest_dir(&a).unwrap().root, with.root);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
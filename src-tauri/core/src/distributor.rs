@@ -0,0 +1,1048 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use serde::Serialize;
+
+use crate::digest::digest_dir;
+use crate::journal::{DistributeTarget, Journal, JournalStep};
+use crate::lint::{blocking_rules, lint_dir};
+use crate::manifest::update_manifest;
+use crate::models::{
+    AgentInfo, CopyStrategy, DistributionMode, Skill, SyncAllSkillsDistributionProgressLog,
+};
+use crate::progress::ProgressReporter;
+use crate::settings::{load_settings, StoreSettings};
+use crate::skill_md::{find_skill_md_path, with_yaml_frontmatter};
+use crate::utils::{
+    agent_roots, ensure_dir, manager_store_root, now_iso, remove_dir_if_exists, replace_atomically,
+    safe_skill_dir_name, symlink_dir, sync_dir_all_with, CopyReport, TargetSync,
+};
+
+/// An enabled agent that was skipped because the skill breaks rules it enforces.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockedTarget {
+    pub skill: String,
+    pub agent_id: String,
+    pub rules: Vec<String>,
+}
+
+/// Outcome of a distribution run, counted per agent root.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DistributionReport {
+    pub unchanged: usize,
+    pub updated: usize,
+    pub created: usize,
+    pub files: CopyReport,
+    pub blocked: Vec<BlockedTarget>,
+}
+
+impl DistributionReport {
+    fn record(&mut self, outcome: TargetSync) {
+        match outcome {
+            TargetSync::Unchanged => self.unchanged += 1,
+            TargetSync::Updated => self.updated += 1,
+            TargetSync::Created => self.created += 1,
+        }
+    }
+}
+
+/// Files an agent needs to differ from the store copy, keyed by relative path.
+/// Codex only loads a SKILL.md that starts with YAML frontmatter.
+pub(crate) fn skill_md_overrides(
+    agent: &AgentInfo,
+    src: &Path,
+    skill_name: &str,
+) -> BTreeMap<String, Vec<u8>> {
+    let mut overrides = BTreeMap::new();
+    if agent.id != "codex" {
+        return overrides;
+    }
+    let Some(path) = find_skill_md_path(src) else {
+        return overrides;
+    };
+    let rewritten = fs::read_to_string(&path)
+        .ok()
+        .and_then(|content| with_yaml_frontmatter(&content, skill_name));
+    if let (Some(rewritten), Some(file_name)) = (rewritten, path.file_name()) {
+        overrides.insert(file_name.to_string_lossy().to_string(), rewritten.into_bytes());
+    }
+    overrides
+}
+
+fn link_skill_dir(src: &Path, dst: &Path) -> Result<TargetSync, String> {
+    let target = fs::canonicalize(src).unwrap_or_else(|_| src.to_path_buf());
+    if fs::read_link(dst).is_ok_and(|current| current == target) {
+        return Ok(TargetSync::Unchanged);
+    }
+
+    let existed = fs::symlink_metadata(dst).is_ok();
+    replace_atomically(dst, |staged| {
+        symlink_dir(&target, staged).map_err(|e| {
+            format!(
+                "Failed to link {} -> {}: {e}",
+                dst.display(),
+                target.display()
+            )
+        })
+    })?;
+    Ok(if existed {
+        TargetSync::Updated
+    } else {
+        TargetSync::Created
+    })
+}
+
+/// One agent root a skill is distributed to or removed from.
+struct Target {
+    skill: usize,
+    src: PathBuf,
+    dst: PathBuf,
+    kind: TargetKind,
+}
+
+#[derive(Clone)]
+enum TargetKind {
+    Remove,
+    Distribute {
+        link: bool,
+        overrides: BTreeMap<String, Vec<u8>>,
+    },
+}
+
+type TargetResult = Result<Option<(TargetSync, CopyReport)>, String>;
+
+/// The agent roots skill number `skill` is distributed to or removed from, in
+/// agent order, and the enabled agents it is blocked for.
+fn skill_targets(
+    store_root: &Path,
+    skill: usize,
+    skill_name: &str,
+    enabled: &[String],
+    agents: &[AgentInfo],
+) -> Result<(Vec<Target>, Vec<BlockedTarget>), String> {
+    let src = store_root.join(safe_skill_dir_name(skill_name));
+    if !src.exists() {
+        return Err(format!(
+            "Skill store not found for name={skill_name} at {}",
+            src.display(),
+        ));
+    }
+
+    let lint = lint_dir(&src, false)?;
+    let mut targets = vec![];
+    let mut blocked = vec![];
+    for agent in agents {
+        if !agent.enabled {
+            continue;
+        }
+        let kind = if enabled.iter().any(|a| a == &agent.id) {
+            // Leave whatever the agent has now rather than ship a copy it would reject.
+            let rules = blocking_rules(&lint, &agent.id);
+            if !rules.is_empty() {
+                blocked.push(BlockedTarget {
+                    skill: skill_name.to_string(),
+                    agent_id: agent.id.clone(),
+                    rules,
+                });
+                continue;
+            }
+            let overrides = skill_md_overrides(agent, &src, skill_name);
+            // A link always shows the store content verbatim, so agents that need
+            // a rewritten SKILL.md get a real copy instead.
+            let link = agent.distribution_mode == DistributionMode::Symlink && overrides.is_empty();
+            TargetKind::Distribute { link, overrides }
+        } else {
+            TargetKind::Remove
+        };
+        for root in agent_roots(agent) {
+            targets.push(Target {
+                skill,
+                src: src.clone(),
+                dst: root.join(safe_skill_dir_name(skill_name)),
+                kind: kind.clone(),
+            });
+        }
+    }
+
+    Ok((targets, blocked))
+}
+
+fn run_target(target: &Target, strategy: CopyStrategy) -> TargetResult {
+    let TargetKind::Distribute { link, overrides } = &target.kind else {
+        let _ = remove_dir_if_exists(&target.dst);
+        return Ok(None);
+    };
+    if let Some(root) = target.dst.parent() {
+        ensure_dir(root)?;
+    }
+    // Platforms without symlink permission (e.g. Windows without developer mode)
+    // fall back to a copy.
+    if *link {
+        if let Ok(outcome) = link_skill_dir(&target.src, &target.dst) {
+            return Ok(Some((outcome, CopyReport::default())));
+        }
+    }
+    sync_dir_all_with(&target.src, &target.dst, strategy, overrides).map(Some)
+}
+
+/// Runs every target on up to `workers` threads, whether or not others fail,
+/// and returns their results by index. Targets sharing a destination run one
+/// after another in their original order. `on_done` is called after each
+/// target with whether it succeeded.
+fn run_targets(
+    targets: &[Target],
+    workers: usize,
+    strategy: CopyStrategy,
+    on_done: &(dyn Fn(&Target, bool) + Sync),
+) -> Vec<TargetResult> {
+    let mut jobs: Vec<Vec<usize>> = vec![];
+    let mut job_by_dst: HashMap<&Path, usize> = HashMap::new();
+    for (idx, target) in targets.iter().enumerate() {
+        match job_by_dst.get(target.dst.as_path()) {
+            Some(&job) => jobs[job].push(idx),
+            None => {
+                job_by_dst.insert(&target.dst, jobs.len());
+                jobs.push(vec![idx]);
+            }
+        }
+    }
+
+    let results: Vec<Mutex<Option<TargetResult>>> =
+        targets.iter().map(|_| Mutex::new(None)).collect();
+    let next_job = AtomicUsize::new(0);
+    let worker = || {
+        while let Some(job) = jobs.get(next_job.fetch_add(1, Ordering::SeqCst)) {
+            for &idx in job {
+                let result = run_target(&targets[idx], strategy);
+                let ok = result.is_ok();
+                *results[idx].lock().unwrap_or_else(|e| e.into_inner()) = Some(result);
+                on_done(&targets[idx], ok);
+            }
+        }
+    };
+    thread::scope(|scope| {
+        for _ in 1..workers.min(jobs.len()) {
+            scope.spawn(worker);
+        }
+        worker();
+    });
+
+    results
+        .into_iter()
+        .map(|slot| {
+            slot.into_inner()
+                .unwrap_or_else(|e| e.into_inner())
+                .unwrap_or_else(|| Err("Distribution target was never run".to_string()))
+        })
+        .collect()
+}
+
+/// Merges target results in target order into `report`, and records the
+/// first error of each skill in `errors`.
+fn collect_results(
+    targets: &[Target],
+    results: Vec<TargetResult>,
+    report: &mut DistributionReport,
+    errors: &mut [Option<String>],
+) {
+    for (target, result) in targets.iter().zip(results) {
+        match result {
+            Ok(Some((outcome, files))) => {
+                report.record(outcome);
+                report.files.merge(files);
+            }
+            Ok(None) => {}
+            Err(err) => {
+                errors[target.skill].get_or_insert(err);
+            }
+        }
+    }
+}
+
+fn distribute_skill(
+    store_root: &Path,
+    skill_name: &str,
+    enabled: &[String],
+    agents: &[AgentInfo],
+    settings: &StoreSettings,
+) -> Result<DistributionReport, String> {
+    let (targets, blocked) = skill_targets(store_root, 0, skill_name, enabled, agents)?;
+    let results = run_targets(
+        &targets,
+        settings.sync_workers(),
+        settings.copy_strategy,
+        &|_, _| {},
+    );
+    let mut report = DistributionReport {
+        blocked,
+        ..DistributionReport::default()
+    };
+    let mut errors = [None];
+    collect_results(&targets, results, &mut report, &mut errors);
+    match errors {
+        [Some(err)] => Err(err),
+        _ => Ok(report),
+    }
+}
+
+/// Stores the enabled agents and the distributed store digest of each synced
+/// skill in the store manifest.
+fn record_distribution(store_root: &Path, synced: &[(&str, &[String])]) -> Result<(), String> {
+    if synced.is_empty() {
+        return Ok(());
+    }
+
+    let digests: Vec<Option<String>> = synced
+        .iter()
+        .map(|(name, _)| {
+            digest_dir(&store_root.join(safe_skill_dir_name(name)))
+                .ok()
+                .map(|digest| digest.content_root())
+        })
+        .collect();
+    let now = now_iso();
+    update_manifest(store_root, |manifest| {
+        for ((name, enabled), digest) in synced.iter().zip(digests) {
+            if let Some(skill) = manifest.get_mut(name) {
+                skill.enabled_agents = enabled.to_vec();
+                skill.last_sync = Some(now.clone());
+                skill.last_sync_digest = digest;
+            }
+        }
+    })
+}
+
+pub fn sync_one_skill(
+    store_root: &Path,
+    skill_name: &str,
+    enabled: &[String],
+    agents: &[AgentInfo],
+) -> Result<DistributionReport, String> {
+    let settings = load_settings(store_root)?;
+    let report = distribute_skill(store_root, skill_name, enabled, agents, &settings)?;
+    record_distribution(store_root, &[(skill_name, enabled)])?;
+    Ok(report)
+}
+
+fn skill_progress(
+    skill: &Skill,
+    status: &str,
+    progress: f64,
+) -> SyncAllSkillsDistributionProgressLog {
+    let label = if status == "error" {
+        format!("技能分发失败: {}", skill.name)
+    } else {
+        format!("正在分发技能: {}", skill.name)
+    };
+    SyncAllSkillsDistributionProgressLog {
+        id: format!("sync-{}", skill.id),
+        label,
+        status: status.to_string(),
+        progress,
+    }
+}
+
+/// Reports skills as finished strictly in order, whichever worker finished
+/// their targets first, so the progress logs match a sequential run.
+struct SkillCursor {
+    remaining: Vec<usize>,
+    failed: Vec<bool>,
+    next: usize,
+}
+
+impl SkillCursor {
+    /// Reports every finished skill at the head of the order as done or
+    /// failed and starts the next one.
+    fn advance(
+        &mut self,
+        skills: &[Skill],
+        progress: &dyn ProgressReporter<SyncAllSkillsDistributionProgressLog>,
+    ) {
+        let total = skills.len().max(1) as f64;
+        while self.remaining.get(self.next) == Some(&0) {
+            let done_progress = ((self.next + 1) as f64 / total) * 100.0;
+            let status = if self.failed[self.next] { "error" } else { "success" };
+            progress.report(skill_progress(&skills[self.next], status, done_progress));
+            self.next += 1;
+            if let Some(skill) = skills.get(self.next) {
+                progress.report(skill_progress(skill, "loading", done_progress));
+            }
+        }
+    }
+}
+
+/// Distributes `skills` to their enabled agents, running up to
+/// [`StoreSettings::sync_workers`] agent roots at once.
+///
+/// A skill that fails does not stop the others. The report, the first error
+/// returned, the skills recorded in the manifest and the order of the
+/// progress logs do not depend on how the work was scheduled.
+pub fn sync_all_skills_distribution(
+    skills: &[Skill],
+    agents: &[AgentInfo],
+    storage_path: &str,
+    progress: &dyn ProgressReporter<SyncAllSkillsDistributionProgressLog>,
+) -> Result<DistributionReport, String> {
+    let store_root = manager_store_root(storage_path)?;
+    let settings = load_settings(&store_root)?;
+    // A failed target does not stop the others, so a sync that returns an
+    // error has still brought every other target up to date, and a failed
+    // target keeps its previous copy. A crash part-way is completed by
+    // `recover_store`.
+    let mut journal = Journal::begin(&store_root, "sync")?;
+    journal.log(JournalStep::Distribute {
+        skills: skills
+            .iter()
+            .map(|skill| DistributeTarget {
+                name: skill.name.clone(),
+                enabled_agents: skill.enabled_agents.clone(),
+            })
+            .collect(),
+        agents: agents.to_vec(),
+    })?;
+
+    let mut report = DistributionReport::default();
+    let mut targets = vec![];
+    let mut remaining = vec![0; skills.len()];
+    let mut errors: Vec<Option<String>> = vec![None; skills.len()];
+    for (idx, skill) in skills.iter().enumerate() {
+        match skill_targets(&store_root, idx, &skill.name, &skill.enabled_agents, agents) {
+            Ok((skill_targets, skill_blocked)) => {
+                remaining[idx] = skill_targets.len();
+                targets.extend(skill_targets);
+                report.blocked.extend(skill_blocked);
+            }
+            Err(err) => errors[idx] = Some(err),
+        }
+    }
+
+    let failed = errors.iter().map(Option::is_some).collect();
+    let mut cursor = SkillCursor {
+        remaining,
+        failed,
+        next: 0,
+    };
+    if let Some(skill) = skills.first() {
+        progress.report(skill_progress(skill, "loading", 0.0));
+    }
+    cursor.advance(skills, progress);
+    let cursor = Mutex::new(cursor);
+    let results = run_targets(
+        &targets,
+        settings.sync_workers(),
+        settings.copy_strategy,
+        &|target, ok| {
+            let mut cursor = cursor.lock().unwrap_or_else(|e| e.into_inner());
+            cursor.failed[target.skill] |= !ok;
+            cursor.remaining[target.skill] -= 1;
+            cursor.advance(skills, progress);
+        },
+    );
+
+    collect_results(&targets, results, &mut report, &mut errors);
+    let synced: Vec<(&str, &[String])> = skills
+        .iter()
+        .zip(&errors)
+        .filter(|(_, error)| error.is_none())
+        .map(|(skill, _)| (skill.name.as_str(), skill.enabled_agents.as_slice()))
+        .collect();
+    record_distribution(&store_root, &synced)?;
+    if let Some(err) = errors.into_iter().flatten().next() {
+        return Err(err);
+    }
+    journal.commit()?;
+
+    progress.report(SyncAllSkillsDistributionProgressLog {
+        id: "done".to_string(),
+        label: "分发完成".to_string(),
+        status: "success".to_string(),
+        progress: 100.0,
+    });
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::progress::NoopProgress;
+    use crate::utils::generate_id;
+
+    fn temp_test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("skills-manager-{name}-{}", generate_id()));
+        ensure_dir(&dir).expect("create temp dir");
+        dir
+    }
+
+    fn write_file(path: &Path, content: &str) {
+        if let Some(parent) = path.parent() {
+            ensure_dir(parent).expect("create parent dir");
+        }
+        fs::write(path, content).expect("write file");
+    }
+
+    fn agent(id: &str, name: &str, root: &Path, enabled: bool) -> AgentInfo {
+        AgentInfo {
+            id: id.to_string(),
+            name: name.to_string(),
+            default_path: root.to_string_lossy().to_string(),
+            current_path: root.to_string_lossy().to_string(),
+            enabled,
+            icon: "test".to_string(),
+            distribution_mode: DistributionMode::Copy,
+        }
+    }
+
+    #[test]
+    fn sync_one_skill_adds_yaml_frontmatter_for_codex() {
+        let tmp = temp_test_dir("sync-one-skill-frontmatter");
+        let store_root = tmp.join("store");
+        let codex_root = tmp.join("codex");
+
+        ensure_dir(&store_root).unwrap();
+        ensure_dir(&codex_root).unwrap();
+
+        let skill_name = "agent-browser";
+        let store_skill_dir = store_root.join(safe_skill_dir_name(skill_name));
+        write_file(
+            &store_skill_dir.join("SKILL.md"),
+            "# agent-browser\n\n---\n",
+        );
+
+        let agents = vec![agent("codex", "Codex", &codex_root, true)];
+        sync_one_skill(&store_root, skill_name, &["codex".to_string()], &agents).unwrap();
+
+        let dst_skill_md = codex_root
+            .join(safe_skill_dir_name(skill_name))
+            .join("SKILL.md");
+        let content = fs::read_to_string(dst_skill_md).unwrap();
+        assert!(
+            content.starts_with("---\n") || content.starts_with("---\r\n"),
+            "Codex target should have YAML frontmatter"
+        );
+        assert!(
+            content.contains("name: 'agent-browser'")
+                || content.contains("name: \"agent-browser\"")
+                || content.contains("name: agent-browser"),
+            "YAML frontmatter should contain name"
+        );
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn sync_one_skill_skips_disabled_agents() {
+        let tmp = temp_test_dir("sync-one-skill");
+        let store_root = tmp.join("store");
+        let enabled_root = tmp.join("enabled-agent");
+        let disabled_root = tmp.join("disabled-agent");
+
+        ensure_dir(&store_root).unwrap();
+        ensure_dir(&enabled_root).unwrap();
+        ensure_dir(&disabled_root).unwrap();
+
+        let skill_name = "My Skill";
+        let store_skill_dir = store_root.join(safe_skill_dir_name(skill_name));
+        write_file(&store_skill_dir.join("SKILL.md"), "# My Skill\n");
+
+        // Create pre-existing folders in both agents to ensure removal/copy behavior is observable.
+        write_file(
+            &enabled_root
+                .join(safe_skill_dir_name(skill_name))
+                .join("SKILL.md"),
+            "# old\n",
+        );
+        write_file(
+            &disabled_root
+                .join(safe_skill_dir_name(skill_name))
+                .join("SKILL.md"),
+            "# keep\n",
+        );
+
+        let agents = vec![
+            agent("enabled", "Enabled", &enabled_root, true),
+            agent("disabled", "Disabled", &disabled_root, false),
+        ];
+
+        // Skill is not enabled for the enabled agent -> should be removed there.
+        // Disabled agent should remain untouched.
+        sync_one_skill(&store_root, skill_name, &[], &agents).unwrap();
+
+        assert!(
+            !enabled_root.join(safe_skill_dir_name(skill_name)).exists(),
+            "enabled agent folder should be removed when not selected"
+        );
+        assert!(
+            disabled_root.join(safe_skill_dir_name(skill_name)).exists(),
+            "disabled agent folder should not be touched"
+        );
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn sync_one_skill_copies_to_all_agent_roots() {
+        let tmp = temp_test_dir("sync-one-skill-all-roots");
+        let store_root = tmp.join("store");
+        let current_root = tmp.join("current");
+        let default_root = tmp.join("default");
+
+        ensure_dir(&store_root).unwrap();
+        ensure_dir(&current_root).unwrap();
+        ensure_dir(&default_root).unwrap();
+
+        let skill_name = "agent-browser";
+        let store_skill_dir = store_root.join(safe_skill_dir_name(skill_name));
+        write_file(&store_skill_dir.join("SKILL.md"), "# agent-browser\n");
+
+        let agent = AgentInfo {
+            id: "x".to_string(),
+            name: "X".to_string(),
+            default_path: default_root.to_string_lossy().to_string(),
+            current_path: current_root.to_string_lossy().to_string(),
+            enabled: true,
+            icon: "test".to_string(),
+            distribution_mode: DistributionMode::Copy,
+        };
+
+        sync_one_skill(&store_root, skill_name, &["x".to_string()], &[agent]).unwrap();
+
+        for root in [current_root, default_root] {
+            assert!(
+                root.join(safe_skill_dir_name(skill_name))
+                    .join("SKILL.md")
+                    .exists(),
+                "skill should be copied into {}",
+                root.display()
+            );
+        }
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    fn symlink_agent(id: &str, root: &Path) -> AgentInfo {
+        AgentInfo {
+            distribution_mode: DistributionMode::Symlink,
+            ..agent(id, id, root, true)
+        }
+    }
+
+    #[test]
+    fn sync_one_skill_links_and_unlinks_in_symlink_mode() {
+        let tmp = temp_test_dir("sync-one-skill-symlink");
+        let store_root = tmp.join("store");
+        let agent_root = tmp.join("agent");
+        write_file(
+            &store_root.join("alpha").join("SKILL.md"),
+            "---\nname: alpha\n---\n",
+        );
+
+        let agents = vec![symlink_agent("a", &agent_root)];
+        sync_one_skill(&store_root, "alpha", &["a".to_string()], &agents).unwrap();
+
+        let dst = agent_root.join("alpha");
+        let meta = fs::symlink_metadata(&dst).unwrap();
+        if !meta.file_type().is_symlink() {
+            // Symlinks are unavailable here and distribution fell back to a copy.
+            let _ = fs::remove_dir_all(&tmp);
+            return;
+        }
+
+        write_file(&store_root.join("alpha").join("notes.md"), "live\n");
+        assert_eq!(fs::read_to_string(dst.join("notes.md")).unwrap(), "live\n");
+
+        sync_one_skill(&store_root, "alpha", &[], &agents).unwrap();
+        assert!(fs::symlink_metadata(&dst).is_err());
+        assert!(store_root.join("alpha").join("SKILL.md").is_file());
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn symlink_mode_falls_back_to_copy_when_codex_needs_frontmatter() {
+        let tmp = temp_test_dir("sync-one-skill-symlink-codex");
+        let store_root = tmp.join("store");
+        let codex_root = tmp.join("codex");
+        write_file(&store_root.join("alpha").join("SKILL.md"), "# alpha\n");
+
+        let agents = vec![symlink_agent("codex", &codex_root)];
+        sync_one_skill(&store_root, "alpha", &["codex".to_string()], &agents).unwrap();
+
+        let dst = codex_root.join("alpha");
+        assert!(!fs::symlink_metadata(&dst).unwrap().file_type().is_symlink());
+        assert!(fs::read_to_string(dst.join("SKILL.md")).unwrap().starts_with("---\n"));
+        assert_eq!(
+            fs::read_to_string(store_root.join("alpha").join("SKILL.md")).unwrap(),
+            "# alpha\n",
+            "store copy must not be rewritten"
+        );
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn sync_one_skill_records_enabled_agents_in_manifest() {
+        let tmp = temp_test_dir("sync-one-skill-manifest");
+        let store_root = tmp.join("store");
+        let agent_root = tmp.join("agent");
+        write_file(&store_root.join("alpha").join("SKILL.md"), "# alpha\n");
+
+        let agents = vec![agent("a", "A", &agent_root, true)];
+        sync_one_skill(&store_root, "alpha", &["a".to_string()], &agents).unwrap();
+
+        let manifest = crate::manifest::load_manifest(&store_root).unwrap();
+        assert_eq!(manifest.get("alpha").unwrap().enabled_agents, vec!["a".to_string()]);
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn resync_skips_unchanged_targets_and_repairs_drift() {
+        let tmp = temp_test_dir("sync-incremental");
+        let store_root = tmp.join("store");
+        let agent_root = tmp.join("agent");
+        let codex_root = tmp.join("codex");
+        write_file(&store_root.join("alpha").join("SKILL.md"), "# alpha\n");
+        write_file(&store_root.join("alpha").join("docs").join("a.md"), "a\n");
+
+        let agents = vec![
+            agent("a", "A", &agent_root, true),
+            agent("codex", "Codex", &codex_root, true),
+        ];
+        let enabled = ["a".to_string(), "codex".to_string()];
+
+        let first = sync_one_skill(&store_root, "alpha", &enabled, &agents).unwrap();
+        assert_eq!((first.created, first.updated, first.unchanged), (2, 0, 0));
+
+        // The codex copy carries a rewritten SKILL.md and must still count as in sync.
+        let second = sync_one_skill(&store_root, "alpha", &enabled, &agents).unwrap();
+        assert_eq!((second.created, second.updated, second.unchanged), (0, 0, 2));
+        assert_eq!(second.files, CopyReport::default());
+
+        let dst = agent_root.join("alpha");
+        write_file(&dst.join("docs").join("a.md"), "edited\n");
+        write_file(&dst.join("extra.md"), "extra\n");
+        let third = sync_one_skill(&store_root, "alpha", &enabled, &agents).unwrap();
+        assert_eq!((third.updated, third.unchanged), (1, 1));
+        assert_eq!(third.files.copied, 1, "only the drifted file is rewritten");
+        assert_eq!(fs::read_to_string(dst.join("docs").join("a.md")).unwrap(), "a\n");
+        assert!(!dst.join("extra.md").exists());
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn sync_one_skill_skips_agents_whose_lint_rules_fail() {
+        let tmp = temp_test_dir("sync-one-skill-lint-gate");
+        let store_root = tmp.join("store");
+        let claude_root = tmp.join("claude");
+        let other_root = tmp.join("other");
+        write_file(
+            &store_root.join("alpha").join("SKILL.md"),
+            "---\nname: Alpha Skill\ndescription: does alpha\n---\n",
+        );
+
+        let agents = vec![
+            agent("claude-code", "Claude Code", &claude_root, true),
+            agent("other", "Other", &other_root, true),
+        ];
+        let enabled = ["claude-code".to_string(), "other".to_string()];
+        let report = sync_one_skill(&store_root, "alpha", &enabled, &agents).unwrap();
+
+        assert_eq!(
+            report.blocked,
+            vec![BlockedTarget {
+                skill: "alpha".to_string(),
+                agent_id: "claude-code".to_string(),
+                rules: vec![crate::lint::RULE_NAME_FORMAT.to_string()],
+            }]
+        );
+        assert!(!claude_root.join("alpha").exists());
+        assert!(other_root.join("alpha").join("SKILL.md").is_file());
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    fn test_skill(name: &str, enabled: &[&str]) -> Skill {
+        Skill {
+            id: name.to_string(),
+            name: name.to_string(),
+            source_url: None,
+            enabled_agents: enabled.iter().map(|id| id.to_string()).collect(),
+            last_sync: None,
+            last_update: None,
+            last_sync_digest: None,
+            source_commit: None,
+            source_subpath: None,
+            source_kind: None,
+            source_ref: None,
+            source_checksum: None,
+            installed_at: None,
+        }
+    }
+
+    struct RecordingProgress(Mutex<Vec<SyncAllSkillsDistributionProgressLog>>);
+
+    impl ProgressReporter<SyncAllSkillsDistributionProgressLog> for RecordingProgress {
+        fn report(&self, payload: SyncAllSkillsDistributionProgressLog) {
+            self.0.lock().unwrap().push(payload);
+        }
+    }
+
+    #[test]
+    fn sync_all_skills_distribution_reports_progress_per_skill() {
+        let tmp = temp_test_dir("sync-all-distribution-progress");
+        let store_root = tmp.join("store");
+        let agent_root = tmp.join("agent");
+        write_file(&store_root.join("alpha").join("SKILL.md"), "# alpha\n");
+        write_file(&store_root.join("beta").join("SKILL.md"), "# beta\n");
+
+        let skills: Vec<Skill> = ["alpha", "beta"]
+            .iter()
+            .map(|name| test_skill(name, &["a"]))
+            .collect();
+        let agents = vec![agent("a", "A", &agent_root, true)];
+
+        let progress = RecordingProgress(Mutex::new(vec![]));
+        sync_all_skills_distribution(&skills, &agents, &store_root.to_string_lossy(), &progress)
+            .unwrap();
+
+        let logs = progress.0.into_inner().unwrap();
+        let ids: Vec<&str> = logs.iter().map(|log| log.id.as_str()).collect();
+        assert_eq!(ids, vec!["sync-alpha", "sync-alpha", "sync-beta", "sync-beta", "done"]);
+        assert_eq!(logs.last().unwrap().progress, 100.0);
+        assert!(agent_root.join("beta").join("SKILL.md").exists());
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    /// The result of a sync and its progress logs as (id, status, percent).
+    type SyncRun = (Result<DistributionReport, String>, Vec<(String, String, u64)>);
+
+    /// Distributes eight skills to three agents, two of which share a root,
+    /// with the given worker count. With `failing`, every third skill also
+    /// goes to an agent whose root cannot be written.
+    fn sync_many(tmp: &Path, workers: usize, failing: bool) -> SyncRun {
+        let store_root = tmp.join("store");
+        let shared_root = tmp.join("shared");
+        for idx in 0..8 {
+            let name = format!("skill-{idx}");
+            write_file(&store_root.join(&name).join("SKILL.md"), &format!("# {name}\n"));
+        }
+        crate::settings::save_settings(
+            &store_root,
+            &StoreSettings {
+                sync_concurrency: Some(workers),
+                ..StoreSettings::default()
+            },
+        )
+        .unwrap();
+
+        let bad_root = tmp.join("bad");
+        write_file(&bad_root, "not a directory\n");
+        let mut agents = vec![
+            agent("a", "A", &tmp.join("a"), true),
+            agent("b", "B", &shared_root, true),
+            agent("c", "C", &shared_root, true),
+        ];
+        if failing {
+            agents.push(agent("bad", "Bad", &bad_root, true));
+        }
+        let skills: Vec<Skill> = (0..8)
+            .map(|idx| {
+                let mut enabled = if idx % 2 == 0 { vec!["a", "b"] } else { vec!["c"] };
+                if idx % 3 == 0 {
+                    enabled.push("bad");
+                }
+                test_skill(&format!("skill-{idx}"), &enabled)
+            })
+            .collect();
+
+        let progress = RecordingProgress(Mutex::new(vec![]));
+        let result = sync_all_skills_distribution(
+            &skills,
+            &agents,
+            &store_root.to_string_lossy(),
+            &progress,
+        );
+        let logs = progress
+            .0
+            .into_inner()
+            .unwrap()
+            .into_iter()
+            .map(|log| (log.id, log.status, log.progress as u64))
+            .collect();
+        (result, logs)
+    }
+
+    #[test]
+    fn parallel_sync_matches_sequential_sync() {
+        let sequential_tmp = temp_test_dir("sync-all-sequential");
+        let parallel_tmp = temp_test_dir("sync-all-parallel");
+
+        let (sequential, sequential_logs) = sync_many(&sequential_tmp, 1, false);
+        let (parallel, parallel_logs) = sync_many(&parallel_tmp, 4, false);
+        let parallel = parallel.unwrap();
+        assert_eq!(parallel, sequential.unwrap());
+        assert_eq!(parallel_logs, sequential_logs);
+        assert_eq!(parallel.created, 4 * 2 + 4);
+
+        // Agents b and c share a root, so the last agent to claim a skill wins.
+        let shared = parallel_tmp.join("shared");
+        assert!(shared.join("skill-1").join("SKILL.md").is_file());
+        assert!(!shared.join("skill-0").exists());
+        assert!(parallel_tmp.join("a").join("skill-0").join("SKILL.md").is_file());
+
+        let _ = fs::remove_dir_all(&sequential_tmp);
+        let _ = fs::remove_dir_all(&parallel_tmp);
+    }
+
+    #[test]
+    fn parallel_sync_with_failures_matches_sequential_sync() {
+        let sequential_tmp = temp_test_dir("sync-all-sequential-error");
+        let parallel_tmp = temp_test_dir("sync-all-parallel-error");
+
+        let (sequential, sequential_logs) = sync_many(&sequential_tmp, 1, true);
+        let (parallel, parallel_logs) = sync_many(&parallel_tmp, 4, true);
+        let err = parallel.unwrap_err();
+        assert_eq!(
+            err.replace(&*parallel_tmp.to_string_lossy(), ""),
+            sequential
+                .unwrap_err()
+                .replace(&*sequential_tmp.to_string_lossy(), "")
+        );
+        assert!(err.contains("bad"), "{err}");
+        assert_eq!(parallel_logs, sequential_logs);
+        let failed: Vec<&str> = parallel_logs
+            .iter()
+            .filter(|(_, status, _)| status == "error")
+            .map(|(id, _, _)| id.as_str())
+            .collect();
+        assert_eq!(failed, vec!["sync-skill-0", "sync-skill-3", "sync-skill-6"]);
+
+        for tmp in [&sequential_tmp, &parallel_tmp] {
+            // Every target of a failed skill except the broken one is written.
+            assert!(tmp.join("a").join("skill-0").join("SKILL.md").is_file());
+            assert!(tmp.join("a").join("skill-4").join("SKILL.md").is_file());
+            assert!(tmp.join("shared").join("skill-7").join("SKILL.md").is_file());
+
+            let manifest = crate::manifest::load_manifest(&tmp.join("store")).unwrap();
+            let recorded: Vec<&str> = manifest
+                .skills
+                .iter()
+                .filter(|skill| !skill.enabled_agents.is_empty())
+                .map(|skill| skill.name.as_str())
+                .collect();
+            assert_eq!(
+                recorded,
+                vec!["skill-1", "skill-2", "skill-4", "skill-5", "skill-7"]
+            );
+        }
+
+        let _ = fs::remove_dir_all(&sequential_tmp);
+        let _ = fs::remove_dir_all(&parallel_tmp);
+    }
+
+    #[test]
+    fn sync_continues_past_a_skill_missing_from_the_store() {
+        let tmp = temp_test_dir("sync-all-missing-skill");
+        let store_root = tmp.join("store");
+        let agent_root = tmp.join("agent");
+        write_file(&store_root.join("alpha").join("SKILL.md"), "# alpha\n");
+        write_file(&store_root.join("gamma").join("SKILL.md"), "# gamma\n");
+        crate::manifest::update_manifest(&store_root, |_| ()).unwrap();
+
+        let skills = vec![
+            test_skill("alpha", &["a"]),
+            test_skill("beta", &["a"]),
+            test_skill("gamma", &["a"]),
+        ];
+        let agents = vec![agent("a", "A", &agent_root, true)];
+        let progress = RecordingProgress(Mutex::new(vec![]));
+        let err = sync_all_skills_distribution(
+            &skills,
+            &agents,
+            &store_root.to_string_lossy(),
+            &progress,
+        )
+        .unwrap_err();
+
+        assert!(err.contains("name=beta"), "{err}");
+        assert!(agent_root.join("alpha").join("SKILL.md").is_file());
+        assert!(agent_root.join("gamma").join("SKILL.md").is_file());
+        let manifest = crate::manifest::load_manifest(&store_root).unwrap();
+        assert_eq!(manifest.get("gamma").unwrap().enabled_agents, vec!["a".to_string()]);
+        let logs: Vec<(String, String)> = progress
+            .0
+            .into_inner()
+            .unwrap()
+            .into_iter()
+            .map(|log| (log.id, log.status))
+            .collect();
+        let expected = [
+            ("sync-alpha", "loading"),
+            ("sync-alpha", "success"),
+            ("sync-beta", "loading"),
+            ("sync-beta", "error"),
+            ("sync-gamma", "loading"),
+            ("sync-gamma", "success"),
+        ];
+        let expected: Vec<(String, String)> = expected
+            .iter()
+            .map(|(id, status)| (id.to_string(), status.to_string()))
+            .collect();
+        assert_eq!(logs, expected);
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn failed_target_does_not_stop_the_rest_of_the_sync() {
+        let tmp = temp_test_dir("sync-all-target-error");
+        let store_root = tmp.join("store");
+        let good_root = tmp.join("good");
+        // A file where the agent root should be makes every copy there fail.
+        let bad_root = tmp.join("bad");
+        write_file(&bad_root, "not a directory\n");
+        for name in ["alpha", "beta"] {
+            write_file(&store_root.join(name).join("SKILL.md"), &format!("# {name}\n"));
+        }
+        crate::manifest::update_manifest(&store_root, |_| ()).unwrap();
+
+        let skills = vec![
+            test_skill("alpha", &["bad", "good"]),
+            test_skill("beta", &["good"]),
+        ];
+        let agents = vec![
+            agent("bad", "Bad", &bad_root, true),
+            agent("good", "Good", &good_root, true),
+        ];
+        let err = sync_all_skills_distribution(
+            &skills,
+            &agents,
+            &store_root.to_string_lossy(),
+            &NoopProgress,
+        )
+        .unwrap_err();
+
+        assert!(err.contains("bad"), "{err}");
+        assert!(good_root.join("alpha").join("SKILL.md").is_file());
+        assert!(good_root.join("beta").join("SKILL.md").is_file());
+        let manifest = crate::manifest::load_manifest(&store_root).unwrap();
+        assert!(manifest.get("alpha").unwrap().enabled_agents.is_empty());
+        assert_eq!(manifest.get("beta").unwrap().enabled_agents, vec!["good".to_string()]);
+        assert_eq!(fs::read_dir(store_root.join(".skillsm").join("journal")).unwrap().count(), 0);
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+}
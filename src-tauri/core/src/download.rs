@@ -0,0 +1,474 @@
+use std::error::Error as _;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::models::InstallProgressLog;
+use crate::progress::ProgressReporter;
+
+/// How often a byte-level progress log is emitted when the length is unknown.
+const PROGRESS_STEP_BYTES: u64 = 256 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DownloadOptions {
+    pub connect_timeout: Duration,
+    /// Longest wait for the next chunk of the response.
+    pub read_timeout: Duration,
+    /// Extra attempts after the first one, for transient failures only.
+    pub retries: u32,
+    /// Multiplied by the attempt number before each retry.
+    pub retry_delay: Duration,
+    /// Lowercase hex SHA-256 the downloaded bytes must match.
+    pub expected_sha256: Option<String>,
+    /// Largest response body accepted, checked against `Content-Length` and
+    /// again while streaming.
+    pub max_bytes: u64,
+}
+
+impl Default for DownloadOptions {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Duration::from_secs(15),
+            read_timeout: Duration::from_secs(30),
+            retries: 2,
+            retry_delay: Duration::from_millis(500),
+            expected_sha256: None,
+            max_bytes: 256 * 1024 * 1024,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum DownloadError {
+    InvalidUrl {
+        url: String,
+        message: String,
+    },
+    /// The server answered with a non-success status.
+    Status {
+        url: String,
+        status: u16,
+    },
+    Dns {
+        url: String,
+        message: String,
+    },
+    Tls {
+        url: String,
+        message: String,
+    },
+    Connect {
+        url: String,
+        message: String,
+    },
+    Timeout {
+        url: String,
+    },
+    /// Reading the response or writing the file failed.
+    Io {
+        message: String,
+    },
+    ChecksumMismatch {
+        expected: String,
+        actual: String,
+    },
+    TooLarge {
+        url: String,
+        limit: u64,
+    },
+}
+
+impl DownloadError {
+    fn is_retryable(&self) -> bool {
+        match self {
+            Self::Status { status, .. } => *status == 429 || *status >= 500,
+            Self::Connect { .. } | Self::Timeout { .. } | Self::Io { .. } => true,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for DownloadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidUrl { url, message } => write!(f, "Invalid download URL {url}: {message}"),
+            Self::Status { url, status } => {
+                write!(f, "Download of {url} failed with HTTP {status}")
+            }
+            Self::Dns { url, message } => write!(f, "Failed to resolve host for {url}: {message}"),
+            Self::Tls { url, message } => write!(f, "TLS error while downloading {url}: {message}"),
+            Self::Connect { url, message } => write!(f, "Failed to connect to {url}: {message}"),
+            Self::Timeout { url } => write!(f, "Download of {url} timed out"),
+            Self::Io { message } => write!(f, "Download failed: {message}"),
+            Self::ChecksumMismatch { expected, actual } => write!(
+                f,
+                "Downloaded file checksum mismatch: expected sha256 {expected}, got {actual}"
+            ),
+            Self::TooLarge { url, limit } => {
+                write!(f, "Download of {url} exceeds the {limit} byte limit")
+            }
+        }
+    }
+}
+
+impl From<DownloadError> for String {
+    fn from(err: DownloadError) -> Self {
+        err.to_string()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DownloadSummary {
+    pub bytes: u64,
+    pub sha256: String,
+}
+
+fn is_timeout(err: &(dyn std::error::Error + 'static)) -> bool {
+    let mut source = Some(err);
+    while let Some(err) = source {
+        if let Some(io_err) = err.downcast_ref::<io::Error>() {
+            if matches!(
+                io_err.kind(),
+                io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock
+            ) {
+                return true;
+            }
+        }
+        source = err.source();
+    }
+    false
+}
+
+fn io_error(url: &str, err: io::Error) -> DownloadError {
+    if is_timeout(&err) {
+        DownloadError::Timeout {
+            url: url.to_string(),
+        }
+    } else {
+        DownloadError::Io {
+            message: err.to_string(),
+        }
+    }
+}
+
+fn request_error(url: &str, err: ureq::Error) -> DownloadError {
+    let url = url.to_string();
+    let transport = match err {
+        ureq::Error::Status(status, _) => return DownloadError::Status { url, status },
+        ureq::Error::Transport(transport) => transport,
+    };
+    let message = transport.to_string();
+    if transport.source().is_some_and(is_timeout) {
+        return DownloadError::Timeout { url };
+    }
+    match transport.kind() {
+        ureq::ErrorKind::InvalidUrl | ureq::ErrorKind::UnknownScheme => {
+            DownloadError::InvalidUrl { url, message }
+        }
+        ureq::ErrorKind::Dns => DownloadError::Dns { url, message },
+        ureq::ErrorKind::ConnectionFailed | ureq::ErrorKind::ProxyConnect => {
+            DownloadError::Connect { url, message }
+        }
+        // The TLS stack surfaces its failures as I/O errors, so go by the text.
+        _ if ["tls", "certificate", "handshake"]
+            .iter()
+            .any(|needle| message.to_lowercase().contains(needle)) =>
+        {
+            DownloadError::Tls { url, message }
+        }
+        _ => DownloadError::Io { message },
+    }
+}
+
+fn report_bytes(
+    progress: &dyn ProgressReporter<InstallProgressLog>,
+    url: &str,
+    bytes: u64,
+    total: Option<u64>,
+    status: &str,
+) {
+    let percent = match total {
+        Some(total) if total > 0 => (bytes as f64 / total as f64 * 100.0).min(100.0),
+        _ if status == "success" => 100.0,
+        _ => 0.0,
+    };
+    progress.report(InstallProgressLog {
+        id: "download".to_string(),
+        label: format!("正在下载: {url}"),
+        status: status.to_string(),
+        progress: percent,
+        bytes: Some(bytes),
+        total_bytes: total,
+    });
+}
+
+fn part_path(dest: &Path) -> PathBuf {
+    let mut name = dest.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    dest.with_file_name(name)
+}
+
+fn try_download(
+    url: &str,
+    dest: &Path,
+    options: &DownloadOptions,
+    progress: &dyn ProgressReporter<InstallProgressLog>,
+) -> Result<DownloadSummary, DownloadError> {
+    let agent = ureq::AgentBuilder::new()
+        .timeout_connect(options.connect_timeout)
+        .timeout_read(options.read_timeout)
+        .build();
+    let response = agent.get(url).call().map_err(|e| request_error(url, e))?;
+    let total = response
+        .header("Content-Length")
+        .and_then(|len| len.trim().parse::<u64>().ok());
+    let too_large = || DownloadError::TooLarge {
+        url: url.to_string(),
+        limit: options.max_bytes,
+    };
+    if total.is_some_and(|total| total > options.max_bytes) {
+        return Err(too_large());
+    }
+
+    let part = part_path(dest);
+    let mut file = fs::File::create(&part).map_err(|e| DownloadError::Io {
+        message: format!("Failed to create {}: {e}", part.display()),
+    })?;
+    let mut reader = response.into_reader();
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 64 * 1024];
+    let mut bytes: u64 = 0;
+    let mut last_reported: u64 = 0;
+    let mut last_percent: u64 = 0;
+
+    report_bytes(progress, url, 0, total, "loading");
+    loop {
+        let read = match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(io_error(url, e)),
+        };
+        if bytes + read as u64 > options.max_bytes {
+            return Err(too_large());
+        }
+        hasher.update(&buf[..read]);
+        file.write_all(&buf[..read])
+            .map_err(|e| DownloadError::Io {
+                message: format!("Failed to write {}: {e}", part.display()),
+            })?;
+        bytes += read as u64;
+
+        let due = match total {
+            Some(total) if total > 0 => {
+                let percent = bytes * 100 / total;
+                let due = percent > last_percent;
+                last_percent = percent;
+                due
+            }
+            _ => bytes - last_reported >= PROGRESS_STEP_BYTES,
+        };
+        if due {
+            last_reported = bytes;
+            report_bytes(progress, url, bytes, total, "loading");
+        }
+    }
+    file.sync_all().map_err(|e| DownloadError::Io {
+        message: format!("Failed to sync {}: {e}", part.display()),
+    })?;
+    drop(file);
+
+    let sha256 = format!("{:x}", hasher.finalize());
+    if let Some(expected) = &options.expected_sha256 {
+        if !expected.eq_ignore_ascii_case(&sha256) {
+            return Err(DownloadError::ChecksumMismatch {
+                expected: expected.to_lowercase(),
+                actual: sha256,
+            });
+        }
+    }
+
+    fs::rename(&part, dest).map_err(|e| DownloadError::Io {
+        message: format!("Failed to move download to {}: {e}", dest.display()),
+    })?;
+    report_bytes(progress, url, bytes, total, "success");
+    Ok(DownloadSummary { bytes, sha256 })
+}
+
+/// Streams `url` into `dest`, retrying transient failures and verifying the
+/// checksum when one is expected. `dest` is only written once the download
+/// is complete; partial data goes to a `.part` sibling that is removed on error.
+pub fn download_to_file(
+    url: &str,
+    dest: &Path,
+    options: &DownloadOptions,
+    progress: &dyn ProgressReporter<InstallProgressLog>,
+) -> Result<DownloadSummary, DownloadError> {
+    let mut attempt = 0;
+    loop {
+        let result = try_download(url, dest, options, progress);
+        let err = match result {
+            Ok(summary) => return Ok(summary),
+            Err(err) => err,
+        };
+        let _ = fs::remove_file(part_path(dest));
+        if attempt >= options.retries || !err.is_retryable() {
+            progress.report(InstallProgressLog {
+                id: "download".to_string(),
+                label: err.to_string(),
+                status: "error".to_string(),
+                progress: 100.0,
+                bytes: None,
+                total_bytes: None,
+            });
+            return Err(err);
+        }
+        attempt += 1;
+        std::thread::sleep(options.retry_delay * attempt);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{ensure_dir, generate_id};
+    use std::net::TcpListener;
+    use std::sync::Mutex;
+    use tiny_http::{Response, Server};
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<InstallProgressLog>>);
+
+    impl ProgressReporter<InstallProgressLog> for Recorder {
+        fn report(&self, payload: InstallProgressLog) {
+            self.0.lock().unwrap().push(payload);
+        }
+    }
+
+    fn options() -> DownloadOptions {
+        DownloadOptions {
+            connect_timeout: Duration::from_secs(2),
+            read_timeout: Duration::from_millis(300),
+            retries: 1,
+            retry_delay: Duration::ZERO,
+            expected_sha256: None,
+            max_bytes: 1024 * 1024,
+        }
+    }
+
+    /// Serves one response per request, in order, then stops.
+    fn serve(responses: Vec<(u16, Vec<u8>)>) -> String {
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let port = server.server_addr().to_ip().unwrap().port();
+        std::thread::spawn(move || {
+            for (status, body) in responses {
+                let Ok(request) = server.recv() else {
+                    return;
+                };
+                let response = Response::from_data(body)
+                    .with_status_code(status)
+                    .with_chunked_threshold(usize::MAX);
+                let _ = request.respond(response);
+            }
+        });
+        format!("http://127.0.0.1:{port}/skill.zip")
+    }
+
+    fn temp_dest(name: &str) -> (PathBuf, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("skills-manager-{name}-{}", generate_id()));
+        ensure_dir(&dir).unwrap();
+        let dest = dir.join("download.zip");
+        (dir, dest)
+    }
+
+    #[test]
+    fn download_streams_to_file_with_progress_and_checksum() {
+        let (dir, dest) = temp_dest("download-ok");
+        let body = vec![7u8; 300 * 1024];
+        let sha256 = format!("{:x}", Sha256::digest(&body));
+        let url = serve(vec![(500, vec![]), (200, body.clone())]);
+
+        let recorder = Recorder::default();
+        let opts = DownloadOptions {
+            expected_sha256: Some(sha256.to_uppercase()),
+            ..options()
+        };
+        let summary = download_to_file(&url, &dest, &opts, &recorder).unwrap();
+        assert_eq!(summary.bytes, body.len() as u64);
+        assert_eq!(summary.sha256, sha256);
+        assert_eq!(fs::read(&dest).unwrap(), body);
+        assert!(!part_path(&dest).exists());
+
+        let logs = recorder.0.lock().unwrap();
+        let last = logs.last().unwrap();
+        assert_eq!(last.status, "success");
+        assert_eq!(last.bytes, Some(body.len() as u64));
+        assert_eq!(last.total_bytes, Some(body.len() as u64));
+        assert!(logs
+            .iter()
+            .any(|log| log.progress > 0.0 && log.progress < 100.0));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn download_reports_structured_errors() {
+        let (dir, dest) = temp_dest("download-err");
+
+        let url = serve(vec![(404, b"nope".to_vec())]);
+        let err = download_to_file(&url, &dest, &options(), &Recorder::default()).unwrap_err();
+        assert!(
+            matches!(err, DownloadError::Status { status: 404, .. }),
+            "{err:?}"
+        );
+
+        let url = serve(vec![(200, b"abc".to_vec())]);
+        let opts = DownloadOptions {
+            expected_sha256: Some("00".repeat(32)),
+            ..options()
+        };
+        let err = download_to_file(&url, &dest, &opts, &Recorder::default()).unwrap_err();
+        assert!(
+            matches!(err, DownloadError::ChecksumMismatch { .. }),
+            "{err:?}"
+        );
+        assert!(!dest.exists() && !part_path(&dest).exists());
+
+        // Accepts the connection but never answers.
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/skill.zip", listener.local_addr().unwrap());
+        let opts = DownloadOptions {
+            retries: 0,
+            ..options()
+        };
+        let err = download_to_file(&url, &dest, &opts, &Recorder::default()).unwrap_err();
+        assert!(matches!(err, DownloadError::Timeout { .. }), "{err:?}");
+
+        let port = listener.local_addr().unwrap().port();
+        drop(listener);
+        let url = format!("http://127.0.0.1:{port}/skill.zip");
+        let err = download_to_file(&url, &dest, &opts, &Recorder::default()).unwrap_err();
+        assert!(matches!(err, DownloadError::Connect { .. }), "{err:?}");
+
+        // Rejected from Content-Length before anything is written.
+        let url = serve(vec![(200, vec![0u8; 4096])]);
+        let opts = DownloadOptions {
+            max_bytes: 1024,
+            ..options()
+        };
+        let err = download_to_file(&url, &dest, &opts, &Recorder::default()).unwrap_err();
+        assert!(
+            matches!(err, DownloadError::TooLarge { limit: 1024, .. }),
+            "{err:?}"
+        );
+        assert!(!dest.exists() && !part_path(&dest).exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,430 @@
+use std::fs;
+use std::path::Path;
+
+use git2::build::CheckoutBuilder;
+use git2::{Direction, FetchOptions, Oid, Remote, RemoteCallbacks, Repository};
+
+use crate::models::InstallProgressLog;
+use crate::progress::{NoopProgress, ProgressReporter};
+use crate::utils::generate_id;
+
+/// Local ref the fetched commit is stored under before checkout.
+const FETCHED_REF: &str = "refs/skillsm/fetched";
+
+fn report(
+    progress: &dyn ProgressReporter<InstallProgressLog>,
+    id: &str,
+    label: String,
+    done: usize,
+    total: usize,
+) {
+    let percent = if total == 0 {
+        0.0
+    } else {
+        done as f64 / total as f64 * 100.0
+    };
+    progress.report(InstallProgressLog {
+        id: id.to_string(),
+        label,
+        status: if done >= total && total > 0 {
+            "success".to_string()
+        } else {
+            "loading".to_string()
+        },
+        progress: percent,
+        bytes: None,
+        total_bytes: None,
+    });
+}
+
+fn is_full_sha(reference: &str) -> bool {
+    reference.len() == 40 && reference.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Git accepts commit abbreviations of four hex digits or more.
+fn is_abbreviated_sha(reference: &str) -> bool {
+    (4..40).contains(&reference.len()) && reference.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Whether `url` names a repository on this machine. Anything else, including
+/// scp-style `host:path`, goes over the network.
+fn is_local_url(url: &str) -> bool {
+    url.starts_with("file://")
+        || url.starts_with("./")
+        || url.starts_with("../")
+        || Path::new(url).is_absolute()
+}
+
+/// The fetch depth for `url`: one commit when `shallow`, except for local
+/// repositories, since libgit2's local transport cannot do shallow fetches.
+fn fetch_depth(url: &str, shallow: bool) -> Option<i32> {
+    (shallow && !is_local_url(url)).then_some(1)
+}
+
+/// Refspecs tried for `reference`, in order of preference.
+fn candidate_refspecs(reference: Option<&str>) -> Vec<String> {
+    let Some(reference) = reference else {
+        return vec![format!("+HEAD:{FETCHED_REF}")];
+    };
+    if reference.starts_with("refs/") {
+        return vec![format!("+{reference}:{FETCHED_REF}")];
+    }
+    let mut specs = vec![
+        format!("+refs/heads/{reference}:{FETCHED_REF}"),
+        format!("+refs/tags/{reference}:{FETCHED_REF}"),
+    ];
+    if is_full_sha(reference) {
+        specs.push(format!("+{reference}:{FETCHED_REF}"));
+    }
+    specs
+}
+
+fn fetch_options<'a>(
+    url: &'a str,
+    shallow: bool,
+    progress: &'a dyn ProgressReporter<InstallProgressLog>,
+) -> FetchOptions<'a> {
+    let mut callbacks = RemoteCallbacks::new();
+    let mut last_percent = usize::MAX;
+    callbacks.transfer_progress(move |stats| {
+        let total = stats.total_objects();
+        let done = stats.received_objects();
+        let percent = (done * 100).checked_div(total).unwrap_or(0);
+        if percent != last_percent {
+            last_percent = percent;
+            report(progress, "fetch", format!("正在拉取: {url}"), done, total);
+        }
+        true
+    });
+    let mut options = FetchOptions::new();
+    options.remote_callbacks(callbacks);
+    if let Some(depth) = fetch_depth(url, shallow) {
+        options.depth(depth);
+    }
+    options
+}
+
+fn fetch_reference(
+    repo: &Repository,
+    url: &str,
+    reference: Option<&str>,
+    progress: &dyn ProgressReporter<InstallProgressLog>,
+) -> Result<Oid, String> {
+    let mut remote = repo
+        .remote_anonymous(url)
+        .map_err(|e| format!("Failed to open remote {url}: {}", e.message()))?;
+    for refspec in candidate_refspecs(reference) {
+        let mut options = fetch_options(url, true, progress);
+        remote
+            .fetch(&[refspec.as_str()], Some(&mut options), None)
+            .map_err(|e| format!("Failed to fetch {url}: {}", e.message()))?;
+        if let Ok(reference) = repo.find_reference(FETCHED_REF) {
+            return reference
+                .peel_to_commit()
+                .map(|commit| commit.id())
+                .map_err(|e| format!("Fetched ref is not a commit: {}", e.message()));
+        }
+    }
+    if let Some(short) = reference.filter(|r| is_abbreviated_sha(r)) {
+        return fetch_abbreviated(repo, &mut remote, url, short, progress);
+    }
+    Err(match reference {
+        Some(reference) => format!("Ref not found in {url}: {reference}"),
+        None => format!("Remote {url} has no HEAD"),
+    })
+}
+
+/// Servers only hand out commits by their full sha, so an abbreviated one is
+/// looked up in the full history of every branch and tag.
+fn fetch_abbreviated(
+    repo: &Repository,
+    remote: &mut Remote,
+    url: &str,
+    short: &str,
+    progress: &dyn ProgressReporter<InstallProgressLog>,
+) -> Result<Oid, String> {
+    let mut options = fetch_options(url, false, progress);
+    remote
+        .fetch(
+            &[
+                "+refs/heads/*:refs/skillsm/heads/*",
+                "+refs/tags/*:refs/skillsm/tags/*",
+            ],
+            Some(&mut options),
+            None,
+        )
+        .map_err(|e| format!("Failed to fetch {url}: {}", e.message()))?;
+    let object = repo.revparse_single(short).map_err(|e| match e.code() {
+        git2::ErrorCode::Ambiguous => {
+            format!("Commit {short} is ambiguous in {url}; use a longer or the full sha")
+        }
+        _ => format!("Ref not found in {url}: {short}"),
+    })?;
+    object
+        .peel_to_commit()
+        .map(|commit| commit.id())
+        .map_err(|_| format!("Ref not found in {url}: {short}"))
+}
+
+/// Fetches `reference` (a branch, tag, commit sha or the remote HEAD when
+/// `None`) from `url` with depth 1 (local repositories in full) and checks it
+/// out into `dest`, which must not exist yet. Abbreviated shas need the full
+/// history and are slower. The `.git` directory is removed afterwards; the resolved
+/// commit sha is returned.
+pub fn fetch_checkout(
+    url: &str,
+    reference: Option<&str>,
+    dest: &Path,
+    progress: &dyn ProgressReporter<InstallProgressLog>,
+) -> Result<String, String> {
+    let result = (|| {
+        let repo = Repository::init(dest).map_err(|e| {
+            format!(
+                "Failed to init repository {}: {}",
+                dest.display(),
+                e.message()
+            )
+        })?;
+        let oid = fetch_reference(&repo, url, reference, progress)?;
+        let commit = repo
+            .find_commit(oid)
+            .map_err(|e| format!("Failed to read commit {oid}: {}", e.message()))?;
+
+        let mut checkout = CheckoutBuilder::new();
+        checkout.force().progress(|path, done, total| {
+            let label = match path {
+                Some(path) => format!("正在检出: {}", path.display()),
+                None => "正在检出".to_string(),
+            };
+            report(progress, "checkout", label, done, total);
+        });
+        repo.checkout_tree(commit.as_object(), Some(&mut checkout))
+            .map_err(|e| format!("Failed to check out {oid}: {}", e.message()))?;
+        Ok(oid.to_string())
+    })();
+
+    let _ = fs::remove_dir_all(dest.join(".git"));
+    result
+}
+
+/// Resolves `reference` on `url` to a commit sha the way [`fetch_checkout`]
+/// would, but only lists the remote refs instead of fetching objects.
+pub fn resolve_remote(url: &str, reference: Option<&str>) -> Result<String, String> {
+    if let Some(reference) = reference.filter(|r| is_full_sha(r)) {
+        return Ok(reference.to_ascii_lowercase());
+    }
+    let mut remote = Remote::create_detached(url)
+        .map_err(|e| format!("Failed to open remote {url}: {}", e.message()))?;
+    remote
+        .connect(Direction::Fetch)
+        .map_err(|e| format!("Failed to connect to {url}: {}", e.message()))?;
+    let heads = remote
+        .list()
+        .map_err(|e| format!("Failed to list refs of {url}: {}", e.message()))?;
+    // Annotated tags are listed twice; the `^{}` entry is the commit.
+    let find = |name: &str| {
+        let peeled = format!("{name}^{{}}");
+        heads
+            .iter()
+            .find(|head| head.name() == peeled)
+            .or_else(|| heads.iter().find(|head| head.name() == name))
+            .map(|head| head.oid().to_string())
+    };
+    let found = match reference {
+        None => find("HEAD"),
+        Some(reference) if reference.starts_with("refs/") => find(reference),
+        Some(reference) => find(&format!("refs/heads/{reference}"))
+            .or_else(|| find(&format!("refs/tags/{reference}"))),
+    };
+    // Listing refs cannot expand an abbreviated sha; that takes the objects.
+    if let Some(short) = reference.filter(|r| found.is_none() && is_abbreviated_sha(r)) {
+        let tmp = std::env::temp_dir().join(format!("skills-manager-resolve-{}", generate_id()));
+        let result = Repository::init_bare(&tmp)
+            .map_err(|e| {
+                format!(
+                    "Failed to init repository {}: {}",
+                    tmp.display(),
+                    e.message()
+                )
+            })
+            .and_then(|repo| fetch_reference(&repo, url, Some(short), &NoopProgress));
+        let _ = fs::remove_dir_all(&tmp);
+        return result.map(|oid| oid.to_string());
+    }
+    found.ok_or_else(|| match reference {
+        Some(reference) => format!("Ref not found in {url}: {reference}"),
+        None => format!("Remote {url} has no HEAD"),
+    })
+}
+
+/// Turns `dir` into a repository with everything in it committed on `main`,
+/// and returns its `file://` url.
+#[cfg(test)]
+pub(crate) fn commit_dir_as_repo(dir: &Path) -> String {
+    let repo = Repository::init(dir).unwrap();
+    repo.set_head("refs/heads/main").unwrap();
+    let mut index = repo.index().unwrap();
+    index
+        .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+        .unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let sig = git2::Signature::now("test", "test@example.com").unwrap();
+    let parent = repo.head().ok().map(|head| head.peel_to_commit().unwrap());
+    let parents: Vec<_> = parent.iter().collect();
+    repo.commit(Some("HEAD"), &sig, &sig, "update", &tree, &parents)
+        .unwrap();
+    format!("file://{}", dir.display())
+}
+
+/// Writes a skill whose SKILL.md holds `content` to `dir`, commits it with
+/// [`commit_dir_as_repo`] and returns its url.
+#[cfg(test)]
+pub(crate) fn write_upstream(dir: &Path, content: &str) -> String {
+    crate::utils::ensure_dir(dir).unwrap();
+    fs::write(dir.join("SKILL.md"), content).unwrap();
+    commit_dir_as_repo(dir)
+}
+
+/// A fresh directory under the system temp dir for the test `name`.
+#[cfg(test)]
+pub(crate) fn temp_test_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("skills-manager-{name}-{}", generate_id()));
+    crate::utils::ensure_dir(&dir).expect("create temp dir");
+    dir
+}
+
+/// A copy-mode agent whose skills live in `root`, for tests that install
+/// from an upstream repo and distribute.
+#[cfg(test)]
+pub(crate) fn test_agent(root: &Path) -> crate::models::AgentInfo {
+    crate::models::AgentInfo {
+        id: "claude".to_string(),
+        name: "Claude".to_string(),
+        default_path: root.to_string_lossy().to_string(),
+        current_path: root.to_string_lossy().to_string(),
+        enabled: true,
+        icon: "test".to_string(),
+        distribution_mode: crate::models::DistributionMode::Copy,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::ensure_dir;
+    use git2::Signature;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<String>>);
+
+    impl ProgressReporter<InstallProgressLog> for Recorder {
+        fn report(&self, payload: InstallProgressLog) {
+            self.0.lock().unwrap().push(payload.id);
+        }
+    }
+
+    fn commit(repo: &Repository, refname: &str, content: &str, parents: &[Oid]) -> Oid {
+        let blob = repo.blob(content.as_bytes()).unwrap();
+        let mut builder = repo.treebuilder(None).unwrap();
+        builder.insert("SKILL.md", blob, 0o100644).unwrap();
+        let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+        let parents: Vec<_> = parents
+            .iter()
+            .map(|oid| repo.find_commit(*oid).unwrap())
+            .collect();
+        let parents: Vec<_> = parents.iter().collect();
+        let sig = Signature::now("test", "test@example.com").unwrap();
+        repo.commit(Some(refname), &sig, &sig, content, &tree, &parents)
+            .unwrap()
+    }
+
+    /// Builds a bare upstream with two commits on `main`, a `v1` tag on the
+    /// first and a `dev` branch forked from it, and returns its `file://` url.
+    fn upstream(tmp: &Path) -> (String, Oid, Oid, Oid) {
+        let path = tmp.join("upstream.git");
+        let repo = Repository::init_bare(&path).unwrap();
+        repo.set_head("refs/heads/main").unwrap();
+        let first = commit(&repo, "refs/heads/main", "# v1\n", &[]);
+        let second = commit(&repo, "refs/heads/main", "# v2\n", &[first]);
+        let dev = commit(&repo, "refs/heads/dev", "# dev\n", &[first]);
+        repo.reference("refs/tags/v1", first, false, "tag").unwrap();
+        (format!("file://{}", path.display()), first, second, dev)
+    }
+
+    #[test]
+    fn fetch_checkout_resolves_head_branches_tags_and_shas() {
+        let tmp = std::env::temp_dir().join(format!("skills-manager-git-{}", generate_id()));
+        ensure_dir(&tmp).unwrap();
+        let (url, first, second, dev) = upstream(&tmp);
+
+        let cases = [
+            (None, second, "# v2\n"),
+            (Some("main"), second, "# v2\n"),
+            (Some("dev"), dev, "# dev\n"),
+            (Some("v1"), first, "# v1\n"),
+        ];
+        for (i, (reference, oid, content)) in cases.into_iter().enumerate() {
+            let dest = tmp.join(format!("out-{i}"));
+            let recorder = Recorder::default();
+            let sha = fetch_checkout(&url, reference, &dest, &recorder).unwrap();
+            assert_eq!(sha, oid.to_string(), "{reference:?}");
+            assert_eq!(fs::read_to_string(dest.join("SKILL.md")).unwrap(), content);
+            assert!(!dest.join(".git").exists());
+            let ids = recorder.0.lock().unwrap();
+            assert!(ids.iter().any(|id| id == "fetch"));
+            assert!(ids.iter().any(|id| id == "checkout"));
+        }
+
+        let sha = second.to_string();
+        let dest = tmp.join("out-sha");
+        assert_eq!(
+            fetch_checkout(&url, Some(&sha), &dest, &NoopProgress).unwrap(),
+            sha
+        );
+
+        let short = &first.to_string()[..7];
+        let dest = tmp.join("out-short");
+        assert_eq!(
+            fetch_checkout(&url, Some(short), &dest, &NoopProgress).unwrap(),
+            first.to_string()
+        );
+        assert_eq!(fs::read_to_string(dest.join("SKILL.md")).unwrap(), "# v1\n");
+        assert_eq!(
+            resolve_remote(&url, Some(short)).unwrap(),
+            first.to_string()
+        );
+
+        let err = fetch_checkout(&url, Some("nope"), &tmp.join("out-missing"), &NoopProgress)
+            .unwrap_err();
+        assert!(err.contains("Ref not found"), "{err}");
+        let err = fetch_checkout(&url, Some("0000000"), &tmp.join("out-none"), &NoopProgress)
+            .unwrap_err();
+        assert!(err.contains("Ref not found"), "{err}");
+
+        for (reference, oid) in [(None, second), (Some("dev"), dev), (Some("v1"), first)] {
+            assert_eq!(resolve_remote(&url, reference).unwrap(), oid.to_string());
+        }
+        let err = resolve_remote(&url, Some("nope")).unwrap_err();
+        assert!(err.contains("Ref not found"), "{err}");
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn network_urls_fetch_shallow() {
+        for url in [
+            "https://github.com/foo/bar.git",
+            "ssh://git@example.com/foo/bar.git",
+            "git@github.com:foo/bar.git",
+            "example.com:foo/bar.git",
+        ] {
+            assert_eq!(fetch_depth(url, true), Some(1), "{url}");
+            assert_eq!(fetch_depth(url, false), None, "{url}");
+        }
+        for url in ["file:///srv/bar.git", "/srv/bar.git", "./bar", "../bar"] {
+            assert_eq!(fetch_depth(url, true), None, "{url}");
+        }
+    }
+}
@@ -0,0 +1,1073 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::Serialize;
+
+use crate::archive::{extract_zip, unwrap_single_top_dir, ExtractLimits};
+use crate::download::{download_to_file, DownloadOptions};
+use crate::git;
+use crate::journal::{Journal, JournalStep};
+use crate::manifest::{load_manifest, update_manifest};
+use crate::models::{InstallProgressLog, Skill, SkillMetadata, SourceKind};
+use crate::progress::{NoopProgress, ProgressReporter};
+use crate::scanner::find_skill_roots;
+use crate::skill_md::read_skill_metadata;
+use crate::utils::{
+    copy_dir_all, ensure_dir, expand_tilde, generate_id, manager_store_root, now_iso,
+    safe_skill_dir_name, unique_skill_dir_name,
+};
+
+fn normalize_repo_url(trimmed: &str) -> String {
+    if trimmed.starts_with("http://") || trimmed.starts_with("https://") {
+        return trimmed.to_string();
+    }
+    if trimmed.starts_with("github.com/") {
+        return format!("https://{trimmed}");
+    }
+    if trimmed.matches('/').count() == 1 && !trimmed.contains(' ') {
+        return format!("https://github.com/{trimmed}");
+    }
+    trimmed.to_string()
+}
+
+fn is_github_repo_url(url: &str) -> bool {
+    url.strip_prefix("https://github.com/")
+        .or_else(|| url.strip_prefix("http://github.com/"))
+        .is_some_and(|path| path.split('/').filter(|p| !p.is_empty()).count() == 2)
+}
+
+/// Splits a `#ref` suffix, or an `@ref` suffix on a GitHub `owner/repo`, off
+/// an install URL. `user@host:` style URLs keep their `@`.
+fn split_ref_pin(input: &str) -> (&str, Option<&str>) {
+    if let Some((base, pin)) = input.split_once('#') {
+        return (base, Some(pin));
+    }
+    match input.rsplit_once('@') {
+        Some((base, pin)) if is_github_repo_url(&normalize_repo_url(base)) => (base, Some(pin)),
+        _ => (input, None),
+    }
+}
+
+/// Expands GitHub shorthands into URLs. A pinned ref, given as `owner/repo@ref`
+/// or a `#ref` suffix, is kept as a `#ref` fragment.
+pub fn normalize_install_url(input: &str) -> String {
+    let trimmed = input.trim().trim_end_matches('/');
+    let (base, pin) = split_ref_pin(trimmed);
+    let url = normalize_repo_url(base.trim_end_matches('/'));
+    match pin.map(str::trim).filter(|pin| !pin.is_empty()) {
+        Some(pin) => format!("{url}#{pin}"),
+        None => url,
+    }
+}
+
+/// Where an install URL points once parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InstallSource {
+    Zip {
+        url: String,
+    },
+    /// A git repository; `subpath` narrows the install to one directory of it.
+    Git {
+        url: String,
+        reference: Option<String>,
+        subpath: Option<String>,
+    },
+}
+
+impl InstallSource {
+    pub fn kind(&self) -> SourceKind {
+        match self {
+            Self::Git { .. } => SourceKind::Git,
+            Self::Zip { .. } => SourceKind::Zip,
+        }
+    }
+
+    pub fn reference(&self) -> Option<&str> {
+        match self {
+            Self::Git { reference, .. } => reference.as_deref(),
+            Self::Zip { .. } => None,
+        }
+    }
+
+    pub fn subpath(&self) -> Option<&str> {
+        match self {
+            Self::Git { subpath, .. } => subpath.as_deref(),
+            Self::Zip { .. } => None,
+        }
+    }
+}
+
+/// Splits a GitHub `/tree/<ref>/<dir>` or `/blob/<ref>/<file>` URL into the
+/// repository, ref and directory. The ref is taken to be a single path
+/// segment, so refs containing `/` are not supported.
+fn parse_github_tree_url(url: &str) -> Option<InstallSource> {
+    let rest = url
+        .strip_prefix("https://github.com/")
+        .or_else(|| url.strip_prefix("http://github.com/"))?;
+    let rest = rest.split(['?', '#']).next().unwrap_or(rest);
+    let parts: Vec<&str> = rest.split('/').filter(|p| !p.is_empty()).collect();
+    let [owner, repo, kind, reference, path @ ..] = parts.as_slice() else {
+        return None;
+    };
+    let path = match *kind {
+        "tree" => path,
+        // A blob points at a file; install the directory holding it.
+        "blob" => path.split_last().map(|(_, dir)| dir).unwrap_or_default(),
+        _ => return None,
+    };
+    Some(InstallSource::Git {
+        url: format!(
+            "https://github.com/{owner}/{}",
+            repo.trim_end_matches(".git")
+        ),
+        reference: Some(reference.to_string()),
+        subpath: (!path.is_empty()).then(|| path.join("/")),
+    })
+}
+
+pub fn parse_install_source(input: &str) -> InstallSource {
+    let normalized = normalize_install_url(input);
+    let (url, pin) = match normalized.split_once('#') {
+        Some((url, pin)) => (url.to_string(), Some(pin.to_string())),
+        None => (normalized, None),
+    };
+    let lower = url.to_lowercase();
+    if lower.ends_with(".zip") || lower.contains(".zip?") {
+        return InstallSource::Zip { url };
+    }
+    match parse_github_tree_url(&url) {
+        // A pin wins over the ref spelled in the tree URL.
+        Some(InstallSource::Git {
+            url,
+            reference,
+            subpath,
+        }) => InstallSource::Git {
+            url,
+            reference: pin.or(reference),
+            subpath,
+        },
+        _ => InstallSource::Git {
+            url,
+            reference: pin,
+            subpath: None,
+        },
+    }
+}
+
+/// Resolves `subpath` inside a fetched source, refusing paths that leave it.
+pub(crate) fn source_subdir(root: &Path, subpath: Option<&str>) -> Result<PathBuf, String> {
+    let Some(subpath) = subpath else {
+        return Ok(root.to_path_buf());
+    };
+    if subpath
+        .split('/')
+        .any(|part| part == ".." || part.contains('\\'))
+        || subpath.starts_with('/')
+    {
+        return Err(format!("Invalid subpath: {subpath}"));
+    }
+    let dir = root.join(subpath);
+    if !dir.is_dir() {
+        return Err(format!("Directory not found in source: {subpath}"));
+    }
+    Ok(dir)
+}
+
+fn parse_name_from_dir(dir: &Path, fallback_name: &str) -> String {
+    if let Some(name) = read_skill_metadata(dir).and_then(|meta| meta.name) {
+        if !name.trim().is_empty() {
+            return name.trim().to_string();
+        }
+    }
+
+    let mut name = fallback_name.to_string();
+
+    let candidates = [
+        "SKILL.md",
+        "skill.md",
+        "README.md",
+        "README.MD",
+        "readme.md",
+    ];
+    let mut content: Option<String> = None;
+    for file in candidates {
+        let path = dir.join(file);
+        if path.exists() {
+            if let Ok(text) = fs::read_to_string(&path) {
+                content = Some(text);
+                break;
+            }
+        }
+    }
+
+    if let Some(text) = content {
+        for line in text.lines() {
+            let t = line.trim();
+            if t.starts_with("# ") {
+                name = t.trim_start_matches("# ").trim().to_string();
+                break;
+            }
+        }
+    }
+
+    name
+}
+
+fn run_cmd(mut cmd: Command, label: &str) -> Result<(), String> {
+    let status = cmd
+        .status()
+        .map_err(|e| format!("Failed to run {label}: {e}"))?;
+    if !status.success() {
+        return Err(format!("{label} exited with status: {status}"));
+    }
+    Ok(())
+}
+
+fn install_zip(
+    url: &str,
+    dest: &Path,
+    expected_sha256: Option<&str>,
+    progress: &dyn ProgressReporter<InstallProgressLog>,
+) -> Result<String, String> {
+    let tmp_dir = std::env::temp_dir().join(format!("skills-manager-zip-{}", generate_id()));
+    ensure_dir(&tmp_dir)?;
+    let zip_path = tmp_dir.join("download.zip");
+    let extract_dir = tmp_dir.join("extract");
+    ensure_dir(&extract_dir)?;
+
+    let options = DownloadOptions {
+        expected_sha256: expected_sha256.map(str::to_string),
+        ..DownloadOptions::default()
+    };
+    let result = download_to_file(url, &zip_path, &options, progress)
+        .map_err(String::from)
+        .and_then(|summary| {
+            extract_zip(&zip_path, &extract_dir, &ExtractLimits::default())?;
+            let root = unwrap_single_top_dir(&extract_dir)?;
+            copy_dir_all(&root, dest)?;
+            Ok(summary.sha256)
+        });
+    let _ = fs::remove_dir_all(&tmp_dir);
+    result
+}
+
+/// What a fetch resolved to: the commit for git sources, the archive
+/// checksum for zip sources.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct FetchedRevision {
+    pub(crate) commit: Option<String>,
+    pub(crate) checksum: Option<String>,
+}
+
+/// Fetches `source` into `dest`. A zip archive whose SHA-256 differs from
+/// `expected_checksum` is rejected before it is extracted.
+fn fetch_source(
+    source: &InstallSource,
+    dest: &Path,
+    expected_checksum: Option<&str>,
+    progress: &dyn ProgressReporter<InstallProgressLog>,
+) -> Result<FetchedRevision, String> {
+    match source {
+        InstallSource::Zip { url } => {
+            install_zip(url, dest, expected_checksum, progress).map(|checksum| FetchedRevision {
+                commit: None,
+                checksum: Some(checksum),
+            })
+        }
+        InstallSource::Git { url, reference, .. } => {
+            git::fetch_checkout(url, reference.as_deref(), dest, progress).map(|commit| {
+                FetchedRevision {
+                    commit: Some(commit),
+                    checksum: None,
+                }
+            })
+        }
+    }
+}
+
+fn candidate_post_install_sources(skill_dir_name: &str) -> Vec<PathBuf> {
+    [
+        "~/.agents/skills", // npx skills add -g installs here
+    ]
+    .iter()
+    .map(|root| expand_tilde(root).join(skill_dir_name))
+    .collect()
+}
+
+pub fn install_skill(repo_url: &str, storage_path: &str) -> Result<Skill, String> {
+    install_skill_with_progress(repo_url, storage_path, &NoopProgress)
+}
+
+pub fn install_skill_with_progress(
+    repo_url: &str,
+    storage_path: &str,
+    progress: &dyn ProgressReporter<InstallProgressLog>,
+) -> Result<Skill, String> {
+    install_source(
+        &parse_install_source(repo_url),
+        repo_url,
+        storage_path,
+        progress,
+    )
+}
+
+/// A skill found inside a fetched source.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceSkill {
+    /// Directory of the skill relative to the inspected URL; empty when the
+    /// source itself is the skill.
+    pub subpath: String,
+    pub name: String,
+    pub metadata: Option<SkillMetadata>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceInspection {
+    pub url: String,
+    pub commit: Option<String>,
+    pub skills: Vec<SourceSkill>,
+}
+
+/// A source fetched into a staging directory under the store.
+pub(crate) struct StagedSource {
+    pub(crate) staging: PathBuf,
+    /// The directory the URL points at, inside `staging`.
+    pub(crate) root: PathBuf,
+    pub(crate) revision: FetchedRevision,
+    /// Never committed: dropping it removes `staging`, and a crash leaves it
+    /// for `recover_store`.
+    _journal: Journal,
+}
+
+pub(crate) fn stage_source(
+    source: &InstallSource,
+    store_dir: &Path,
+    expected_checksum: Option<&str>,
+    progress: &dyn ProgressReporter<InstallProgressLog>,
+) -> Result<StagedSource, String> {
+    let mut journal = Journal::begin(store_dir, "stage")?;
+    let staging = journal.temp_dir("install")?;
+    let revision = fetch_source(source, &staging, expected_checksum, progress)?;
+    let root = source_subdir(&staging, source.subpath())?;
+    Ok(StagedSource {
+        staging,
+        root,
+        revision,
+        _journal: journal,
+    })
+}
+
+fn join_subpath(base: Option<&str>, rel: &str) -> Option<String> {
+    match (base, rel) {
+        (None, "") => None,
+        (None, rel) => Some(rel.to_string()),
+        (Some(base), "") => Some(base.to_string()),
+        (Some(base), rel) => Some(format!("{base}/{rel}")),
+    }
+}
+
+/// Skill directories below `root`, keyed by their `/`-separated path
+/// relative to it.
+fn source_skill_dirs(root: &Path) -> Vec<(String, PathBuf)> {
+    let mut dirs: Vec<(String, PathBuf)> = find_skill_roots(root)
+        .into_iter()
+        .map(|dir| {
+            let rel = dir
+                .strip_prefix(root)
+                .unwrap_or(&dir)
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().to_string())
+                .collect::<Vec<_>>()
+                .join("/");
+            (rel, dir)
+        })
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+fn fallback_skill_name(url: &str, subpath: Option<&str>) -> String {
+    let url = url.split('#').next().unwrap_or(url);
+    subpath
+        .unwrap_or(url)
+        .split('/')
+        .next_back()
+        .unwrap_or("skill")
+        .trim_end_matches(".git")
+        .trim_end_matches(".zip")
+        .to_string()
+}
+
+/// Moves `dir` out of staging into the store as a new skill, recording
+/// where it came from.
+fn adopt_staged_dir(
+    store_dir: &Path,
+    dir: &Path,
+    fallback_name: &str,
+    source: &InstallSource,
+    source_url: &str,
+    revision: &FetchedRevision,
+    source_subpath: Option<String>,
+) -> Result<Skill, String> {
+    let meta_name = parse_name_from_dir(dir, fallback_name);
+    let dir_name = unique_skill_dir_name(store_dir, &meta_name);
+    adopt_staged_dir_as(
+        store_dir,
+        dir,
+        &dir_name,
+        source,
+        source_url,
+        revision,
+        source_subpath,
+    )
+}
+
+/// Like [`adopt_staged_dir`], but stores the skill under `dir_name`, which
+/// must not be taken yet.
+pub(crate) fn adopt_staged_dir_as(
+    store_dir: &Path,
+    dir: &Path,
+    dir_name: &str,
+    source: &InstallSource,
+    source_url: &str,
+    revision: &FetchedRevision,
+    source_subpath: Option<String>,
+) -> Result<Skill, String> {
+    let final_dest = store_dir.join(dir_name);
+    let mut journal = Journal::begin(store_dir, &format!("install {dir_name}"))?;
+    journal.log(JournalStep::Create {
+        path: final_dest.clone(),
+    })?;
+
+    // A linked subdirectory would dangle once staging is gone, so copy it.
+    if dir.is_symlink() || fs::rename(dir, &final_dest).is_err() {
+        copy_dir_all(dir, &final_dest)?;
+    }
+
+    let now = now_iso();
+
+    let skill = Skill {
+        id: generate_id(),
+        name: dir_name.to_string(),
+        source_url: Some(source_url.to_string()),
+        source_commit: revision.commit.clone(),
+        source_subpath,
+        source_kind: Some(source.kind()),
+        source_ref: source.reference().map(str::to_string),
+        source_checksum: revision.checksum.clone(),
+        installed_at: Some(now.clone()),
+        enabled_agents: vec![],
+        last_sync: Some(now.clone()),
+        last_update: Some(now),
+        last_sync_digest: None,
+    };
+    journal.log(JournalStep::Record {
+        name: skill.name.clone(),
+        before: None,
+    })?;
+    update_manifest(store_dir, |manifest| manifest.upsert(skill.clone()))?;
+    journal.commit()?;
+    Ok(skill)
+}
+
+fn report_installed(progress: &dyn ProgressReporter<InstallProgressLog>, names: &[&str]) {
+    progress.report(InstallProgressLog {
+        id: "done".to_string(),
+        label: format!("安装完成: {}", names.join(", ")),
+        status: "success".to_string(),
+        progress: 100.0,
+        bytes: None,
+        total_bytes: None,
+    });
+}
+
+fn install_source(
+    source: &InstallSource,
+    repo_url: &str,
+    storage_path: &str,
+    progress: &dyn ProgressReporter<InstallProgressLog>,
+) -> Result<Skill, String> {
+    let store_dir = manager_store_root(storage_path)?;
+    let staged = stage_source(source, &store_dir, None, progress)?;
+    let url = normalize_install_url(repo_url);
+    let skill = adopt_staged_dir(
+        &store_dir,
+        &staged.root,
+        &fallback_skill_name(&url, source.subpath()),
+        source,
+        repo_url,
+        &staged.revision,
+        source.subpath().map(str::to_string),
+    )?;
+    report_installed(progress, &[&skill.name]);
+    Ok(skill)
+}
+
+/// Fetches `url` into a staging directory and lists every skill it contains,
+/// without installing anything.
+pub fn inspect_source(
+    url: &str,
+    storage_path: &str,
+    progress: &dyn ProgressReporter<InstallProgressLog>,
+) -> Result<SourceInspection, String> {
+    let source = parse_install_source(url);
+    let store_dir = manager_store_root(storage_path)?;
+    let staged = stage_source(&source, &store_dir, None, progress)?;
+    let normalized = normalize_install_url(url);
+
+    let skills = source_skill_dirs(&staged.root)
+        .into_iter()
+        .map(|(subpath, dir)| {
+            let fallback = fallback_skill_name(
+                &normalized,
+                join_subpath(source.subpath(), &subpath).as_deref(),
+            );
+            SourceSkill {
+                name: parse_name_from_dir(&dir, &fallback),
+                metadata: read_skill_metadata(&dir),
+                subpath,
+            }
+        })
+        .collect();
+
+    Ok(SourceInspection {
+        url: url.to_string(),
+        commit: staged.revision.commit.clone(),
+        skills,
+    })
+}
+
+/// Installs the skills at `selected` (subpaths as returned by
+/// [`inspect_source`]) as separate store entries from one fetch of `url`.
+pub fn install_from_source(
+    url: &str,
+    selected: &[String],
+    storage_path: &str,
+    progress: &dyn ProgressReporter<InstallProgressLog>,
+) -> Result<Vec<Skill>, String> {
+    if selected.is_empty() {
+        return Err("No skills selected".to_string());
+    }
+    let source = parse_install_source(url);
+    let store_dir = manager_store_root(storage_path)?;
+    let staged = stage_source(&source, &store_dir, None, progress)?;
+    let normalized = normalize_install_url(url);
+
+    let available = source_skill_dirs(&staged.root);
+    let mut chosen = vec![];
+    for subpath in selected {
+        let subpath = subpath.trim_matches('/');
+        let (rel, dir) = available
+            .iter()
+            .find(|(rel, _)| rel == subpath)
+            .ok_or_else(|| format!("No skill found at {subpath:?} in {url}"))?;
+        if !chosen.iter().any(|(seen, _)| *seen == rel) {
+            chosen.push((rel, dir));
+        }
+    }
+
+    let mut skills = vec![];
+    for (rel, dir) in chosen {
+        let subpath = join_subpath(source.subpath(), rel);
+        skills.push(adopt_staged_dir(
+            &store_dir,
+            dir,
+            &fallback_skill_name(&normalized, subpath.as_deref()),
+            &source,
+            url,
+            &staged.revision,
+            subpath,
+        )?);
+    }
+    let names: Vec<&str> = skills.iter().map(|skill| skill.name.as_str()).collect();
+    report_installed(progress, &names);
+    Ok(skills)
+}
+
+pub fn reinstall_skill(
+    skill_id: &str,
+    skill_name: &str,
+    repo_url: &str,
+    enabled_agents: Vec<String>,
+    storage_path: &str,
+) -> Result<Skill, String> {
+    if repo_url.trim().is_empty() {
+        return Err("repoUrl is empty".to_string());
+    }
+
+    let store_dir = manager_store_root(storage_path)?;
+    ensure_dir(&store_dir)?;
+
+    let safe_name = safe_skill_dir_name(skill_name);
+    if safe_name.trim().is_empty() {
+        return Err("skillName is empty".to_string());
+    }
+
+    let url = normalize_install_url(repo_url);
+
+    let mut npx = Command::new("npx");
+    npx.arg("skills")
+        .arg("add")
+        .arg(&url)
+        .arg("--skill")
+        .arg(&safe_name)
+        .arg("-g")
+        .arg("-y");
+    run_cmd(npx, "npx skills add")?;
+
+    let mut journal = Journal::begin(&store_dir, &format!("reinstall {safe_name}"))?;
+    let temp_dest = journal.temp_dir("reinstall")?;
+
+    let mut copied = false;
+    for src in candidate_post_install_sources(&safe_name) {
+        if src.exists() && src.is_dir() {
+            copy_dir_all(&src, &temp_dest)?;
+            copied = true;
+            break;
+        }
+    }
+
+    if !copied {
+        return Err(format!(
+            "Installed skill directory not found under known locations (expected ~/.agents/skills/{0})",
+            safe_name
+        ));
+    }
+
+    let final_dest = store_dir.join(&safe_name);
+    journal.move_aside(&final_dest, "reinstall")?;
+    journal.log(JournalStep::Create {
+        path: final_dest.clone(),
+    })?;
+
+    if let Err(_e) = fs::rename(&temp_dest, &final_dest) {
+        copy_dir_all(&temp_dest, &final_dest)?;
+    }
+
+    let now = now_iso();
+
+    let skill = Skill {
+        id: skill_id.to_string(),
+        name: safe_name,
+        source_url: Some(repo_url.to_string()),
+        enabled_agents,
+        last_sync: Some(now.clone()),
+        last_update: Some(now.clone()),
+        last_sync_digest: None,
+        source_commit: None,
+        source_subpath: None,
+        source_kind: Some(SourceKind::Npx),
+        source_ref: None,
+        source_checksum: None,
+        installed_at: Some(now),
+    };
+    journal.log(JournalStep::Record {
+        name: skill.name.clone(),
+        before: load_manifest(&store_dir)?
+            .get(&skill.name)
+            .cloned()
+            .map(Box::new),
+    })?;
+    // Keep the digest of the last distribution so the old agent copies read as stale.
+    let skill = update_manifest(&store_dir, |manifest| {
+        let mut skill = skill;
+        skill.last_sync_digest = manifest
+            .get(&skill.name)
+            .and_then(|existing| existing.last_sync_digest.clone());
+        manifest.upsert(skill.clone());
+        skill
+    })?;
+    journal.commit()?;
+    Ok(skill)
+}
+
+pub fn install_skill_cli(
+    repo_url: &str,
+    skill_name: &str,
+    storage_path: &str,
+) -> Result<Skill, String> {
+    if skill_name.trim().is_empty() {
+        return Err("skillName is empty".to_string());
+    }
+
+    let url = normalize_install_url(repo_url);
+    let desired_name = safe_skill_dir_name(skill_name);
+
+    let mut npx = Command::new("npx");
+    npx.arg("skills")
+        .arg("add")
+        .arg(&url)
+        .arg("--skill")
+        .arg(&desired_name)
+        .arg("-g")
+        .arg("-y");
+    run_cmd(npx, "npx skills add")?;
+
+    let store_root = manager_store_root(storage_path)?;
+    let store_dest = store_root.join(&desired_name);
+    let mut journal = Journal::begin(&store_root, &format!("install {desired_name}"))?;
+    let before = load_manifest(&store_root)?
+        .get(&desired_name)
+        .cloned()
+        .map(Box::new);
+    if !store_dest.exists() {
+        journal.log(JournalStep::Create {
+            path: store_dest.clone(),
+        })?;
+    }
+
+    let mut copied = false;
+    for src in candidate_post_install_sources(&desired_name) {
+        if src.exists() && src.is_dir() {
+            copy_dir_all(&src, &store_dest)?;
+            copied = true;
+            break;
+        }
+    }
+
+    if !copied {
+        return Err(format!(
+            "Installed skill directory not found under known locations (expected ~/.agents/skills/{0})",
+            desired_name
+        ));
+    }
+
+    let now = now_iso();
+
+    let skill = Skill {
+        id: generate_id(),
+        name: desired_name,
+        source_url: Some(repo_url.to_string()),
+        enabled_agents: vec![],
+        last_sync: Some(now.clone()),
+        last_update: Some(now.clone()),
+        last_sync_digest: None,
+        source_commit: None,
+        source_subpath: None,
+        source_kind: Some(SourceKind::Npx),
+        source_ref: None,
+        source_checksum: None,
+        installed_at: Some(now),
+    };
+    journal.log(JournalStep::Record {
+        name: skill.name.clone(),
+        before,
+    })?;
+    update_manifest(&store_root, |manifest| manifest.upsert(skill.clone()))?;
+    journal.commit()?;
+    Ok(skill)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_install_url_normalizes_github_urls() {
+        assert_eq!(
+            normalize_install_url("github.com/foo/bar"),
+            "https://github.com/foo/bar"
+        );
+        assert_eq!(
+            normalize_install_url("foo/bar"),
+            "https://github.com/foo/bar"
+        );
+        assert_eq!(
+            normalize_install_url("https://github.com/foo/bar/"),
+            "https://github.com/foo/bar"
+        );
+        assert_eq!(
+            normalize_install_url("https://github.com/affaan-m/everything-claude-code/tree/main/skills/security-review/"),
+            "https://github.com/affaan-m/everything-claude-code/tree/main/skills/security-review"
+        );
+        assert_eq!(
+            normalize_install_url("http://example.com/x"),
+            "http://example.com/x"
+        );
+    }
+
+    #[test]
+    fn candidate_post_install_sources_prefers_agents_dir_first() {
+        let sources = candidate_post_install_sources("demo-skill");
+        assert_eq!(sources.len(), 1);
+        assert!(sources[0]
+            .to_string_lossy()
+            .contains("/.agents/skills/demo-skill"));
+    }
+
+    #[test]
+    fn parse_name_from_dir_prefers_frontmatter_name() {
+        let dir = std::env::temp_dir().join(format!("skills-manager-parse-name-{}", generate_id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("SKILL.md"),
+            "---\nname: pdf-tools\ndescription: x\n---\n\n# PDF Tools\n",
+        )
+        .unwrap();
+        assert_eq!(parse_name_from_dir(&dir, "repo"), "pdf-tools");
+
+        fs::write(dir.join("SKILL.md"), "# PDF Tools\n").unwrap();
+        assert_eq!(parse_name_from_dir(&dir, "repo"), "PDF Tools");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn install_skill_downloads_and_unwraps_zip() {
+        use sha2::Digest;
+        use std::io::Write;
+
+        let tmp =
+            std::env::temp_dir().join(format!("skills-manager-install-zip-{}", generate_id()));
+        let store = tmp.join("store");
+        fs::create_dir_all(&store).unwrap();
+
+        let mut body = std::io::Cursor::new(Vec::new());
+        let mut zip = zip::ZipWriter::new(&mut body);
+        zip.start_file(
+            "demo-main/SKILL.md",
+            zip::write::SimpleFileOptions::default(),
+        )
+        .unwrap();
+        zip.write_all(b"---\nname: demo\ndescription: x\n---\n")
+            .unwrap();
+        zip.finish().unwrap();
+
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let port = server.server_addr().to_ip().unwrap().port();
+        let body = body.into_inner();
+        let checksum = format!("{:x}", sha2::Sha256::digest(&body));
+        std::thread::spawn(move || {
+            if let Ok(request) = server.recv() {
+                let _ = request.respond(tiny_http::Response::from_data(body));
+            }
+        });
+
+        let url = format!("http://127.0.0.1:{port}/demo.zip");
+        let skill = install_skill(&url, &store.to_string_lossy()).unwrap();
+        assert_eq!(skill.name, "demo");
+        assert!(store.join("demo").join("SKILL.md").is_file());
+        assert_eq!(skill.source_kind, Some(SourceKind::Zip));
+        assert_eq!(skill.source_checksum, Some(checksum));
+        assert!(skill.installed_at.is_some());
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn parse_install_source_splits_github_tree_and_blob_urls() {
+        assert_eq!(
+            parse_install_source("github.com/foo/bar/tree/main/skills/security-review/"),
+            InstallSource::Git {
+                url: "https://github.com/foo/bar".to_string(),
+                reference: Some("main".to_string()),
+                subpath: Some("skills/security-review".to_string()),
+            }
+        );
+        assert_eq!(
+            parse_install_source("https://github.com/foo/bar/blob/v1.2/skills/pdf/SKILL.md"),
+            InstallSource::Git {
+                url: "https://github.com/foo/bar".to_string(),
+                reference: Some("v1.2".to_string()),
+                subpath: Some("skills/pdf".to_string()),
+            }
+        );
+        assert_eq!(
+            parse_install_source("https://github.com/foo/bar/tree/dev"),
+            InstallSource::Git {
+                url: "https://github.com/foo/bar".to_string(),
+                reference: Some("dev".to_string()),
+                subpath: None,
+            }
+        );
+        assert_eq!(
+            parse_install_source("foo/bar"),
+            InstallSource::Git {
+                url: "https://github.com/foo/bar".to_string(),
+                reference: None,
+                subpath: None,
+            }
+        );
+        assert!(matches!(
+            parse_install_source("https://example.com/x.zip"),
+            InstallSource::Zip { .. }
+        ));
+    }
+
+    #[test]
+    fn install_source_installs_only_the_subdirectory() {
+        let tmp =
+            std::env::temp_dir().join(format!("skills-manager-install-sub-{}", generate_id()));
+        let upstream = tmp.join("upstream");
+        let skill_dir = upstream.join("skills").join("pdf");
+        fs::create_dir_all(&skill_dir).unwrap();
+        fs::write(upstream.join("README.md"), "# Pack\n").unwrap();
+        fs::write(
+            skill_dir.join("SKILL.md"),
+            "---\nname: pdf-tools\ndescription: x\n---\n",
+        )
+        .unwrap();
+        let url = crate::git::commit_dir_as_repo(&upstream);
+        let store = tmp.join("store");
+
+        let source = InstallSource::Git {
+            url,
+            reference: Some("main".to_string()),
+            subpath: Some("skills/pdf".to_string()),
+        };
+        let skill =
+            install_source(&source, "pack", &store.to_string_lossy(), &NoopProgress).unwrap();
+        assert_eq!(skill.name, "pdf-tools");
+        assert!(skill.source_commit.is_some());
+        assert_eq!(skill.source_kind, Some(SourceKind::Git));
+        assert_eq!(skill.source_ref.as_deref(), Some("main"));
+        assert_eq!(skill.source_subpath.as_deref(), Some("skills/pdf"));
+        assert!(store.join("pdf-tools").join("SKILL.md").is_file());
+        assert!(!store.join("pdf-tools").join("README.md").exists());
+
+        let missing = InstallSource::Git {
+            url: crate::git::commit_dir_as_repo(&upstream),
+            reference: None,
+            subpath: Some("skills/nope".to_string()),
+        };
+        let err =
+            install_source(&missing, "pack", &store.to_string_lossy(), &NoopProgress).unwrap_err();
+        assert!(err.contains("not found"), "{err}");
+        let leftovers = fs::read_dir(&store)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().starts_with(".tmp-install-"))
+            .count();
+        assert_eq!(leftovers, 0);
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn inspect_and_install_skill_pack() {
+        let tmp = std::env::temp_dir().join(format!("skills-manager-pack-{}", generate_id()));
+        let upstream = tmp.join("upstream");
+        for (dir, name) in [("skills/alpha", "alpha"), ("skills/beta", "beta")] {
+            fs::create_dir_all(upstream.join(dir)).unwrap();
+            fs::write(
+                upstream.join(dir).join("SKILL.md"),
+                format!("---\nname: {name}\ndescription: the {name} skill\n---\n"),
+            )
+            .unwrap();
+        }
+        fs::create_dir_all(upstream.join("docs")).unwrap();
+        fs::write(upstream.join("docs").join("guide.md"), "# Guide\n").unwrap();
+        let url = crate::git::commit_dir_as_repo(&upstream);
+        let store = tmp.join("store");
+        let storage_path = store.to_string_lossy().to_string();
+
+        let inspection = inspect_source(&url, &storage_path, &NoopProgress).unwrap();
+        let found: Vec<(&str, &str)> = inspection
+            .skills
+            .iter()
+            .map(|s| (s.subpath.as_str(), s.name.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![("skills/alpha", "alpha"), ("skills/beta", "beta")]
+        );
+        assert_eq!(
+            inspection.skills[1]
+                .metadata
+                .as_ref()
+                .and_then(|m| m.description.as_deref()),
+            Some("the beta skill")
+        );
+        assert!(inspection.commit.is_some());
+        assert!(!store.join("alpha").exists());
+
+        let selected = ["skills/beta".to_string(), "skills/alpha".to_string()];
+        let skills = install_from_source(&url, &selected, &storage_path, &NoopProgress).unwrap();
+        let installed: Vec<(&str, Option<&str>)> = skills
+            .iter()
+            .map(|s| (s.name.as_str(), s.source_subpath.as_deref()))
+            .collect();
+        assert_eq!(
+            installed,
+            vec![
+                ("beta", Some("skills/beta")),
+                ("alpha", Some("skills/alpha"))
+            ]
+        );
+        assert!(skills.iter().all(|s| s.source_url.as_deref() == Some(&url)));
+        assert!(store.join("alpha").join("SKILL.md").is_file());
+        assert!(!store.join("docs").exists());
+
+        let err = install_from_source(&url, &["docs".to_string()], &storage_path, &NoopProgress)
+            .unwrap_err();
+        assert!(err.contains("No skill found"), "{err}");
+        let staged = fs::read_dir(&store)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .any(|e| e.file_name().to_string_lossy().starts_with(".tmp-install-"));
+        assert!(!staged);
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn normalize_install_url_keeps_ref_pins() {
+        assert_eq!(
+            normalize_install_url("foo/bar@v1.2.0"),
+            "https://github.com/foo/bar#v1.2.0"
+        );
+        assert_eq!(
+            normalize_install_url("github.com/foo/bar@feature/x"),
+            "https://github.com/foo/bar#feature/x"
+        );
+        assert_eq!(
+            normalize_install_url("https://github.com/foo/bar/#abc123"),
+            "https://github.com/foo/bar#abc123"
+        );
+        assert!(!normalize_install_url("git@github.com:foo/bar.git").contains('#'));
+        assert_eq!(
+            parse_install_source("foo/bar@v1"),
+            InstallSource::Git {
+                url: "https://github.com/foo/bar".to_string(),
+                reference: Some("v1".to_string()),
+                subpath: None,
+            }
+        );
+        assert_eq!(
+            parse_install_source("https://github.com/foo/bar/tree/main/skills/pdf#v2").reference(),
+            Some("v2")
+        );
+    }
+
+    #[test]
+    fn install_skill_honors_pinned_tag() {
+        let tmp = std::env::temp_dir().join(format!("skills-manager-pin-{}", generate_id()));
+        let upstream = tmp.join("upstream");
+        fs::create_dir_all(&upstream).unwrap();
+        fs::write(upstream.join("SKILL.md"), "---\nname: pinned\n---\nv1\n").unwrap();
+        let url = crate::git::commit_dir_as_repo(&upstream);
+        let repo = git2::Repository::open(&upstream).unwrap();
+        let v1 = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.tag_lightweight("v1", v1.as_object(), false).unwrap();
+        fs::write(upstream.join("SKILL.md"), "---\nname: pinned\n---\nv2\n").unwrap();
+        crate::git::commit_dir_as_repo(&upstream);
+
+        let store = tmp.join("store");
+        let skill = install_skill(&format!("{url}#v1"), &store.to_string_lossy()).unwrap();
+        assert_eq!(skill.source_ref.as_deref(), Some("v1"));
+        assert_eq!(skill.source_commit, Some(v1.id().to_string()));
+        assert!(fs::read_to_string(store.join("pinned").join("SKILL.md"))
+            .unwrap()
+            .ends_with("v1\n"));
+
+        let manifest = crate::manifest::load_manifest(&store).unwrap();
+        let recorded = manifest.get("pinned").unwrap();
+        assert_eq!(recorded.source_ref.as_deref(), Some("v1"));
+        assert_eq!(recorded.source_kind, Some(SourceKind::Git));
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+}
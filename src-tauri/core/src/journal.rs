@@ -0,0 +1,700 @@
+//! Write-ahead journal for store operations that touch more than one path.
+//!
+//! Each operation keeps `<store>/.skillsm/journal/<id>.json` and logs every
+//! step there before performing it. A finished operation commits and deletes
+//! its journal, a failed one is rolled back in-process, and one cut short by a
+//! crash is rolled back, or for distribution completed, by [`recover_store`]
+//! on the next start.
+//!
+//! While an operation runs, its process holds an exclusive lock on
+//! `<id>.lock` next to the journal, which tells `recover_store` that the
+//! operation is still live. The OS releases the lock when the process dies.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::distributor::sync_one_skill;
+use crate::manifest::update_manifest;
+use crate::models::{AgentInfo, Skill};
+use crate::utils::{
+    agent_roots, ensure_dir, generate_id, is_staging_sibling, move_dir, now_iso,
+    remove_dir_if_exists, remove_path_if_exists, safe_skill_dir_name, write_file_atomic,
+};
+
+const JOURNAL_DIR: &str = "journal";
+/// Scratch directories in the store root start with this prefix.
+pub const TEMP_PREFIX: &str = ".tmp-";
+/// Staging siblings are not journaled; one untouched for this long is taken
+/// to be left over from a crash rather than in use by another process.
+const STALE_STAGING_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// A skill and the agents it is being distributed to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DistributeTarget {
+    pub name: String,
+    pub enabled_agents: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "step", rename_all = "snake_case")]
+pub enum JournalStep {
+    /// A scratch directory, removed whatever the outcome.
+    Temp { path: PathBuf },
+    /// `path` is renamed to `backup`; rolling back moves it back.
+    MoveAside { path: PathBuf, backup: PathBuf },
+    /// `path` is created; rolling back removes it.
+    Create { path: PathBuf },
+    /// `from` is moved to `to`, which only appears once complete; rolling
+    /// back moves it back.
+    Move { from: PathBuf, to: PathBuf },
+    /// The manifest record of `name` before the operation changed it.
+    Record {
+        name: String,
+        before: Option<Box<Skill>>,
+    },
+    /// Skills distributed to agents. Agent copies are not backed up, so an
+    /// interrupted distribution is completed rather than undone.
+    Distribute {
+        skills: Vec<DistributeTarget>,
+        agents: Vec<AgentInfo>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JournalRecord {
+    id: String,
+    operation: String,
+    pid: u32,
+    started_at: String,
+    /// Set once the operation's result is in place; only cleanup is left.
+    committed: bool,
+    steps: Vec<JournalStep>,
+}
+
+/// An open journal. Dropping it without [`Journal::commit`] rolls the logged
+/// steps back.
+pub(crate) struct Journal {
+    store_root: PathBuf,
+    path: PathBuf,
+    record: JournalRecord,
+    closed: bool,
+    lock: Option<fs::File>,
+}
+
+fn journal_dir(store_root: &Path) -> PathBuf {
+    store_root.join(".skillsm").join(JOURNAL_DIR)
+}
+
+fn lock_path(journal_path: &Path) -> PathBuf {
+    journal_path.with_extension("lock")
+}
+
+/// Whether the process that wrote the journal at `path` is still running.
+fn owner_alive(path: &Path) -> bool {
+    let Ok(file) = fs::OpenOptions::new().write(true).open(lock_path(path)) else {
+        return false;
+    };
+    matches!(file.try_lock(), Err(fs::TryLockError::WouldBlock))
+}
+
+/// Deletes a journal and its lock file.
+fn remove_journal(path: &Path) -> Result<(), String> {
+    fs::remove_file(path)
+        .map_err(|e| format!("Failed to remove journal {}: {e}", path.display()))?;
+    let _ = fs::remove_file(lock_path(path));
+    Ok(())
+}
+
+impl Journal {
+    pub(crate) fn begin(store_root: &Path, operation: &str) -> Result<Self, String> {
+        let dir = journal_dir(store_root);
+        ensure_dir(&dir)?;
+        let id = generate_id();
+        let path = dir.join(format!("{id}.json"));
+        let lock_file = lock_path(&path);
+        let lock = fs::File::create(&lock_file)
+            .map_err(|e| format!("Failed to create {}: {e}", lock_file.display()))?;
+        lock.try_lock()
+            .map_err(|e| format!("Failed to lock {}: {e}", lock_file.display()))?;
+        let journal = Journal {
+            store_root: store_root.to_path_buf(),
+            path,
+            record: JournalRecord {
+                id,
+                operation: operation.to_string(),
+                pid: std::process::id(),
+                started_at: now_iso(),
+                committed: false,
+                steps: vec![],
+            },
+            closed: false,
+            lock: Some(lock),
+        };
+        journal.write()?;
+        Ok(journal)
+    }
+
+    fn write(&self) -> Result<(), String> {
+        let text = serde_json::to_string_pretty(&self.record)
+            .map_err(|e| format!("Failed to serialize journal: {e}"))?;
+        write_file_atomic(&self.path, text.as_bytes())
+    }
+
+    /// Persists `step`; call it before performing the step.
+    pub(crate) fn log(&mut self, step: JournalStep) -> Result<(), String> {
+        self.record.steps.push(step);
+        self.write()
+    }
+
+    /// A scratch path in the store root that nothing uses yet.
+    fn fresh_temp_path(&self, purpose: &str) -> Result<PathBuf, String> {
+        let path = self
+            .store_root
+            .join(format!("{TEMP_PREFIX}{purpose}-{}", generate_id()));
+        if fs::symlink_metadata(&path).is_ok() {
+            return Err(format!("Scratch path {} already exists", path.display()));
+        }
+        Ok(path)
+    }
+
+    /// Logs and returns a fresh scratch directory path in the store root.
+    /// The directory itself is not created.
+    pub(crate) fn temp_dir(&mut self, purpose: &str) -> Result<PathBuf, String> {
+        let path = self.fresh_temp_path(purpose)?;
+        self.log(JournalStep::Temp { path: path.clone() })?;
+        Ok(path)
+    }
+
+    /// Renames `path` to a scratch directory so it can be replaced, and
+    /// returns where it went. Does nothing when `path` does not exist.
+    pub(crate) fn move_aside(
+        &mut self,
+        path: &Path,
+        purpose: &str,
+    ) -> Result<Option<PathBuf>, String> {
+        if fs::symlink_metadata(path).is_err() {
+            return Ok(None);
+        }
+        let backup = self.fresh_temp_path(purpose)?;
+        self.log(JournalStep::MoveAside {
+            path: path.to_path_buf(),
+            backup: backup.clone(),
+        })?;
+        if let Err(e) = fs::rename(path, &backup) {
+            // Nothing moved, so there is nothing to move back. Should the
+            // step stay on disk, its backup never exists and rollback skips it.
+            self.record.steps.pop();
+            let _ = self.write();
+            return Err(format!("Failed to move aside {}: {e}", path.display()));
+        }
+        Ok(Some(backup))
+    }
+
+    /// Moves `from` to `to`, even across filesystems.
+    pub(crate) fn move_dir(&mut self, from: &Path, to: &Path) -> Result<(), String> {
+        self.log(JournalStep::Move {
+            from: from.to_path_buf(),
+            to: to.to_path_buf(),
+        })?;
+        move_dir(from, to)
+    }
+
+    /// Marks the operation done, removes its scratch directories and deletes
+    /// the journal.
+    pub(crate) fn commit(mut self) -> Result<(), String> {
+        self.record.committed = true;
+        self.write()?;
+        self.closed = true;
+        finish(&self.record)?;
+        // Windows cannot delete a file that is still open.
+        self.lock = None;
+        remove_journal(&self.path)
+    }
+}
+
+impl Drop for Journal {
+    fn drop(&mut self) {
+        if self.closed {
+            return;
+        }
+        // A journal that cannot be rolled back stays for `recover_store`.
+        if roll_back(&self.store_root, &self.record).is_ok() {
+            self.lock = None;
+            let _ = remove_journal(&self.path);
+        }
+    }
+}
+
+/// Removes the scratch directories of a committed operation.
+fn finish(record: &JournalRecord) -> Result<(), String> {
+    for step in &record.steps {
+        match step {
+            JournalStep::Temp { path } => remove_dir_if_exists(path)?,
+            JournalStep::MoveAside { backup, .. } => remove_dir_if_exists(backup)?,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Undoes the logged steps, newest first. Distribution steps are left alone.
+fn roll_back(store_root: &Path, record: &JournalRecord) -> Result<(), String> {
+    for step in record.steps.iter().rev() {
+        match step {
+            JournalStep::Temp { path } | JournalStep::Create { path } => {
+                remove_dir_if_exists(path)?
+            }
+            JournalStep::MoveAside { path, backup } => {
+                // The backup path was fresh, so it exists only if the rename happened.
+                if fs::symlink_metadata(backup).is_ok() {
+                    remove_dir_if_exists(path)?;
+                    fs::rename(backup, path)
+                        .map_err(|e| format!("Failed to move back {}: {e}", backup.display()))?;
+                }
+            }
+            JournalStep::Move { from, to } => {
+                // Whatever is left at `from` is the rest of a copy that
+                // completed at `to`.
+                if fs::symlink_metadata(to).is_ok() {
+                    remove_dir_if_exists(from)?;
+                    move_dir(to, from)?;
+                }
+            }
+            JournalStep::Record { name, before } => {
+                update_manifest(store_root, |manifest| match before {
+                    Some(skill) => manifest.upsert(skill.as_ref().clone()),
+                    None => {
+                        manifest.remove(name);
+                    }
+                })?;
+            }
+            JournalStep::Distribute { .. } => {}
+        }
+    }
+    Ok(())
+}
+
+/// Redistributes the skills of an interrupted distribution.
+fn complete(store_root: &Path, record: &JournalRecord) -> Result<(), String> {
+    for step in &record.steps {
+        if let JournalStep::Distribute { skills, agents } = step {
+            for target in skills {
+                if store_root.join(safe_skill_dir_name(&target.name)).is_dir() {
+                    sync_one_skill(store_root, &target.name, &target.enabled_agents, agents)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The directories below a skill root (the store or an agent root) that
+/// staging siblings appear in: the root itself, next to its skill dirs, and
+/// each skill dir, next to a SKILL.md rewritten in place. Dot dirs such as the
+/// trash and snapshots are left out, and so are links into the store.
+fn staging_dirs(root: &Path) -> Vec<PathBuf> {
+    let mut dirs = vec![root.to_path_buf()];
+    let Ok(entries) = fs::read_dir(root) else {
+        return dirs;
+    };
+    for entry in entries.flatten() {
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+        if !hidden && entry.file_type().is_ok_and(|kind| kind.is_dir()) {
+            dirs.push(entry.path());
+        }
+    }
+    dirs
+}
+
+/// Removes the stale staging siblings directly inside `dir`.
+fn sweep_staging(dir: &Path, report: &mut RecoveryReport) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if !is_staging_sibling(&entry.file_name().to_string_lossy()) {
+            continue;
+        }
+        let path = entry.path();
+        let stale = fs::symlink_metadata(&path)
+            .and_then(|meta| meta.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age >= STALE_STAGING_AGE);
+        if stale {
+            match remove_path_if_exists(&path) {
+                Ok(()) => report.removed_temp_dirs.push(path.display().to_string()),
+                Err(err) => report.failed.push(err),
+            }
+        }
+    }
+}
+
+/// What [`recover_store`] did, by operation and scratch path.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryReport {
+    pub rolled_back: Vec<String>,
+    pub completed: Vec<String>,
+    pub removed_temp_dirs: Vec<String>,
+    pub failed: Vec<String>,
+}
+
+/// Settles the journals left behind by operations whose process is gone and
+/// removes scratch directories no running operation owns.
+///
+/// Uncommitted operations are rolled back, except distributions, which are
+/// completed. A journal that cannot be settled is kept for the next attempt.
+/// Stale staging siblings (`.<name>.tmp-<id>`) in the store and in the roots
+/// of `agents` are removed as well.
+pub fn recover_store(store_root: &Path, agents: &[AgentInfo]) -> Result<RecoveryReport, String> {
+    let mut report = RecoveryReport::default();
+    let mut in_use: Vec<PathBuf> = vec![];
+
+    let dir = journal_dir(store_root);
+    let mut paths: Vec<PathBuf> = match fs::read_dir(&dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .collect(),
+        Err(_) => vec![],
+    };
+    paths.sort();
+
+    for path in paths {
+        let record = fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| {
+                serde_json::from_str::<JournalRecord>(&text).map_err(|e| e.to_string())
+            });
+        let record = match record {
+            Ok(record) => record,
+            Err(err) => {
+                report.failed.push(format!("{}: {err}", path.display()));
+                continue;
+            }
+        };
+        if record.pid == std::process::id() || owner_alive(&path) {
+            for step in &record.steps {
+                match step {
+                    JournalStep::Temp { path } => in_use.push(path.clone()),
+                    JournalStep::MoveAside { backup, .. } => in_use.push(backup.clone()),
+                    _ => {}
+                }
+            }
+            continue;
+        }
+
+        let settled = if record.committed {
+            finish(&record)
+        } else {
+            roll_back(store_root, &record).and_then(|_| complete(store_root, &record))
+        };
+        let distributed = record
+            .steps
+            .iter()
+            .any(|step| matches!(step, JournalStep::Distribute { .. }));
+        match settled {
+            Ok(()) if record.committed || distributed => {
+                let _ = remove_journal(&path);
+                report.completed.push(record.operation);
+            }
+            Ok(()) => {
+                let _ = remove_journal(&path);
+                report.rolled_back.push(record.operation);
+            }
+            Err(err) => report.failed.push(format!("{}: {err}", record.operation)),
+        }
+    }
+
+    // The manifest, settings and journals are written atomically as well.
+    let mut dirs = vec![dir.clone()];
+    dirs.extend(dir.parent().map(Path::to_path_buf));
+    dirs.extend(staging_dirs(store_root));
+    dirs.extend(
+        agents
+            .iter()
+            .flat_map(agent_roots)
+            .flat_map(|root| staging_dirs(&root)),
+    );
+    for dir in &dirs {
+        sweep_staging(dir, &mut report);
+    }
+
+    let Ok(entries) = fs::read_dir(store_root) else {
+        return Ok(report);
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let path = entry.path();
+        if !name.starts_with(TEMP_PREFIX) || !path.is_dir() || in_use.contains(&path) {
+            continue;
+        }
+        match remove_dir_if_exists(&path) {
+            Ok(()) => report.removed_temp_dirs.push(name),
+            Err(err) => report.failed.push(err),
+        }
+    }
+    report.removed_temp_dirs.sort();
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::load_manifest;
+    use crate::models::DistributionMode;
+
+    fn temp_test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("skills-manager-{name}-{}", generate_id()));
+        ensure_dir(&dir).expect("create temp dir");
+        dir
+    }
+
+    fn write_skill(store_root: &Path, name: &str, body: &str) {
+        let dir = store_root.join(name);
+        ensure_dir(&dir).unwrap();
+        fs::write(dir.join("SKILL.md"), body).unwrap();
+    }
+
+    /// Leaves the journal on disk as if the process had died mid-operation.
+    fn crash(mut journal: Journal) {
+        journal.record.pid = u32::MAX;
+        journal.write().unwrap();
+        journal.closed = true;
+    }
+
+    #[test]
+    fn dropped_journal_rolls_back_replacement() {
+        let store = temp_test_dir("journal-drop");
+        write_skill(&store, "alpha", "old");
+        update_manifest(&store, |_| ()).unwrap();
+        let before = load_manifest(&store).unwrap().get("alpha").cloned();
+
+        {
+            let mut journal = Journal::begin(&store, "update alpha").unwrap();
+            let dest = store.join("alpha");
+            journal.move_aside(&dest, "update").unwrap();
+            journal
+                .log(JournalStep::Create { path: dest.clone() })
+                .unwrap();
+            write_skill(&store, "alpha", "new");
+            journal
+                .log(JournalStep::Record {
+                    name: "alpha".to_string(),
+                    before: before.clone().map(Box::new),
+                })
+                .unwrap();
+            update_manifest(&store, |m| {
+                m.get_mut("alpha").unwrap().source_commit = Some("abc".to_string())
+            })
+            .unwrap();
+        }
+
+        assert_eq!(
+            fs::read_to_string(store.join("alpha/SKILL.md")).unwrap(),
+            "old"
+        );
+        assert!(load_manifest(&store)
+            .unwrap()
+            .get("alpha")
+            .unwrap()
+            .source_commit
+            .is_none());
+        assert_eq!(fs::read_dir(journal_dir(&store)).unwrap().count(), 0);
+        assert!(recover_store(&store, &[])
+            .unwrap()
+            .removed_temp_dirs
+            .is_empty());
+
+        let _ = fs::remove_dir_all(&store);
+    }
+
+    #[test]
+    fn rolls_back_many_paths_moved_aside_in_one_journal() {
+        let store = temp_test_dir("journal-many-aside");
+        let names = ["a", "b", "c", "d", "e", "f"];
+        for name in names {
+            write_skill(&store, name, name);
+        }
+
+        {
+            let mut journal = Journal::begin(&store, "import").unwrap();
+            let mut backups = vec![];
+            for name in names {
+                let dest = store.join(name);
+                backups.push(journal.move_aside(&dest, "import").unwrap().unwrap());
+                journal
+                    .log(JournalStep::Create { path: dest.clone() })
+                    .unwrap();
+                write_skill(&store, name, "replaced");
+            }
+            backups.sort();
+            backups.dedup();
+            assert_eq!(backups.len(), names.len());
+        }
+
+        for name in names {
+            assert_eq!(
+                fs::read_to_string(store.join(name).join("SKILL.md")).unwrap(),
+                name
+            );
+        }
+        assert!(recover_store(&store, &[])
+            .unwrap()
+            .removed_temp_dirs
+            .is_empty());
+
+        let _ = fs::remove_dir_all(&store);
+    }
+
+    #[test]
+    fn recover_store_rolls_back_crashed_install_and_removes_orphans() {
+        let store = temp_test_dir("journal-recover");
+        ensure_dir(&store.join(".tmp-reinstall-orphan")).unwrap();
+
+        let mut journal = Journal::begin(&store, "install beta").unwrap();
+        let staging = journal.temp_dir("install").unwrap();
+        write_skill(&staging, "beta", "staged");
+        let dest = store.join("beta");
+        journal
+            .log(JournalStep::Create { path: dest.clone() })
+            .unwrap();
+        write_skill(&store, "beta", "partial");
+        crash(journal);
+
+        // Another process: only its held lock tells recovery to keep out.
+        let mut live = Journal::begin(&store, "install gamma").unwrap();
+        live.record.pid = u32::MAX;
+        let live_staging = live.temp_dir("install").unwrap();
+        ensure_dir(&live_staging).unwrap();
+
+        let report = recover_store(&store, &[]).unwrap();
+        assert_eq!(report.rolled_back, vec!["install beta".to_string()]);
+        assert_eq!(
+            report.removed_temp_dirs,
+            vec![".tmp-reinstall-orphan".to_string()]
+        );
+        assert!(report.failed.is_empty());
+        assert!(!dest.exists());
+        assert!(!staging.exists());
+        assert!(live_staging.is_dir());
+
+        drop(live);
+        assert!(!live_staging.exists());
+
+        let _ = fs::remove_dir_all(&store);
+    }
+
+    #[test]
+    fn recover_store_completes_crashed_distribution() {
+        let tmp = temp_test_dir("journal-distribute");
+        let store = tmp.join("store");
+        write_skill(&store, "alpha", "# alpha\n");
+        let agent_root = tmp.join("agent");
+        let agents = vec![AgentInfo {
+            id: "agent".to_string(),
+            name: "agent".to_string(),
+            default_path: agent_root.to_string_lossy().to_string(),
+            current_path: agent_root.to_string_lossy().to_string(),
+            enabled: true,
+            icon: "test".to_string(),
+            distribution_mode: DistributionMode::Copy,
+        }];
+
+        let mut journal = Journal::begin(&store, "sync").unwrap();
+        journal
+            .log(JournalStep::Distribute {
+                skills: vec![DistributeTarget {
+                    name: "alpha".to_string(),
+                    enabled_agents: vec!["agent".to_string()],
+                }],
+                agents,
+            })
+            .unwrap();
+        crash(journal);
+
+        let report = recover_store(&store, &[]).unwrap();
+        assert_eq!(report.completed, vec!["sync".to_string()]);
+        assert!(agent_root.join("alpha/SKILL.md").is_file());
+        let record = load_manifest(&store)
+            .unwrap()
+            .get("alpha")
+            .cloned()
+            .unwrap();
+        assert_eq!(record.enabled_agents, vec!["agent".to_string()]);
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn recover_store_sweeps_stale_staging_siblings() {
+        let tmp = temp_test_dir("journal-staging");
+        let store = tmp.join("store");
+        write_skill(&store, "alpha", "# alpha\n");
+        let agent_root = tmp.join("agent");
+        let agents = vec![AgentInfo {
+            id: "agent".to_string(),
+            name: "agent".to_string(),
+            default_path: agent_root.to_string_lossy().to_string(),
+            current_path: agent_root.to_string_lossy().to_string(),
+            enabled: true,
+            icon: "test".to_string(),
+            distribution_mode: DistributionMode::Copy,
+        }];
+
+        let stale = [
+            store.join(format!(".alpha.tmp-{}", generate_id())),
+            store
+                .join("alpha")
+                .join(format!(".SKILL.md.tmp-{}", generate_id())),
+            agent_root.join(format!(".alpha.tmp-{}", generate_id())),
+        ];
+        let fresh = agent_root.join(format!(".beta.tmp-{}", generate_id()));
+        let unrelated = agent_root.join(".notes.tmp-mine");
+        // Nothing stages this deep or inside the trash and snapshots.
+        let out_of_reach = [
+            store
+                .join("alpha")
+                .join("nested")
+                .join(format!(".x.tmp-{}", generate_id())),
+            store
+                .join(".trash")
+                .join("entry")
+                .join(format!(".alpha.tmp-{}", generate_id())),
+            store
+                .join(".skillsm")
+                .join("snapshots")
+                .join(format!(".alpha.tmp-{}", generate_id())),
+        ];
+        ensure_dir(&stale[0]).unwrap();
+        fs::write(&stale[1], "half").unwrap();
+        ensure_dir(&stale[2]).unwrap();
+        ensure_dir(&fresh).unwrap();
+        for path in out_of_reach.iter().chain([&unrelated]) {
+            ensure_dir(path).unwrap();
+        }
+        let long_ago = std::time::SystemTime::now() - STALE_STAGING_AGE * 2;
+        for path in stale.iter().chain(&out_of_reach).chain([&unrelated]) {
+            fs::File::open(path)
+                .unwrap()
+                .set_modified(long_ago)
+                .unwrap();
+        }
+
+        let report = recover_store(&store, &agents).unwrap();
+        assert_eq!(report.removed_temp_dirs.len(), 3, "{report:?}");
+        assert!(stale.iter().all(|path| !path.exists()));
+        assert!(fresh.is_dir() && unrelated.is_dir());
+        assert!(out_of_reach.iter().all(|path| path.is_dir()));
+        assert!(store.join("alpha").join("SKILL.md").is_file());
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+}
@@ -0,0 +1,27 @@
+//! Business logic behind Skills Manager, free of any Tauri or GUI dependency.
+//!
+//! The desktop app and the `skillsm` CLI are thin front-ends over these modules.
+
+pub mod archive;
+pub mod backup;
+pub mod diff;
+pub mod digest;
+pub mod distributor;
+pub mod download;
+pub mod git;
+pub mod installer;
+pub mod journal;
+pub mod lint;
+pub mod lockfile;
+pub mod manifest;
+pub mod models;
+pub mod plan;
+pub mod progress;
+pub mod scanner;
+pub mod settings;
+pub mod skill_md;
+pub mod status;
+pub mod store;
+pub mod trash;
+pub mod updater;
+pub mod utils;
@@ -0,0 +1,461 @@
+use std::fs;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::manifest::load_manifest;
+use crate::skill_md::{
+    find_skill_md_path, parse_frontmatter, split_frontmatter, with_yaml_frontmatter,
+    yaml_single_quote,
+};
+use crate::utils::{manager_store_root, safe_skill_dir_name, write_file_atomic};
+
+pub const MAX_NAME_CHARS: usize = 64;
+pub const MAX_DESCRIPTION_CHARS: usize = 1024;
+
+pub const RULE_MISSING_SKILL_MD: &str = "missing-skill-md";
+pub const RULE_MISSING_FRONTMATTER: &str = "missing-frontmatter";
+pub const RULE_INVALID_FRONTMATTER: &str = "invalid-frontmatter";
+pub const RULE_MISSING_NAME: &str = "missing-name";
+pub const RULE_NAME_MISMATCH: &str = "name-mismatch";
+pub const RULE_NAME_FORMAT: &str = "name-format";
+pub const RULE_NAME_TOO_LONG: &str = "name-too-long";
+pub const RULE_MISSING_DESCRIPTION: &str = "missing-description";
+pub const RULE_DESCRIPTION_TOO_LONG: &str = "description-too-long";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostic {
+    pub rule: String,
+    pub severity: Severity,
+    /// Path relative to the skill directory.
+    pub file: String,
+    /// 1-based line in `file`, when the problem has one.
+    pub line: Option<usize>,
+    pub message: String,
+    pub fixable: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LintReport {
+    pub skill: String,
+    /// What is left after fixing, when fixes were requested.
+    pub diagnostics: Vec<Diagnostic>,
+    /// Rule ids that `--fix` repaired.
+    pub fixed: Vec<String>,
+}
+
+impl LintReport {
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|d| d.severity == Severity::Error)
+    }
+}
+
+/// Rules an agent refuses to load a skill over. Distribution skips agents
+/// whose rules a skill violates instead of shipping a copy they would ignore.
+///
+/// Codex is missing `missing-frontmatter` and `missing-name` on purpose: the
+/// distributor writes a frontmatter block into its copy.
+pub fn rules_enforced_by(agent_id: &str) -> &'static [&'static str] {
+    match agent_id {
+        "claude-code" => &[
+            RULE_MISSING_SKILL_MD,
+            RULE_MISSING_FRONTMATTER,
+            RULE_INVALID_FRONTMATTER,
+            RULE_MISSING_NAME,
+            RULE_NAME_FORMAT,
+            RULE_NAME_TOO_LONG,
+            RULE_MISSING_DESCRIPTION,
+            RULE_DESCRIPTION_TOO_LONG,
+        ],
+        "codex" => &[
+            RULE_MISSING_SKILL_MD,
+            RULE_INVALID_FRONTMATTER,
+            RULE_NAME_TOO_LONG,
+            RULE_DESCRIPTION_TOO_LONG,
+        ],
+        _ => &[RULE_MISSING_SKILL_MD],
+    }
+}
+
+/// Error rules of `report` that `agent_id` enforces.
+pub fn blocking_rules(report: &LintReport, agent_id: &str) -> Vec<String> {
+    let enforced = rules_enforced_by(agent_id);
+    report
+        .diagnostics
+        .iter()
+        .filter(|d| d.severity == Severity::Error && enforced.contains(&d.rule.as_str()))
+        .map(|d| d.rule.clone())
+        .collect()
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('-')
+        && !name.ends_with('-')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+/// 1-based line of a top-level frontmatter key, counting the opening `---` as line 1.
+fn frontmatter_key_line(content: &str, key: &str) -> Option<usize> {
+    let (yaml, _) = split_frontmatter(content)?;
+    yaml.lines()
+        .position(|line| {
+            line.strip_prefix(key)
+                .is_some_and(|rest| rest.trim_start().starts_with(':'))
+        })
+        .map(|idx| idx + 2)
+}
+
+/// Whether a top-level frontmatter key continues past its own line, as block
+/// scalars and wrapped values do. Rewriting only its first line would leave
+/// invalid YAML behind.
+fn frontmatter_key_spans_lines(content: &str, key: &str) -> bool {
+    let (Some((yaml, _)), Some(line)) = (
+        split_frontmatter(content),
+        frontmatter_key_line(content, key),
+    ) else {
+        return false;
+    };
+    let lines: Vec<&str> = yaml.lines().collect();
+    let value = lines[line - 2]
+        .split_once(':')
+        .map_or("", |(_, v)| v.trim());
+    value.starts_with(['|', '>'])
+        || lines
+            .get(line - 1)
+            .is_some_and(|next| next.starts_with([' ', '\t']))
+}
+
+/// Rewrites (or inserts) the top-level `name:` line of the frontmatter.
+/// Names spanning several lines are left alone.
+fn set_frontmatter_name(content: &str, name: &str) -> Option<String> {
+    if frontmatter_key_spans_lines(content, "name") {
+        return None;
+    }
+    let (yaml, body) = split_frontmatter(content)?;
+    let name_line = format!("name: {}", yaml_single_quote(name));
+    let mut lines: Vec<String> = yaml.lines().map(str::to_string).collect();
+    match frontmatter_key_line(content, "name") {
+        Some(line) => lines[line - 2] = name_line,
+        None => lines.insert(0, name_line),
+    }
+    Some(format!("---\n{}\n---\n{body}", lines.join("\n")))
+}
+
+fn diagnostic(
+    rule: &str,
+    severity: Severity,
+    file: &str,
+    line: Option<usize>,
+    message: String,
+    fixable: bool,
+) -> Diagnostic {
+    Diagnostic {
+        rule: rule.to_string(),
+        severity,
+        file: file.to_string(),
+        line,
+        message,
+        fixable,
+    }
+}
+
+fn check_skill_md(dir_name: &str, file: &str, content: &str) -> Vec<Diagnostic> {
+    use Severity::{Error, Warning};
+
+    let meta = match parse_frontmatter(content) {
+        Ok(Some(meta)) => meta,
+        Ok(None) => {
+            return vec![diagnostic(
+                RULE_MISSING_FRONTMATTER,
+                Error,
+                file,
+                Some(1),
+                "SKILL.md does not start with a YAML frontmatter block".to_string(),
+                true,
+            )];
+        }
+        Err(e) => {
+            // serde_yaml counts from the first line after the opening `---`.
+            let line = split_frontmatter(content)
+                .and_then(|(yaml, _)| serde_yaml::from_str::<serde_yaml::Value>(yaml).err())
+                .and_then(|err| err.location())
+                .map(|location| location.line() + 1);
+            return vec![diagnostic(
+                RULE_INVALID_FRONTMATTER,
+                Error,
+                file,
+                line.or(Some(1)),
+                e,
+                false,
+            )];
+        }
+    };
+
+    let mut out = vec![];
+    let name_line = frontmatter_key_line(content, "name");
+    let name_fixable = !frontmatter_key_spans_lines(content, "name");
+    let name = meta.name.as_deref().map(str::trim).unwrap_or_default();
+    let dir_name_is_valid = is_valid_name(dir_name);
+    if name.is_empty() {
+        out.push(diagnostic(
+            RULE_MISSING_NAME,
+            Error,
+            file,
+            name_line.or(Some(1)),
+            "Frontmatter has no `name`".to_string(),
+            name_fixable,
+        ));
+    } else {
+        if name != dir_name {
+            out.push(diagnostic(
+                RULE_NAME_MISMATCH,
+                Warning,
+                file,
+                name_line,
+                format!("`name` is \"{name}\" but the directory is \"{dir_name}\""),
+                dir_name_is_valid && name_fixable,
+            ));
+        }
+        if !is_valid_name(name) {
+            out.push(diagnostic(
+                RULE_NAME_FORMAT,
+                Error,
+                file,
+                name_line,
+                format!("`name` \"{name}\" may only use lowercase letters, digits and hyphens"),
+                false,
+            ));
+        }
+        if name.chars().count() > MAX_NAME_CHARS {
+            out.push(diagnostic(
+                RULE_NAME_TOO_LONG,
+                Error,
+                file,
+                name_line,
+                format!("`name` is longer than {MAX_NAME_CHARS} characters"),
+                false,
+            ));
+        }
+    }
+
+    let description = meta
+        .description
+        .as_deref()
+        .map(str::trim)
+        .unwrap_or_default();
+    let description_line = frontmatter_key_line(content, "description");
+    if description.is_empty() {
+        out.push(diagnostic(
+            RULE_MISSING_DESCRIPTION,
+            Error,
+            file,
+            description_line.or(Some(1)),
+            "Frontmatter has no `description`".to_string(),
+            false,
+        ));
+    } else if description.chars().count() > MAX_DESCRIPTION_CHARS {
+        out.push(diagnostic(
+            RULE_DESCRIPTION_TOO_LONG,
+            Error,
+            file,
+            description_line,
+            format!(
+                "`description` is {} characters, the limit is {MAX_DESCRIPTION_CHARS}",
+                description.chars().count()
+            ),
+            false,
+        ));
+    }
+
+    out
+}
+
+/// Applies every available fix to `content`, returning the new text and the rules it fixed.
+fn fix_skill_md(
+    dir_name: &str,
+    content: &str,
+    diagnostics: &[Diagnostic],
+) -> (String, Vec<String>) {
+    let mut text = content.to_string();
+    let mut fixed = vec![];
+    for d in diagnostics.iter().filter(|d| d.fixable) {
+        let next = match d.rule.as_str() {
+            RULE_MISSING_FRONTMATTER => with_yaml_frontmatter(&text, dir_name),
+            RULE_MISSING_NAME | RULE_NAME_MISMATCH => set_frontmatter_name(&text, dir_name),
+            _ => None,
+        };
+        if let Some(next) = next {
+            text = next;
+            fixed.push(d.rule.clone());
+        }
+    }
+    (text, fixed)
+}
+
+/// Lints the skill directory `dir`, optionally writing the available fixes back.
+pub fn lint_dir(dir: &Path, fix: bool) -> Result<LintReport, String> {
+    let dir_name = dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let Some(path) = find_skill_md_path(dir) else {
+        return Ok(LintReport {
+            skill: dir_name,
+            diagnostics: vec![diagnostic(
+                RULE_MISSING_SKILL_MD,
+                Severity::Error,
+                "SKILL.md",
+                None,
+                "No SKILL.md in the skill directory".to_string(),
+                false,
+            )],
+            fixed: vec![],
+        });
+    };
+    let file = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    let mut diagnostics = check_skill_md(&dir_name, &file, &content);
+
+    let mut fixed = vec![];
+    if fix && diagnostics.iter().any(|d| d.fixable) {
+        let (text, rules) = fix_skill_md(&dir_name, &content, &diagnostics);
+        if text != content {
+            write_file_atomic(&path, text.as_bytes())?;
+            diagnostics = check_skill_md(&dir_name, &file, &text);
+            fixed = rules;
+        }
+    }
+
+    Ok(LintReport {
+        skill: dir_name,
+        diagnostics,
+        fixed,
+    })
+}
+
+pub fn lint_skill(skill_name: &str, storage_path: &str, fix: bool) -> Result<LintReport, String> {
+    let store_root = manager_store_root(storage_path)?;
+    let dir = store_root.join(safe_skill_dir_name(skill_name));
+    if !dir.is_dir() {
+        return Err(format!("Skill not found in store: {skill_name}"));
+    }
+    lint_dir(&dir, fix)
+}
+
+pub fn lint_store(storage_path: &str, fix: bool) -> Result<Vec<LintReport>, String> {
+    let store_root = manager_store_root(storage_path)?;
+    load_manifest(&store_root)?
+        .skills
+        .iter()
+        .map(|skill| lint_dir(&store_root.join(safe_skill_dir_name(&skill.name)), fix))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{ensure_dir, generate_id};
+
+    fn skill_dir(name: &str, skill_md: &str) -> (std::path::PathBuf, std::path::PathBuf) {
+        let tmp = std::env::temp_dir().join(format!("skills-manager-lint-{}", generate_id()));
+        let dir = tmp.join(name);
+        ensure_dir(&dir).unwrap();
+        fs::write(dir.join("SKILL.md"), skill_md).unwrap();
+        (tmp, dir)
+    }
+
+    fn rules(report: &LintReport) -> Vec<&str> {
+        report.diagnostics.iter().map(|d| d.rule.as_str()).collect()
+    }
+
+    #[test]
+    fn lint_reports_rules_with_lines() {
+        let long = "x".repeat(MAX_DESCRIPTION_CHARS + 1);
+        let (tmp, dir) = skill_dir(
+            "pdf-tools",
+            &format!("---\nlicense: MIT\nname: PDF Tools\ndescription: {long}\n---\n# PDF\n"),
+        );
+
+        let report = lint_dir(&dir, false).unwrap();
+        assert_eq!(
+            rules(&report),
+            vec![
+                RULE_NAME_MISMATCH,
+                RULE_NAME_FORMAT,
+                RULE_DESCRIPTION_TOO_LONG
+            ]
+        );
+        assert_eq!(report.diagnostics[1].line, Some(3));
+        assert_eq!(report.diagnostics[2].line, Some(4));
+        assert!(report.has_errors());
+        assert_eq!(
+            blocking_rules(&report, "claude-code"),
+            vec![RULE_NAME_FORMAT, RULE_DESCRIPTION_TOO_LONG]
+        );
+        assert_eq!(
+            blocking_rules(&report, "codex"),
+            vec![RULE_DESCRIPTION_TOO_LONG]
+        );
+        assert!(blocking_rules(&report, "cursor").is_empty());
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn fix_adds_frontmatter_and_aligns_name() {
+        let (tmp, dir) = skill_dir("pdf-tools", "# PDF Tools\n");
+        let report = lint_dir(&dir, true).unwrap();
+        assert_eq!(report.fixed, vec![RULE_MISSING_FRONTMATTER]);
+        assert_eq!(rules(&report), vec![RULE_MISSING_DESCRIPTION]);
+        let content = fs::read_to_string(dir.join("SKILL.md")).unwrap();
+        assert!(content.starts_with("---\nname: 'pdf-tools'\n---\n"));
+        assert!(content.ends_with("# PDF Tools\n"));
+
+        fs::write(
+            dir.join("SKILL.md"),
+            "---\ndescription: d\nname: other\n---\nbody\n",
+        )
+        .unwrap();
+        let report = lint_dir(&dir, true).unwrap();
+        assert_eq!(report.fixed, vec![RULE_NAME_MISMATCH]);
+        assert!(report.diagnostics.is_empty());
+        assert_eq!(
+            fs::read_to_string(dir.join("SKILL.md")).unwrap(),
+            "---\ndescription: d\nname: 'pdf-tools'\n---\nbody\n"
+        );
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn fix_leaves_multi_line_names_alone() {
+        let content = "---\nname: >\n  pdf\n  tools\ndescription: d\n---\nbody\n";
+        let (tmp, dir) = skill_dir("pdf-tools", content);
+        let report = lint_dir(&dir, true).unwrap();
+        assert!(report.fixed.is_empty());
+        assert_eq!(rules(&report), vec![RULE_NAME_MISMATCH, RULE_NAME_FORMAT]);
+        assert!(report.diagnostics.iter().all(|d| !d.fixable));
+        assert_eq!(fs::read_to_string(dir.join("SKILL.md")).unwrap(), content);
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+}
@@ -0,0 +1,530 @@
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::digest::digest_dir;
+use crate::distributor::{sync_all_skills_distribution, DistributionReport};
+use crate::installer::{
+    adopt_staged_dir_as, install_skill_cli, parse_install_source, source_subdir, stage_source,
+    InstallSource,
+};
+use crate::manifest::{load_manifest, update_manifest};
+use crate::models::{AgentInfo, InstallProgressLog, Skill, SourceKind};
+use crate::progress::{NoopProgress, ProgressReporter};
+use crate::utils::{
+    manager_store_root, remove_dir_if_exists, safe_skill_dir_name, write_file_atomic,
+};
+
+pub const LOCKFILE_VERSION: u32 = 1;
+pub const LOCKFILE_NAME: &str = "skills.lock";
+
+/// A store skill pinned to the exact revision and content it had when locked.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LockedSkill {
+    pub name: String,
+    pub kind: SourceKind,
+    pub source_url: String,
+    /// The ref the skill was installed from, kept so later updates follow it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
+    /// Commit sha for git sources, archive sha256 for zip sources.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revision: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subpath: Option<String>,
+    /// Digest of the skill directory, see [`content_hash`].
+    pub content_hash: String,
+    #[serde(default)]
+    pub enabled_agents: Vec<String>,
+}
+
+/// Contents of a `skills.lock` file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillLock {
+    pub version: u32,
+    #[serde(default)]
+    pub skills: Vec<LockedSkill>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LockExport {
+    pub lock: SkillLock,
+    /// Store skills left out because they have no source to fetch them from.
+    pub skipped: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MismatchKind {
+    Revision,
+    Content,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LockMismatch {
+    pub name: String,
+    pub kind: MismatchKind,
+    pub expected: String,
+    pub actual: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LockFailure {
+    pub name: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LockInstallReport {
+    pub installed: Vec<String>,
+    /// Already in the store with the locked content.
+    pub unchanged: Vec<String>,
+    /// Not installed, or left as they were, because they differ from the lock.
+    pub mismatched: Vec<LockMismatch>,
+    pub failed: Vec<LockFailure>,
+    pub distribution: DistributionReport,
+}
+
+/// Pins every store skill that has a recorded source to its installed
+/// revision and current content.
+pub fn export_lock(storage_path: &str) -> Result<LockExport, String> {
+    let store_root = manager_store_root(storage_path)?;
+    let mut skills = vec![];
+    let mut skipped = vec![];
+    for skill in load_manifest(&store_root)?.skills {
+        let (Some(source_url), Some(kind)) = (skill.source_url.clone(), skill.source_kind) else {
+            skipped.push(skill.name);
+            continue;
+        };
+        let dir = store_root.join(&skill.name);
+        if !dir.is_dir() {
+            skipped.push(skill.name);
+            continue;
+        }
+        let revision = match kind {
+            SourceKind::Git => skill.source_commit.clone(),
+            SourceKind::Zip => skill.source_checksum.clone(),
+            SourceKind::Npx => None,
+        };
+        skills.push(LockedSkill {
+            content_hash: content_hash(&dir)?,
+            name: skill.name,
+            kind,
+            source_url,
+            reference: skill.source_ref,
+            revision,
+            subpath: skill.source_subpath,
+            enabled_agents: skill.enabled_agents,
+        });
+    }
+    Ok(LockExport {
+        lock: SkillLock {
+            version: LOCKFILE_VERSION,
+            skills,
+        },
+        skipped,
+    })
+}
+
+/// The content hash of `dir`: relative paths, contents and executable bits,
+/// so it matches across umasks and platforms.
+pub fn content_hash(dir: &Path) -> Result<String, String> {
+    Ok(digest_dir(dir)?.content_root())
+}
+
+pub fn write_lock_file(path: &Path, lock: &SkillLock) -> Result<(), String> {
+    let mut text = serde_json::to_string_pretty(lock)
+        .map_err(|e| format!("Failed to serialize lockfile: {e}"))?;
+    text.push('\n');
+    write_file_atomic(path, text.as_bytes())
+}
+
+pub fn read_lock_file(path: &Path) -> Result<SkillLock, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read lockfile {}: {e}", path.display()))?;
+    let lock: SkillLock = serde_json::from_str(&text)
+        .map_err(|e| format!("Failed to parse lockfile {}: {e}", path.display()))?;
+    if lock.version > LOCKFILE_VERSION {
+        return Err(format!(
+            "Lockfile {} has version {}, newer than the supported {LOCKFILE_VERSION}",
+            path.display(),
+            lock.version
+        ));
+    }
+    Ok(lock)
+}
+
+fn report_entry(
+    progress: &dyn ProgressReporter<InstallProgressLog>,
+    name: &str,
+    label: String,
+    status: &str,
+    percent: f64,
+) {
+    progress.report(InstallProgressLog {
+        id: format!("lock-{name}"),
+        label,
+        status: status.to_string(),
+        progress: percent,
+        bytes: None,
+        total_bytes: None,
+    });
+}
+
+/// Outcome of installing one locked skill.
+enum Installed {
+    Ok,
+    Mismatch(LockMismatch),
+}
+
+fn content_mismatch(entry: &LockedSkill, actual: String) -> Installed {
+    Installed::Mismatch(LockMismatch {
+        name: entry.name.clone(),
+        kind: MismatchKind::Content,
+        expected: entry.content_hash.clone(),
+        actual: Some(actual),
+    })
+}
+
+/// Fetches a git or zip entry at its locked revision and adopts it into the
+/// store only when both the revision and the content match the lock.
+fn install_fetched(
+    store_root: &Path,
+    entry: &LockedSkill,
+    name: &str,
+    progress: &dyn ProgressReporter<InstallProgressLog>,
+) -> Result<Installed, String> {
+    let (fetch, recorded) = match (entry.kind, parse_install_source(&entry.source_url)) {
+        (SourceKind::Git, InstallSource::Git { url, .. }) => (
+            InstallSource::Git {
+                url: url.clone(),
+                reference: entry.revision.clone().or_else(|| entry.reference.clone()),
+                subpath: entry.subpath.clone(),
+            },
+            InstallSource::Git {
+                url,
+                reference: entry.reference.clone(),
+                subpath: entry.subpath.clone(),
+            },
+        ),
+        (SourceKind::Zip, source @ InstallSource::Zip { .. }) => (source.clone(), source),
+        _ => {
+            return Err(format!(
+                "{} is not a {:?} source",
+                entry.source_url, entry.kind
+            ))
+        }
+    };
+
+    // The downloader rejects an archive that does not match its locked checksum.
+    let expected_checksum = match entry.kind {
+        SourceKind::Zip => entry.revision.as_deref(),
+        _ => None,
+    };
+    let staged = stage_source(&fetch, store_root, expected_checksum, progress)?;
+    let resolved = match entry.kind {
+        SourceKind::Zip => staged.revision.checksum.clone(),
+        _ => staged.revision.commit.clone(),
+    };
+    if let Some(expected) = &entry.revision {
+        if resolved.as_ref() != Some(expected) {
+            return Ok(Installed::Mismatch(LockMismatch {
+                name: entry.name.clone(),
+                kind: MismatchKind::Revision,
+                expected: expected.clone(),
+                actual: resolved,
+            }));
+        }
+    }
+
+    let dir = source_subdir(&staged.staging, entry.subpath.as_deref())?;
+    let actual = content_hash(&dir)?;
+    if actual != entry.content_hash {
+        return Ok(content_mismatch(entry, actual));
+    }
+    adopt_staged_dir_as(
+        store_root,
+        &dir,
+        name,
+        &recorded,
+        &entry.source_url,
+        &staged.revision,
+        entry.subpath.clone(),
+    )?;
+    Ok(Installed::Ok)
+}
+
+/// npx installs straight into the store, so a mismatching result is removed
+/// again afterwards.
+fn install_npx(
+    store_root: &Path,
+    entry: &LockedSkill,
+    name: &str,
+    storage_path: &str,
+) -> Result<Installed, String> {
+    install_skill_cli(&entry.source_url, name, storage_path)?;
+    let actual = content_hash(&store_root.join(name))?;
+    if actual != entry.content_hash {
+        remove_dir_if_exists(&store_root.join(name))?;
+        update_manifest(store_root, |manifest| manifest.remove(name))?;
+        return Ok(content_mismatch(entry, actual));
+    }
+    Ok(Installed::Ok)
+}
+
+/// Recreates the skills of `lock` in the store and distributes them to their
+/// locked agents.
+///
+/// Skills already in the store are left as they are and reported as
+/// mismatched when their content differs from the lock. Fetched skills are
+/// installed only when their revision and content hash match the lock.
+pub fn install_from_lock(
+    lock: &SkillLock,
+    agents: &[AgentInfo],
+    storage_path: &str,
+    progress: &dyn ProgressReporter<InstallProgressLog>,
+) -> Result<LockInstallReport, String> {
+    let store_root = manager_store_root(storage_path)?;
+    let mut report = LockInstallReport::default();
+    let mut distribute: Vec<(String, Vec<String>)> = vec![];
+    let total = lock.skills.len().max(1) as f64;
+
+    for (idx, entry) in lock.skills.iter().enumerate() {
+        let name = safe_skill_dir_name(&entry.name);
+        let label = format!("正在安装技能: {name}");
+        report_entry(
+            progress,
+            &name,
+            label.clone(),
+            "loading",
+            idx as f64 / total * 100.0,
+        );
+        let done = (idx + 1) as f64 / total * 100.0;
+
+        let existing = store_root.join(&name);
+        let outcome = if load_manifest(&store_root)?.get(&name).is_some() && existing.is_dir() {
+            let actual = content_hash(&existing)?;
+            if actual == entry.content_hash {
+                report.unchanged.push(name.clone());
+                distribute.push((name.clone(), entry.enabled_agents.clone()));
+                report_entry(progress, &name, label, "success", done);
+                continue;
+            }
+            Ok(content_mismatch(entry, actual))
+        } else if existing.exists() {
+            Err(format!("{} already exists", existing.display()))
+        } else if entry.kind == SourceKind::Npx {
+            install_npx(&store_root, entry, &name, storage_path)
+        } else {
+            install_fetched(&store_root, entry, &name, progress)
+        };
+
+        match outcome {
+            Ok(Installed::Ok) => {
+                report.installed.push(name.clone());
+                distribute.push((name.clone(), entry.enabled_agents.clone()));
+                report_entry(progress, &name, label, "success", done);
+            }
+            Ok(Installed::Mismatch(mismatch)) => {
+                report.mismatched.push(mismatch);
+                report_entry(progress, &name, format!("校验失败: {name}"), "error", done);
+            }
+            Err(error) => {
+                report.failed.push(LockFailure {
+                    name: name.clone(),
+                    error,
+                });
+                report_entry(progress, &name, format!("安装失败: {name}"), "error", done);
+            }
+        }
+    }
+
+    let skills: Vec<Skill> = update_manifest(&store_root, |manifest| {
+        distribute
+            .iter()
+            .filter_map(|(name, enabled)| {
+                let skill = manifest.get_mut(name)?;
+                skill.enabled_agents = enabled.clone();
+                Some(skill.clone())
+            })
+            .collect()
+    })?;
+    report.distribution =
+        sync_all_skills_distribution(&skills, agents, storage_path, &NoopProgress)?;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distributor::sync_one_skill;
+    use crate::git::{temp_test_dir, test_agent, write_upstream};
+    use crate::installer::install_skill;
+
+    #[test]
+    fn install_from_lock_recreates_pinned_skills_and_distribution() {
+        let tmp = temp_test_dir("lock-roundtrip");
+        let store_a = tmp.join("a");
+        let upstream = tmp.join("demo");
+        let url = write_upstream(&upstream, "---\nname: demo\n---\nv1\n");
+        let skill = install_skill(&url, &store_a.to_string_lossy()).unwrap();
+        let agents_a = vec![test_agent(&tmp.join("agent-a"))];
+        sync_one_skill(&store_a, &skill.name, &["claude".to_string()], &agents_a).unwrap();
+        fs::create_dir_all(store_a.join("local-only")).unwrap();
+        fs::write(store_a.join("local-only").join("SKILL.md"), "# local\n").unwrap();
+
+        let export = export_lock(&store_a.to_string_lossy()).unwrap();
+        assert_eq!(export.skipped, vec!["local-only".to_string()]);
+        assert_eq!(export.lock.skills.len(), 1);
+        assert_eq!(export.lock.skills[0].revision, skill.source_commit);
+        let lock_path = tmp.join(LOCKFILE_NAME);
+        write_lock_file(&lock_path, &export.lock).unwrap();
+        let lock = read_lock_file(&lock_path).unwrap();
+        assert_eq!(lock, export.lock);
+
+        // The upstream moves on; the lock still installs the pinned revision.
+        write_upstream(&upstream, "---\nname: demo\n---\nv2\n");
+        let store_b = tmp.join("b");
+        let agent_b = tmp.join("agent-b");
+        let report = install_from_lock(
+            &lock,
+            &[test_agent(&agent_b)],
+            &store_b.to_string_lossy(),
+            &NoopProgress,
+        )
+        .unwrap();
+        assert_eq!(report.installed, vec!["demo".to_string()]);
+        assert!(report.mismatched.is_empty() && report.failed.is_empty());
+        assert!(fs::read_to_string(agent_b.join("demo").join("SKILL.md"))
+            .unwrap()
+            .contains("v1"));
+        let installed = load_manifest(&store_b)
+            .unwrap()
+            .get("demo")
+            .cloned()
+            .unwrap();
+        assert_eq!(installed.source_commit, skill.source_commit);
+        assert_eq!(installed.enabled_agents, vec!["claude".to_string()]);
+
+        let again =
+            install_from_lock(&lock, &[], &store_b.to_string_lossy(), &NoopProgress).unwrap();
+        assert_eq!(again.unchanged, vec!["demo".to_string()]);
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn install_from_lock_reports_hash_mismatches() {
+        let tmp = temp_test_dir("lock-mismatch");
+        let store_a = tmp.join("a");
+        let url = write_upstream(&tmp.join("demo"), "---\nname: demo\n---\nv1\n");
+        install_skill(&url, &store_a.to_string_lossy()).unwrap();
+        let mut lock = export_lock(&store_a.to_string_lossy()).unwrap().lock;
+        lock.skills[0].content_hash = "0".repeat(64);
+
+        let store_b = tmp.join("b");
+        let report =
+            install_from_lock(&lock, &[], &store_b.to_string_lossy(), &NoopProgress).unwrap();
+        assert!(report.installed.is_empty());
+        assert_eq!(report.mismatched.len(), 1);
+        assert_eq!(report.mismatched[0].kind, MismatchKind::Content);
+        assert!(!store_b.join("demo").exists());
+
+        let report =
+            install_from_lock(&lock, &[], &store_a.to_string_lossy(), &NoopProgress).unwrap();
+        assert_eq!(report.mismatched.len(), 1);
+        assert!(store_a.join("demo").join("SKILL.md").is_file());
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn install_from_lock_rejects_an_archive_with_another_checksum() {
+        use std::io::Write;
+
+        let tmp = temp_test_dir("lock-zip");
+        let mut body = std::io::Cursor::new(Vec::new());
+        let mut zip = zip::ZipWriter::new(&mut body);
+        zip.start_file("demo/SKILL.md", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        zip.write_all(b"---\nname: demo\n---\n").unwrap();
+        zip.finish().unwrap();
+        let body = body.into_inner();
+
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let port = server.server_addr().to_ip().unwrap().port();
+        std::thread::spawn(move || {
+            if let Ok(request) = server.recv() {
+                let _ = request.respond(tiny_http::Response::from_data(body));
+            }
+        });
+        let lock = SkillLock {
+            version: LOCKFILE_VERSION,
+            skills: vec![LockedSkill {
+                name: "demo".to_string(),
+                kind: SourceKind::Zip,
+                source_url: format!("http://127.0.0.1:{port}/demo.zip"),
+                reference: None,
+                revision: Some("0".repeat(64)),
+                subpath: None,
+                content_hash: "0".repeat(64),
+                enabled_agents: vec![],
+            }],
+        };
+
+        let store = tmp.join("store");
+        let report =
+            install_from_lock(&lock, &[], &store.to_string_lossy(), &NoopProgress).unwrap();
+        assert!(report.installed.is_empty());
+        assert_eq!(report.failed.len(), 1);
+        assert!(
+            report.failed[0].error.contains("checksum mismatch"),
+            "{}",
+            report.failed[0].error
+        );
+        assert!(!store.join("demo").exists());
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn content_hash_ignores_permission_bits_other_than_exec() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp = temp_test_dir("lock-modes");
+        let store_a = tmp.join("a");
+        let url = write_upstream(&tmp.join("demo"), "---\nname: demo\n---\nv1\n");
+        install_skill(&url, &store_a.to_string_lossy()).unwrap();
+        // Exported where the umask leaves files group-writable.
+        let skill_md = store_a.join("demo").join("SKILL.md");
+        fs::set_permissions(&skill_md, fs::Permissions::from_mode(0o664)).unwrap();
+        let lock = export_lock(&store_a.to_string_lossy()).unwrap().lock;
+
+        let store_b = tmp.join("b");
+        let report =
+            install_from_lock(&lock, &[], &store_b.to_string_lossy(), &NoopProgress).unwrap();
+        assert_eq!(report.installed, vec!["demo".to_string()]);
+        assert!(report.mismatched.is_empty());
+
+        let installed = store_b.join("demo").join("SKILL.md");
+        fs::set_permissions(&installed, fs::Permissions::from_mode(0o600)).unwrap();
+        let again =
+            install_from_lock(&lock, &[], &store_b.to_string_lossy(), &NoopProgress).unwrap();
+        assert_eq!(again.unchanged, vec!["demo".to_string()]);
+
+        fs::set_permissions(&installed, fs::Permissions::from_mode(0o755)).unwrap();
+        let exec =
+            install_from_lock(&lock, &[], &store_b.to_string_lossy(), &NoopProgress).unwrap();
+        assert_eq!(exec.mismatched.len(), 1);
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+}
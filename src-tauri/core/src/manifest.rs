@@ -0,0 +1,226 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::Skill;
+use crate::store::read_store_skill_names;
+use crate::utils::{ensure_dir, now_iso, safe_skill_dir_name, write_file_atomic};
+
+pub const MANIFEST_VERSION: u32 = 1;
+const MANIFEST_DIR: &str = ".skillsm";
+const MANIFEST_FILE: &str = "manifest.json";
+
+// Commands run on a blocking pool and may race on the same store; every
+// read-modify-write of the manifest goes through this lock.
+static MANIFEST_LOCK: Mutex<()> = Mutex::new(());
+
+/// Every skill record of a manager store, persisted at `<store>/.skillsm/manifest.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoreManifest {
+    pub version: u32,
+    #[serde(default)]
+    pub skills: Vec<Skill>,
+}
+
+impl Default for StoreManifest {
+    fn default() -> Self {
+        Self {
+            version: MANIFEST_VERSION,
+            skills: vec![],
+        }
+    }
+}
+
+impl StoreManifest {
+    pub fn get(&self, name: &str) -> Option<&Skill> {
+        let key = safe_skill_dir_name(name);
+        self.skills.iter().find(|skill| skill.name == key)
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Skill> {
+        let key = safe_skill_dir_name(name);
+        self.skills.iter_mut().find(|skill| skill.name == key)
+    }
+
+    pub fn upsert(&mut self, skill: Skill) {
+        match self.get_mut(&skill.name) {
+            Some(existing) => *existing = skill,
+            None => {
+                self.skills.push(skill);
+                self.skills.sort_by(|a, b| a.name.cmp(&b.name));
+            }
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Skill> {
+        let key = safe_skill_dir_name(name);
+        let idx = self.skills.iter().position(|skill| skill.name == key)?;
+        Some(self.skills.remove(idx))
+    }
+
+    /// Drops records whose store directory is gone and adds a bare record for
+    /// every directory the manifest does not know about yet.
+    fn reconcile(&mut self, store_root: &Path) -> Result<(), String> {
+        let now = now_iso();
+        let mut by_name: BTreeMap<String, Skill> = self
+            .skills
+            .drain(..)
+            .map(|skill| (safe_skill_dir_name(&skill.name), skill))
+            .collect();
+
+        for name in read_store_skill_names(store_root)? {
+            let mut skill = by_name.remove(&name).unwrap_or_else(|| Skill {
+                id: name.clone(),
+                name: name.clone(),
+                source_url: None,
+                enabled_agents: vec![],
+                last_sync: None,
+                last_update: None,
+                last_sync_digest: None,
+                source_commit: None,
+                source_subpath: None,
+                source_kind: None,
+                source_ref: None,
+                source_checksum: None,
+                installed_at: None,
+            });
+            skill.name = name;
+            if skill.last_sync.is_none() {
+                skill.last_sync = Some(now.clone());
+            }
+            if skill.last_update.is_none() {
+                skill.last_update = Some(now.clone());
+            }
+            self.skills.push(skill);
+        }
+
+        Ok(())
+    }
+}
+
+pub fn manifest_path(store_root: &Path) -> PathBuf {
+    store_root.join(MANIFEST_DIR).join(MANIFEST_FILE)
+}
+
+pub fn manifest_exists(store_root: &Path) -> bool {
+    manifest_path(store_root).is_file()
+}
+
+fn read_manifest_file(store_root: &Path) -> Result<StoreManifest, String> {
+    let path = manifest_path(store_root);
+    if !path.exists() {
+        return Ok(StoreManifest::default());
+    }
+
+    let text = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read manifest {}: {e}", path.display()))?;
+    let manifest: StoreManifest = serde_json::from_str(&text)
+        .map_err(|e| format!("Failed to parse manifest {}: {e}", path.display()))?;
+    if manifest.version > MANIFEST_VERSION {
+        return Err(format!(
+            "Manifest {} has version {}, this build supports up to {MANIFEST_VERSION}",
+            path.display(),
+            manifest.version
+        ));
+    }
+    Ok(manifest)
+}
+
+fn write_manifest_file(store_root: &Path, manifest: &StoreManifest) -> Result<(), String> {
+    let path = manifest_path(store_root);
+    let dir = store_root.join(MANIFEST_DIR);
+    ensure_dir(&dir)?;
+
+    let mut manifest = manifest.clone();
+    manifest.version = MANIFEST_VERSION;
+    let text = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| format!("Failed to serialize manifest: {e}"))?;
+
+    write_file_atomic(&path, text.as_bytes())
+}
+
+/// Loads the manifest, reconciled against the directories present in the store.
+pub fn load_manifest(store_root: &Path) -> Result<StoreManifest, String> {
+    let _guard = MANIFEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut manifest = read_manifest_file(store_root)?;
+    manifest.reconcile(store_root)?;
+    Ok(manifest)
+}
+
+/// Applies `f` to the reconciled manifest and atomically writes the result back.
+pub fn update_manifest<T>(
+    store_root: &Path,
+    f: impl FnOnce(&mut StoreManifest) -> T,
+) -> Result<T, String> {
+    let _guard = MANIFEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut manifest = read_manifest_file(store_root)?;
+    manifest.reconcile(store_root)?;
+    let out = f(&mut manifest);
+    manifest.reconcile(store_root)?;
+    write_manifest_file(store_root, &manifest)?;
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::generate_id;
+
+    fn temp_test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("skills-manager-{name}-{}", generate_id()));
+        ensure_dir(&dir).expect("create temp dir");
+        dir
+    }
+
+    fn write_skill(store_root: &Path, name: &str) {
+        let dir = store_root.join(name);
+        ensure_dir(&dir).expect("create skill dir");
+        fs::write(dir.join("SKILL.md"), format!("# {name}\n")).expect("write SKILL.md");
+    }
+
+    #[test]
+    fn update_manifest_persists_records_and_reconciles_with_store() {
+        let root = temp_test_dir("manifest-roundtrip");
+        write_skill(&root, "alpha");
+        write_skill(&root, "beta");
+
+        update_manifest(&root, |m| {
+            let alpha = m.get_mut("alpha").unwrap();
+            alpha.source_url = Some("https://github.com/foo/alpha".to_string());
+            alpha.enabled_agents = vec!["codex".to_string()];
+        })
+        .unwrap();
+        assert!(manifest_exists(&root));
+
+        fs::remove_dir_all(root.join("beta")).unwrap();
+        write_skill(&root, "gamma");
+
+        let manifest = load_manifest(&root).unwrap();
+        let names: Vec<&str> = manifest.skills.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["alpha", "gamma"]);
+        let alpha = manifest.get("alpha").unwrap();
+        assert_eq!(
+            alpha.source_url.as_deref(),
+            Some("https://github.com/foo/alpha")
+        );
+        assert_eq!(alpha.enabled_agents, vec!["codex".to_string()]);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn load_manifest_rejects_newer_versions() {
+        let root = temp_test_dir("manifest-version");
+        ensure_dir(&root.join(MANIFEST_DIR)).unwrap();
+        fs::write(manifest_path(&root), r#"{ "version": 99, "skills": [] }"#).unwrap();
+
+        let err = load_manifest(&root).unwrap_err();
+        assert!(err.contains("version 99"));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
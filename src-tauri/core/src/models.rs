@@ -0,0 +1,151 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// How a skill got into the store.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SourceKind {
+    Git,
+    Zip,
+    /// Installed through `npx skills add`.
+    Npx,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Skill {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub source_url: Option<String>,
+    /// Commit checked out when the skill was installed from git.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_commit: Option<String>,
+    /// Directory of the source the skill was taken from, relative to the
+    /// repository or archive root.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_subpath: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_kind: Option<SourceKind>,
+    /// Branch, tag or commit the install asked for; none follows the default branch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_ref: Option<String>,
+    /// SHA-256 of the downloaded archive, for zip installs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_checksum: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub installed_at: Option<String>,
+    #[serde(default)]
+    pub enabled_agents: Vec<String>,
+    #[serde(default)]
+    pub last_sync: Option<String>,
+    #[serde(default)]
+    pub last_update: Option<String>,
+    /// Digest of the store copy as of the last distribution; tells a stale
+    /// target apart from one edited in the agent directory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_sync_digest: Option<String>,
+}
+
+/// Typed view of a SKILL.md YAML frontmatter block.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillMetadata {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub license: Option<String>,
+    /// `allowed-tools`, given either as a YAML list or a comma/space separated string.
+    #[serde(default)]
+    pub allowed_tools: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Every other frontmatter key, kept as-is.
+    #[serde(default)]
+    pub extra: BTreeMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupDetectedSkill {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub source_agent_ids: Vec<String>,
+    #[serde(default)]
+    pub source_agent_names: Vec<String>,
+}
+
+/// How a skill is placed into an agent's skills directory.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DistributionMode {
+    #[default]
+    Copy,
+    /// Link `<agent_root>/<skill>` to the store directory, so store edits are live.
+    Symlink,
+}
+
+/// How copy-distributed files are materialized. Each strategy falls back to
+/// the next one (reflink -> hardlink -> copy) when the filesystem refuses it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CopyStrategy {
+    #[default]
+    Copy,
+    Hardlink,
+    Reflink,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentInfo {
+    pub id: String,
+    pub name: String,
+    pub default_path: String,
+    #[serde(default)]
+    pub current_path: String,
+    pub enabled: bool,
+    #[serde(default)]
+    pub icon: String,
+    #[serde(default)]
+    pub distribution_mode: DistributionMode,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncAllToManagerProgressLog {
+    pub id: String,
+    pub label: String,
+    pub status: String,
+    pub progress: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncAllSkillsDistributionProgressLog {
+    pub id: String,
+    pub label: String,
+    pub status: String,
+    pub progress: f64,
+}
+
+/// Progress of a single install. Byte counts are set while downloading;
+/// `total_bytes` stays empty when the server sends no length.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallProgressLog {
+    pub id: String,
+    pub label: String,
+    pub status: String,
+    pub progress: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_bytes: Option<u64>,
+}
@@ -0,0 +1,421 @@
+//! Read-only previews of the bulk commands that rewrite many directories.
+//!
+//! Each `plan_*` function walks the same decisions as the command it mirrors
+//! and returns the operations in the order the command would perform them,
+//! without writing anything.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::digest::digest_dir_with;
+use crate::distributor::skill_md_overrides;
+use crate::lint::{blocking_rules, lint_dir};
+use crate::models::{AgentInfo, DistributionMode, Skill};
+use crate::scanner::{find_skill_roots, should_replace_skill_dir};
+use crate::utils::{agent_roots, expand_tilde, is_same_path, safe_skill_dir_name};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlannedAction {
+    /// Create `target` as a copy of `source`.
+    Copy,
+    /// Replace whatever is at `target` with a copy of `source`.
+    Replace,
+    /// Point `target` at `source` with a symlink.
+    Link,
+    /// Remove `target`; uninstall moves it to the trash instead.
+    Remove,
+    /// Write `target` with YAML frontmatter the store copy lacks.
+    RewriteFrontmatter,
+}
+
+impl PlannedAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Copy => "copy",
+            Self::Replace => "replace",
+            Self::Link => "link",
+            Self::Remove => "remove",
+            Self::RewriteFrontmatter => "rewrite_frontmatter",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedOperation {
+    pub action: PlannedAction,
+    pub skill: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agent_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    pub target: String,
+    pub reason: String,
+}
+
+fn operation(
+    action: PlannedAction,
+    skill: &str,
+    agent_id: Option<&str>,
+    source: Option<&Path>,
+    target: &Path,
+    reason: impl Into<String>,
+) -> PlannedOperation {
+    PlannedOperation {
+        action,
+        skill: skill.to_string(),
+        agent_id: agent_id.map(str::to_string),
+        source: source.map(|path| path.to_string_lossy().to_string()),
+        target: target.to_string_lossy().to_string(),
+        reason: reason.into(),
+    }
+}
+
+/// What distributing `src` as a copy to `dst` would do, if anything.
+fn plan_copy(
+    src: &Path,
+    dst: &Path,
+    overrides: &BTreeMap<String, Vec<u8>>,
+) -> Result<Option<(PlannedAction, &'static str)>, String> {
+    let Ok(meta) = fs::symlink_metadata(dst) else {
+        return Ok(Some((PlannedAction::Copy, "not distributed yet")));
+    };
+    if !meta.is_dir() {
+        return Ok(Some((
+            PlannedAction::Replace,
+            "target is a link or file, not a copy",
+        )));
+    }
+    let wanted = digest_dir_with(src, true, overrides)?;
+    let current = digest_dir_with(dst, false, &BTreeMap::new())?;
+    if current.content_root() == wanted.content_root() {
+        return Ok(None);
+    }
+    Ok(Some((
+        PlannedAction::Replace,
+        "content differs from the store",
+    )))
+}
+
+/// Plans [`crate::distributor::sync_all_skills_distribution`] for `skills`,
+/// distributing each to its `enabled_agents`.
+///
+/// Agents the skill breaks a rule for are skipped, as the sync skips them.
+pub fn plan_sync_all_skills_distribution(
+    skills: &[Skill],
+    agents: &[AgentInfo],
+    storage_path: &str,
+) -> Result<Vec<PlannedOperation>, String> {
+    let store_root = expand_tilde(storage_path);
+    let mut ops = vec![];
+    for skill in skills {
+        let dir_name = safe_skill_dir_name(&skill.name);
+        let src = store_root.join(&dir_name);
+        if !src.exists() {
+            return Err(format!(
+                "Skill store not found for name={} at {}",
+                skill.name,
+                src.display(),
+            ));
+        }
+        let lint = lint_dir(&src, false)?;
+
+        for agent in agents.iter().filter(|agent| agent.enabled) {
+            let agent_id = Some(agent.id.as_str());
+            if !skill.enabled_agents.iter().any(|id| id == &agent.id) {
+                for root in agent_roots(agent) {
+                    let dst = root.join(&dir_name);
+                    if fs::symlink_metadata(&dst).is_ok() {
+                        ops.push(operation(
+                            PlannedAction::Remove,
+                            &skill.name,
+                            agent_id,
+                            None,
+                            &dst,
+                            "agent is not enabled for this skill",
+                        ));
+                    }
+                }
+                continue;
+            }
+            if !blocking_rules(&lint, &agent.id).is_empty() {
+                continue;
+            }
+
+            let overrides = skill_md_overrides(agent, &src, &skill.name);
+            let link = agent.distribution_mode == DistributionMode::Symlink && overrides.is_empty();
+            for root in agent_roots(agent) {
+                let dst = root.join(&dir_name);
+                if link {
+                    let target = fs::canonicalize(&src).unwrap_or_else(|_| src.clone());
+                    if fs::read_link(&dst).is_ok_and(|current| current == target) {
+                        continue;
+                    }
+                    let reason = if fs::symlink_metadata(&dst).is_ok() {
+                        "replaces the existing copy with a link to the store"
+                    } else {
+                        "not distributed yet"
+                    };
+                    ops.push(operation(
+                        PlannedAction::Link,
+                        &skill.name,
+                        agent_id,
+                        Some(&target),
+                        &dst,
+                        reason,
+                    ));
+                    continue;
+                }
+
+                let Some((action, reason)) = plan_copy(&src, &dst, &overrides)? else {
+                    continue;
+                };
+                ops.push(operation(
+                    action,
+                    &skill.name,
+                    agent_id,
+                    Some(&src),
+                    &dst,
+                    reason,
+                ));
+                for rel in overrides.keys() {
+                    ops.push(operation(
+                        PlannedAction::RewriteFrontmatter,
+                        &skill.name,
+                        agent_id,
+                        Some(&src.join(rel)),
+                        &dst.join(rel),
+                        format!("{} only loads a {rel} with YAML frontmatter", agent.name),
+                    ));
+                }
+            }
+        }
+    }
+    Ok(ops)
+}
+
+/// Plans [`crate::scanner::sync_all_to_manager_store`]: which agent copies
+/// would be imported into the store, and which would replace the store copy.
+pub fn plan_sync_all_to_manager_store(
+    agents: &[AgentInfo],
+    storage_path: &str,
+) -> Result<Vec<PlannedOperation>, String> {
+    let store_root = expand_tilde(storage_path);
+    // What each store directory holds once the operations so far have run.
+    let mut current: BTreeMap<String, PathBuf> = BTreeMap::new();
+    let mut ops = vec![];
+    for agent in agents {
+        for agent_root in agent_roots(agent) {
+            if !agent_root.is_dir() {
+                continue;
+            }
+            for skill_root in find_skill_roots(&agent_root) {
+                let name = skill_root
+                    .file_name()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                if name.is_empty() || name.starts_with('.') {
+                    continue;
+                }
+                let key = safe_skill_dir_name(&name);
+                let dst = store_root.join(&key);
+                if is_same_path(&skill_root, &dst) {
+                    continue;
+                }
+
+                let existing = current.get(&key).cloned().or_else(|| {
+                    // The store copy, unless an earlier agent already replaced it.
+                    dst.exists().then(|| dst.clone())
+                });
+                let planned = match existing {
+                    None => Some((PlannedAction::Copy, "not in the store yet")),
+                    Some(existing) if should_replace_skill_dir(&skill_root, &existing) => Some((
+                        PlannedAction::Replace,
+                        "its SKILL.md is more complete than the store copy",
+                    )),
+                    Some(existing) => {
+                        current.insert(key.clone(), existing);
+                        None
+                    }
+                };
+                if let Some((action, reason)) = planned {
+                    ops.push(operation(
+                        action,
+                        &key,
+                        Some(&agent.id),
+                        Some(&skill_root),
+                        &dst,
+                        reason,
+                    ));
+                    current.insert(key, skill_root);
+                }
+            }
+        }
+    }
+    Ok(ops)
+}
+
+/// Plans [`crate::store::uninstall_skill`]: the store copy and every agent
+/// copy that would move to the trash.
+pub fn plan_uninstall_skill(
+    skill_name: &str,
+    agents: &[AgentInfo],
+    storage_path: &str,
+) -> Result<Vec<PlannedOperation>, String> {
+    let store_root = expand_tilde(storage_path);
+    let name = safe_skill_dir_name(skill_name);
+    let mut ops = vec![];
+    let src = store_root.join(&name);
+    if src.is_dir() && !src.is_symlink() {
+        ops.push(operation(
+            PlannedAction::Remove,
+            &name,
+            None,
+            None,
+            &src,
+            "store copy moves to the trash",
+        ));
+    }
+    for agent in agents {
+        for root in agent_roots(agent) {
+            let dst = root.join(&name);
+            let Ok(meta) = fs::symlink_metadata(&dst) else {
+                continue;
+            };
+            let reason = if meta.file_type().is_symlink() {
+                "link is removed and recreated on restore"
+            } else if meta.is_dir() {
+                "agent copy moves to the trash"
+            } else {
+                continue;
+            };
+            ops.push(operation(
+                PlannedAction::Remove,
+                &name,
+                Some(&agent.id),
+                None,
+                &dst,
+                reason,
+            ));
+        }
+    }
+    Ok(ops)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distributor::sync_all_skills_distribution;
+    use crate::progress::NoopProgress;
+    use crate::store::list_skills;
+    use crate::utils::{ensure_dir, generate_id};
+
+    fn temp_test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("skills-manager-{name}-{}", generate_id()));
+        ensure_dir(&dir).expect("create temp dir");
+        dir
+    }
+
+    fn write_skill(root: &Path, name: &str, body: &str) {
+        let dir = root.join(name);
+        ensure_dir(&dir).unwrap();
+        fs::write(dir.join("SKILL.md"), body).unwrap();
+    }
+
+    fn agent(id: &str, root: &Path) -> AgentInfo {
+        AgentInfo {
+            id: id.to_string(),
+            name: id.to_string(),
+            default_path: root.to_string_lossy().to_string(),
+            current_path: root.to_string_lossy().to_string(),
+            enabled: true,
+            icon: "test".to_string(),
+            distribution_mode: DistributionMode::Copy,
+        }
+    }
+
+    fn actions(ops: &[PlannedOperation]) -> Vec<(PlannedAction, String)> {
+        ops.iter()
+            .map(|op| (op.action, op.agent_id.clone().unwrap_or_default()))
+            .collect()
+    }
+
+    #[test]
+    fn distribution_plan_matches_what_sync_does() {
+        let tmp = temp_test_dir("plan-distribution");
+        let store = tmp.join("store");
+        let storage_path = store.to_string_lossy().to_string();
+        write_skill(&store, "alpha", "# alpha\n");
+        let agents = vec![
+            agent("fresh", &tmp.join("fresh")),
+            agent("drifted", &tmp.join("drifted")),
+            agent("codex", &tmp.join("codex")),
+            agent("stale", &tmp.join("stale")),
+        ];
+        write_skill(&tmp.join("drifted"), "alpha", "# edited\n");
+        write_skill(&tmp.join("stale"), "alpha", "# alpha\n");
+        let mut skill = list_skills(&storage_path).unwrap().remove(0);
+        skill.enabled_agents = vec!["fresh".into(), "drifted".into(), "codex".into()];
+        let skills = vec![skill];
+
+        let ops = plan_sync_all_skills_distribution(&skills, &agents, &storage_path).unwrap();
+        assert_eq!(
+            actions(&ops),
+            vec![
+                (PlannedAction::Copy, "fresh".to_string()),
+                (PlannedAction::Replace, "drifted".to_string()),
+                (PlannedAction::Copy, "codex".to_string()),
+                (PlannedAction::RewriteFrontmatter, "codex".to_string()),
+                (PlannedAction::Remove, "stale".to_string()),
+            ]
+        );
+        assert!(!tmp.join("fresh").join("alpha").exists());
+
+        sync_all_skills_distribution(&skills, &agents, &storage_path, &NoopProgress).unwrap();
+        assert!(
+            plan_sync_all_skills_distribution(&skills, &agents, &storage_path)
+                .unwrap()
+                .is_empty()
+        );
+
+        let ops = plan_uninstall_skill("alpha", &agents, &storage_path).unwrap();
+        assert_eq!(ops.len(), 4);
+        assert!(ops.iter().all(|op| op.action == PlannedAction::Remove));
+        assert_eq!(ops[0].agent_id, None);
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn adoption_plan_prefers_the_most_complete_copy() {
+        let tmp = temp_test_dir("plan-adoption");
+        let store = tmp.join("store");
+        let storage_path = store.to_string_lossy().to_string();
+        write_skill(&store, "kept", "---\nname: kept\ndescription: d\n---\n");
+        write_skill(&tmp.join("a"), "kept", "# kept\n");
+        write_skill(&tmp.join("a"), "alpha", "# alpha\n");
+        write_skill(
+            &tmp.join("b"),
+            "alpha",
+            "---\nname: alpha\ndescription: d\n---\n",
+        );
+        let agents = vec![agent("a", &tmp.join("a")), agent("b", &tmp.join("b"))];
+
+        let ops = plan_sync_all_to_manager_store(&agents, &storage_path).unwrap();
+        assert_eq!(
+            actions(&ops),
+            vec![
+                (PlannedAction::Copy, "a".to_string()),
+                (PlannedAction::Replace, "b".to_string()),
+            ]
+        );
+        assert!(ops.iter().all(|op| op.skill == "alpha"));
+        assert!(!store.join("alpha").exists());
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+}
@@ -0,0 +1,13 @@
+/// Receives progress logs emitted by long-running operations.
+///
+/// The desktop app forwards them to the frontend as events; headless callers
+/// can pass [`NoopProgress`].
+pub trait ProgressReporter<T>: Send + Sync {
+    fn report(&self, payload: T);
+}
+
+pub struct NoopProgress;
+
+impl<T> ProgressReporter<T> for NoopProgress {
+    fn report(&self, _payload: T) {}
+}
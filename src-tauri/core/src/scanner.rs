@@ -0,0 +1,682 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::digest::digest_dir;
+use crate::distributor::{sync_one_skill, DistributionReport};
+use crate::manifest::{load_manifest, update_manifest};
+use crate::models::{
+    AgentInfo, CopyStrategy, Skill, StartupDetectedSkill, SyncAllToManagerProgressLog,
+};
+use crate::progress::ProgressReporter;
+use crate::store::snapshot_skill;
+use crate::skill_md::{dir_contains_skill_md, find_skill_md_path, parse_frontmatter};
+use crate::utils::{
+    agent_roots, copy_dir_all, is_same_path, manager_store_root, now_iso, safe_skill_dir_name,
+    sync_dir_all_with,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SkillMdQuality {
+    /// 0: no frontmatter, 1: frontmatter without a usable name and
+    /// description, 2: frontmatter with both.
+    metadata_rank: u8,
+    bytes: u64,
+}
+
+fn skill_md_quality(dir: &Path) -> SkillMdQuality {
+    let Some(path) = find_skill_md_path(dir) else {
+        return SkillMdQuality {
+            metadata_rank: 0,
+            bytes: 0,
+        };
+    };
+
+    let bytes = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    let content = fs::read_to_string(&path).unwrap_or_default();
+    let metadata_rank = match parse_frontmatter(&content) {
+        Ok(Some(meta)) => {
+            let filled = |v: &Option<String>| v.as_deref().is_some_and(|s| !s.trim().is_empty());
+            if filled(&meta.name) && filled(&meta.description) {
+                2
+            } else {
+                1
+            }
+        }
+        Ok(None) => 0,
+        Err(_) => 1,
+    };
+
+    SkillMdQuality {
+        metadata_rank,
+        bytes,
+    }
+}
+
+pub(crate) fn should_replace_skill_dir(candidate_src: &Path, existing_dst: &Path) -> bool {
+    let src_q = skill_md_quality(candidate_src);
+    let dst_q = skill_md_quality(existing_dst);
+
+    if src_q.metadata_rank != dst_q.metadata_rank {
+        return src_q.metadata_rank > dst_q.metadata_rank;
+    }
+
+    src_q.bytes > dst_q.bytes
+}
+
+pub fn find_skill_roots(root: &Path) -> Vec<PathBuf> {
+    let mut roots: Vec<PathBuf> = vec![];
+    let mut stack: Vec<PathBuf> = vec![root.to_path_buf()];
+
+    while let Some(dir) = stack.pop() {
+        if !dir.is_dir() {
+            continue;
+        }
+
+        if dir_contains_skill_md(&dir) {
+            roots.push(dir);
+            continue;
+        }
+
+        let entries = match fs::read_dir(&dir) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !path.is_dir() {
+                continue;
+            }
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.is_empty() || name.starts_with('.') {
+                continue;
+            }
+            stack.push(path);
+        }
+    }
+
+    roots
+}
+
+pub fn sync_all_to_manager_store(
+    agents: &[AgentInfo],
+    storage_path: &str,
+    progress: &dyn ProgressReporter<SyncAllToManagerProgressLog>,
+) -> Result<Vec<Skill>, String> {
+    let emit = |payload: SyncAllToManagerProgressLog| progress.report(payload);
+
+    emit(SyncAllToManagerProgressLog {
+        id: "init".to_string(),
+        label: "正在初始化中心库索引...".to_string(),
+        status: "loading".to_string(),
+        progress: 0.0,
+    });
+
+    let result = (|| -> Result<Vec<Skill>, String> {
+        let store_root = manager_store_root(storage_path)?;
+        let mut found: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+
+        emit(SyncAllToManagerProgressLog {
+            id: "init".to_string(),
+            label: "正在初始化中心库索引...".to_string(),
+            status: "success".to_string(),
+            progress: 15.0,
+        });
+
+        let total = agents.len().max(1) as f64;
+        for (idx, agent) in agents.iter().enumerate() {
+            let id = format!("extract-{}", agent.id);
+            let start_progress = 15.0 + (idx as f64 / total) * 70.0;
+            emit(SyncAllToManagerProgressLog {
+                id: id.clone(),
+                label: format!("正在从 {} 目录提取技能资产...", agent.name),
+                status: "loading".to_string(),
+                progress: start_progress,
+            });
+
+            for agent_root in agent_roots(agent) {
+                if !agent_root.exists() || !agent_root.is_dir() {
+                    continue;
+                }
+                for skill_root in find_skill_roots(&agent_root) {
+                    let name = skill_root
+                        .file_name()
+                        .map(|s| s.to_string_lossy().to_string())
+                        .unwrap_or_default();
+                    if name.is_empty() || name.starts_with('.') {
+                        continue;
+                    }
+
+                    let key = safe_skill_dir_name(&name);
+                    let dst = store_root.join(&key);
+                    if !is_same_path(&skill_root, &dst)
+                        && (!dst.exists() || should_replace_skill_dir(&skill_root, &dst))
+                    {
+                        copy_dir_all(&skill_root, &dst)?;
+                    }
+
+                    found.entry(key).or_default().insert(agent.id.clone());
+                }
+            }
+
+            let done_progress = 15.0 + ((idx + 1) as f64 / total) * 70.0;
+            emit(SyncAllToManagerProgressLog {
+                id: id.clone(),
+                label: format!("正在从 {} 目录提取技能资产...", agent.name),
+                status: "success".to_string(),
+                progress: done_progress,
+            });
+        }
+
+        emit(SyncAllToManagerProgressLog {
+            id: "merge".to_string(),
+            label: "正在进行资产去重与元数据合并...".to_string(),
+            status: "loading".to_string(),
+            progress: 90.0,
+        });
+
+        let now = now_iso();
+        let skills = update_manifest(&store_root, |manifest| {
+            for skill in manifest.skills.iter_mut() {
+                skill.enabled_agents = found
+                    .get(&skill.name)
+                    .map(|s| s.iter().cloned().collect())
+                    .unwrap_or_default();
+                skill.last_sync = Some(now.clone());
+                skill.last_update = Some(now.clone());
+            }
+            manifest.skills.clone()
+        })?;
+
+        emit(SyncAllToManagerProgressLog {
+            id: "merge".to_string(),
+            label: "正在进行资产去重与元数据合并...".to_string(),
+            status: "success".to_string(),
+            progress: 100.0,
+        });
+
+        Ok(skills)
+    })();
+
+    match result {
+        Ok(skills) => Ok(skills),
+        Err(err) => {
+            emit(SyncAllToManagerProgressLog {
+                id: "error".to_string(),
+                label: format!("同步失败: {err}"),
+                status: "error".to_string(),
+                progress: 100.0,
+            });
+            Err(err)
+        }
+    }
+}
+
+pub fn collect_untracked_skills(
+    store_root: &Path,
+    agents: &[AgentInfo],
+) -> Result<BTreeMap<String, BTreeSet<String>>, String> {
+    let mut tracked: BTreeSet<String> = BTreeSet::new();
+    for entry in fs::read_dir(store_root)
+        .map_err(|e| format!("Failed to read manager store {}: {e}", store_root.display()))?
+    {
+        let entry = entry.map_err(|e| format!("Failed to read entry: {e}"))?;
+        let file_type = entry
+            .file_type()
+            .map_err(|e| format!("Failed to read file type: {e}"))?;
+        if !file_type.is_dir() {
+            continue;
+        }
+
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.is_empty() || name.starts_with('.') {
+            continue;
+        }
+        tracked.insert(safe_skill_dir_name(&name));
+    }
+
+    let mut untracked: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+
+    for agent in agents {
+        for agent_root in agent_roots(agent) {
+            if !agent_root.exists() || !agent_root.is_dir() {
+                continue;
+            }
+            for skill_root in find_skill_roots(&agent_root) {
+                let name = skill_root
+                    .file_name()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                if name.is_empty() || name.starts_with('.') {
+                    continue;
+                }
+
+                let key = safe_skill_dir_name(&name);
+                if tracked.contains(&key) {
+                    continue;
+                }
+
+                untracked.entry(key).or_default().insert(agent.id.clone());
+            }
+        }
+    }
+
+    Ok(untracked)
+}
+
+pub fn detect_untracked_skills(
+    agents: &[AgentInfo],
+    storage_path: &str,
+) -> Result<Vec<StartupDetectedSkill>, String> {
+    let store_root = manager_store_root(storage_path)?;
+
+    let agent_name_by_id: BTreeMap<String, String> = agents
+        .iter()
+        .map(|agent| (agent.id.clone(), agent.name.clone()))
+        .collect();
+
+    let untracked = collect_untracked_skills(&store_root, agents)?;
+    let mut detected: Vec<StartupDetectedSkill> = vec![];
+
+    for (name, source_agent_ids_set) in untracked {
+        let source_agent_ids: Vec<String> = source_agent_ids_set.into_iter().collect();
+        let source_agent_names: Vec<String> = source_agent_ids
+            .iter()
+            .map(|id| {
+                agent_name_by_id
+                    .get(id)
+                    .cloned()
+                    .unwrap_or_else(|| id.clone())
+            })
+            .collect();
+
+        detected.push(StartupDetectedSkill {
+            id: name.clone(),
+            name,
+            source_agent_ids,
+            source_agent_names,
+        });
+    }
+
+    Ok(detected)
+}
+
+pub fn sync_selected_skills_to_manager_store(
+    agents: &[AgentInfo],
+    skill_names: &[String],
+    storage_path: &str,
+) -> Result<Vec<Skill>, String> {
+    if skill_names.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let selected: BTreeSet<String> = skill_names
+        .iter()
+        .map(|name| safe_skill_dir_name(name))
+        .collect();
+
+    let store_root = manager_store_root(storage_path)?;
+    let mut found: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+
+    for agent in agents.iter() {
+        for agent_root in agent_roots(agent) {
+            if !agent_root.exists() || !agent_root.is_dir() {
+                continue;
+            }
+
+            for skill_root in find_skill_roots(&agent_root) {
+                let name = skill_root
+                    .file_name()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                if name.is_empty() || name.starts_with('.') {
+                    continue;
+                }
+
+                let key = safe_skill_dir_name(&name);
+                if !selected.contains(&key) {
+                    continue;
+                }
+
+                let dst = store_root.join(&key);
+                if !is_same_path(&skill_root, &dst)
+                    && (!dst.exists() || should_replace_skill_dir(&skill_root, &dst))
+                {
+                    copy_dir_all(&skill_root, &dst)?;
+                }
+
+                found.entry(key).or_default().insert(agent.id.clone());
+            }
+        }
+    }
+
+    let now = now_iso();
+    let synced = update_manifest(&store_root, |manifest| {
+        let mut synced: Vec<Skill> = vec![];
+        for (name, agent_ids) in &found {
+            if let Some(skill) = manifest.get_mut(name) {
+                skill.enabled_agents = agent_ids.iter().cloned().collect();
+                skill.last_sync = Some(now.clone());
+                skill.last_update = Some(now.clone());
+                synced.push(skill.clone());
+            }
+        }
+        synced
+    })?;
+
+    Ok(synced)
+}
+
+/// Result of [`pull_skill_from_agent`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PullReport {
+    pub skill: Skill,
+    /// Where the previous store version was saved; `None` when the agent copy
+    /// already matched the store and nothing was pulled.
+    pub snapshot: Option<String>,
+    pub distribution: DistributionReport,
+}
+
+/// Copies one agent's edited copy of a skill into the store, after saving the
+/// current store version as a snapshot, then redistributes it to the other
+/// enabled agents.
+pub fn pull_skill_from_agent(
+    skill_name: &str,
+    agent_id: &str,
+    agents: &[AgentInfo],
+    storage_path: &str,
+) -> Result<PullReport, String> {
+    let store_root = manager_store_root(storage_path)?;
+    let skill = load_manifest(&store_root)?
+        .get(skill_name)
+        .cloned()
+        .ok_or_else(|| format!("Skill not found in store: {skill_name}"))?;
+    let agent = agents
+        .iter()
+        .find(|agent| agent.id == agent_id)
+        .ok_or_else(|| format!("Unknown agent: {agent_id}"))?;
+
+    let store_dir = store_root.join(&skill.name);
+    let agent_dir = agent_roots(agent)
+        .into_iter()
+        .map(|root| root.join(&skill.name))
+        .find(|dir| dir.is_dir())
+        .ok_or_else(|| format!("Agent {agent_id} has no copy of {}", skill.name))?;
+    if is_same_path(&agent_dir, &store_dir) {
+        return Err(format!(
+            "{} is linked to the store; its edits are already there",
+            agent_dir.display()
+        ));
+    }
+
+    let mut snapshot = None;
+    if digest_dir(&agent_dir)?.content_root() != digest_dir(&store_dir)?.content_root() {
+        snapshot = Some(snapshot_skill(&store_root, &skill.name)?);
+        // Always a real copy: a hardlinked store would keep tracking the agent dir.
+        sync_dir_all_with(&agent_dir, &store_dir, CopyStrategy::Copy, &BTreeMap::new())?;
+        let now = now_iso();
+        update_manifest(&store_root, |manifest| {
+            if let Some(record) = manifest.get_mut(&skill.name) {
+                record.last_update = Some(now);
+            }
+        })?;
+    }
+
+    // The source agent keeps the skill it was pulled from, even if it was not
+    // enabled for it before; otherwise the sync would remove its copy.
+    let mut enabled = skill.enabled_agents.clone();
+    if !enabled.iter().any(|id| id == agent_id) {
+        enabled.push(agent_id.to_string());
+    }
+    let distribution = sync_one_skill(&store_root, &skill.name, &enabled, agents)?;
+    let skill = load_manifest(&store_root)?
+        .get(&skill.name)
+        .cloned()
+        .ok_or_else(|| format!("Skill not found in store: {skill_name}"))?;
+
+    Ok(PullReport {
+        skill,
+        snapshot: snapshot.map(|path| path.to_string_lossy().to_string()),
+        distribution,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::DistributionMode;
+    use crate::progress::NoopProgress;
+    use crate::utils::{ensure_dir, generate_id};
+
+    fn temp_test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("skills-manager-{name}-{}", generate_id()));
+        ensure_dir(&dir).expect("create temp dir");
+        dir
+    }
+
+    fn write_file(path: &Path, content: &str) {
+        if let Some(parent) = path.parent() {
+            ensure_dir(parent).expect("create parent dir");
+        }
+        fs::write(path, content).expect("write file");
+    }
+
+    fn agent(id: &str, name: &str, root: &Path, enabled: bool) -> AgentInfo {
+        AgentInfo {
+            id: id.to_string(),
+            name: name.to_string(),
+            default_path: root.to_string_lossy().to_string(),
+            current_path: root.to_string_lossy().to_string(),
+            enabled,
+            icon: "test".to_string(),
+            distribution_mode: DistributionMode::Copy,
+        }
+    }
+
+    #[test]
+    fn pull_skill_from_agent_snapshots_and_redistributes() {
+        let tmp = temp_test_dir("pull-from-agent");
+        let store_root = tmp.join("store");
+        let a_root = tmp.join("a");
+        let b_root = tmp.join("b");
+        write_file(&store_root.join("foo").join("SKILL.md"), "# foo\n");
+
+        let agents = vec![agent("a", "A", &a_root, true), agent("b", "B", &b_root, true)];
+        let enabled = ["a".to_string(), "b".to_string()];
+        sync_one_skill(&store_root, "foo", &enabled, &agents).unwrap();
+
+        write_file(&a_root.join("foo").join("SKILL.md"), "# foo\nedited in place\n");
+        let storage_path = store_root.to_string_lossy().to_string();
+        let report = pull_skill_from_agent("foo", "a", &agents, &storage_path).unwrap();
+
+        let snapshot = PathBuf::from(report.snapshot.expect("snapshot taken"));
+        assert_eq!(fs::read_to_string(snapshot.join("SKILL.md")).unwrap(), "# foo\n");
+        for dir in [store_root.join("foo"), b_root.join("foo")] {
+            assert_eq!(
+                fs::read_to_string(dir.join("SKILL.md")).unwrap(),
+                "# foo\nedited in place\n"
+            );
+        }
+        assert_eq!((report.distribution.updated, report.distribution.unchanged), (1, 1));
+        assert_eq!(load_manifest(&store_root).unwrap().skills.len(), 1);
+
+        let again = pull_skill_from_agent("foo", "a", &agents, &storage_path).unwrap();
+        assert!(again.snapshot.is_none());
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn pull_skill_from_agent_keeps_the_copy_of_an_agent_not_enabled_for_it() {
+        let tmp = temp_test_dir("pull-from-other-agent");
+        let store_root = tmp.join("store");
+        let a_root = tmp.join("a");
+        let b_root = tmp.join("b");
+        write_file(&store_root.join("foo").join("SKILL.md"), "# foo\n");
+
+        let agents = vec![agent("a", "A", &a_root, true), agent("b", "B", &b_root, true)];
+        sync_one_skill(&store_root, "foo", &["a".to_string()], &agents).unwrap();
+        write_file(&b_root.join("foo").join("SKILL.md"), "# foo\nwritten by b\n");
+
+        let storage_path = store_root.to_string_lossy().to_string();
+        let report = pull_skill_from_agent("foo", "b", &agents, &storage_path).unwrap();
+
+        for dir in [store_root.join("foo"), a_root.join("foo"), b_root.join("foo")] {
+            assert_eq!(
+                fs::read_to_string(dir.join("SKILL.md")).unwrap(),
+                "# foo\nwritten by b\n"
+            );
+        }
+        assert_eq!(report.skill.enabled_agents, ["a", "b"]);
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn should_replace_prefers_complete_metadata_over_size() {
+        let tmp = temp_test_dir("replace-prefers-metadata");
+        let complete = tmp.join("complete");
+        let partial = tmp.join("partial");
+        write_file(
+            &complete.join("SKILL.md"),
+            "---\nname: foo\ndescription: does foo\n---\n",
+        );
+        write_file(
+            &partial.join("SKILL.md"),
+            "---\nname: foo\n---\n\n# foo\n\nA much longer body without a description.\n",
+        );
+
+        assert!(should_replace_skill_dir(&complete, &partial));
+        assert!(!should_replace_skill_dir(&partial, &complete));
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn sync_all_prefers_skill_with_yaml_frontmatter() {
+        let tmp = temp_test_dir("sync-all-prefers-frontmatter");
+        let store_root = tmp.join("store");
+        let agent_a_root = tmp.join("agent-a");
+        let agent_b_root = tmp.join("agent-b");
+
+        ensure_dir(&store_root).unwrap();
+        ensure_dir(&agent_a_root).unwrap();
+        ensure_dir(&agent_b_root).unwrap();
+
+        let skill_dir = "agent-browser";
+        write_file(
+            &agent_a_root.join(skill_dir).join("SKILL.md"),
+            "# agent-browser\n\n---\n",
+        );
+        write_file(
+            &agent_b_root.join(skill_dir).join("SKILL.md"),
+            "---\nname: agent-browser\n---\n\n# agent-browser\n",
+        );
+
+        let agents = vec![
+            agent("a", "A", &agent_a_root, true),
+            agent("b", "B", &agent_b_root, true),
+        ];
+
+        let _skills =
+            sync_all_to_manager_store(&agents, &store_root.to_string_lossy(), &NoopProgress)
+                .unwrap();
+
+        let content = fs::read_to_string(store_root.join(skill_dir).join("SKILL.md")).unwrap();
+        assert!(
+            content.starts_with("---\n") || content.starts_with("---\r\n"),
+            "store should keep the version with YAML frontmatter"
+        );
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn sync_all_keeps_store_dir_behind_agent_symlink() {
+        let tmp = temp_test_dir("sync-all-symlinked-agent");
+        let store_root = tmp.join("store");
+        let agent_root = tmp.join("agent");
+        ensure_dir(&agent_root).unwrap();
+        write_file(
+            &store_root.join("linked").join("SKILL.md"),
+            "---\nname: linked\n---\n",
+        );
+
+        if crate::utils::symlink_dir(&store_root.join("linked"), &agent_root.join("linked")).is_err() {
+            let _ = fs::remove_dir_all(&tmp);
+            return;
+        }
+
+        let agents = vec![agent("a", "A", &agent_root, true)];
+        let skills =
+            sync_all_to_manager_store(&agents, &store_root.to_string_lossy(), &NoopProgress).unwrap();
+
+        assert_eq!(skills.len(), 1);
+        assert_eq!(skills[0].enabled_agents, vec!["a".to_string()]);
+        assert!(store_root.join("linked").join("SKILL.md").is_file());
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn detect_startup_untracked_skills_does_not_copy_to_store() {
+        let tmp = temp_test_dir("startup-detect-no-copy");
+        let store_root = tmp.join("store");
+        let codex_root = tmp.join("codex");
+
+        ensure_dir(&store_root).unwrap();
+        ensure_dir(&codex_root).unwrap();
+
+        write_file(
+            &codex_root.join("new-skill").join("SKILL.md"),
+            "---\nname: new-skill\n---\n",
+        );
+
+        let agents = vec![agent("codex", "Codex", &codex_root, true)];
+        let detected =
+            detect_untracked_skills(&agents, &store_root.to_string_lossy()).unwrap();
+
+        assert_eq!(detected.len(), 1);
+        assert_eq!(detected[0].name, "new-skill");
+        assert!(!store_root.join("new-skill").is_dir());
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn sync_selected_skills_to_manager_store_only_syncs_selected() {
+        let tmp = temp_test_dir("sync-selected-only");
+        let store_root = tmp.join("store");
+        let codex_root = tmp.join("codex");
+
+        ensure_dir(&store_root).unwrap();
+        ensure_dir(&codex_root).unwrap();
+
+        write_file(
+            &codex_root.join("alpha").join("SKILL.md"),
+            "---\nname: alpha\n---\n",
+        );
+        write_file(
+            &codex_root.join("beta").join("SKILL.md"),
+            "---\nname: beta\n---\n",
+        );
+
+        let agents = vec![agent("codex", "Codex", &codex_root, true)];
+        let synced = sync_selected_skills_to_manager_store(
+            &agents,
+            &["alpha".to_string()],
+            &store_root.to_string_lossy(),
+        )
+        .unwrap();
+
+        assert_eq!(synced.len(), 1);
+        assert_eq!(synced[0].name, "alpha");
+        assert!(store_root.join("alpha").join("SKILL.md").exists());
+        assert!(!store_root.join("beta").join("SKILL.md").exists());
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+}
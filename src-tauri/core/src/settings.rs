@@ -0,0 +1,86 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::CopyStrategy;
+use crate::utils::{ensure_dir, write_file_atomic};
+
+const SETTINGS_DIR: &str = ".skillsm";
+const SETTINGS_FILE: &str = "settings.json";
+/// Distribution is mostly disk-bound, so more workers rarely help.
+pub const DEFAULT_MAX_SYNC_WORKERS: usize = 8;
+
+/// Per-store preferences, persisted at `<store>/.skillsm/settings.json`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoreSettings {
+    #[serde(default)]
+    pub copy_strategy: CopyStrategy,
+    /// How many agent roots a bulk sync writes at once; one available core
+    /// each, up to [`DEFAULT_MAX_SYNC_WORKERS`], when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sync_concurrency: Option<usize>,
+}
+
+impl StoreSettings {
+    pub fn sync_workers(&self) -> usize {
+        self.sync_concurrency
+            .unwrap_or_else(|| {
+                thread::available_parallelism()
+                    .map_or(1, |n| n.get())
+                    .min(DEFAULT_MAX_SYNC_WORKERS)
+            })
+            .max(1)
+    }
+}
+
+pub fn settings_path(store_root: &Path) -> PathBuf {
+    store_root.join(SETTINGS_DIR).join(SETTINGS_FILE)
+}
+
+/// Loads the store settings, falling back to defaults when none were saved yet.
+pub fn load_settings(store_root: &Path) -> Result<StoreSettings, String> {
+    let path = settings_path(store_root);
+    if !path.exists() {
+        return Ok(StoreSettings::default());
+    }
+
+    let text = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read settings {}: {e}", path.display()))?;
+    serde_json::from_str(&text)
+        .map_err(|e| format!("Failed to parse settings {}: {e}", path.display()))
+}
+
+pub fn save_settings(store_root: &Path, settings: &StoreSettings) -> Result<(), String> {
+    ensure_dir(&store_root.join(SETTINGS_DIR))?;
+    let text = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to serialize settings: {e}"))?;
+    write_file_atomic(&settings_path(store_root), text.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::generate_id;
+
+    #[test]
+    fn settings_roundtrip_and_default_when_missing() {
+        let root = std::env::temp_dir().join(format!("skills-manager-settings-{}", generate_id()));
+        ensure_dir(&root).unwrap();
+        assert_eq!(load_settings(&root).unwrap(), StoreSettings::default());
+
+        let settings = StoreSettings {
+            copy_strategy: CopyStrategy::Reflink,
+            sync_concurrency: Some(4),
+        };
+        save_settings(&root, &settings).unwrap();
+        assert_eq!(load_settings(&root).unwrap(), settings);
+        assert!(fs::read_to_string(settings_path(&root))
+            .unwrap()
+            .contains("\"copyStrategy\": \"reflink\""));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
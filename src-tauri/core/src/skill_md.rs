@@ -0,0 +1,307 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::models::SkillMetadata;
+use crate::utils::{manager_store_root, safe_skill_dir_name, write_file_atomic};
+
+pub fn dir_contains_skill_md(dir: &Path) -> bool {
+    let entries = match fs::read_dir(dir) {
+        Ok(v) => v,
+        Err(_) => return false,
+    };
+
+    for entry in entries.flatten() {
+        let file_type = match entry.file_type() {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        if !file_type.is_file() {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.eq_ignore_ascii_case("skill.md") {
+            return true;
+        }
+    }
+
+    false
+}
+
+pub fn find_skill_md_path(dir: &Path) -> Option<PathBuf> {
+    let entries = fs::read_dir(dir).ok()?;
+    for entry in entries.flatten() {
+        let file_type = entry.file_type().ok()?;
+        if !file_type.is_file() {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.eq_ignore_ascii_case("skill.md") {
+            return Some(entry.path());
+        }
+    }
+    None
+}
+
+pub fn skill_md_starts_with_yaml_frontmatter(content: &str) -> bool {
+    let trimmed = content.trim_start_matches('\u{feff}');
+    trimmed.starts_with("---\n") || trimmed.starts_with("---\r\n")
+}
+
+pub(crate) fn yaml_single_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Splits a SKILL.md into its YAML frontmatter and the body after the closing
+/// `---`. `None` when the file does not start with a complete frontmatter block.
+pub fn split_frontmatter(content: &str) -> Option<(&str, &str)> {
+    let trimmed = content.trim_start_matches('\u{feff}');
+    if !skill_md_starts_with_yaml_frontmatter(trimmed) {
+        return None;
+    }
+    let after_open = &trimmed[trimmed.find('\n')? + 1..];
+
+    let mut offset = 0;
+    for line in after_open.split_inclusive('\n') {
+        let l = line.trim_end_matches(['\r', '\n']);
+        if l == "---" || l == "..." {
+            return Some((&after_open[..offset], &after_open[offset + line.len()..]));
+        }
+        offset += line.len();
+    }
+    None
+}
+
+fn yaml_scalar(value: &serde_yaml::Value) -> Option<String> {
+    match value {
+        serde_yaml::Value::String(s) => Some(s.clone()),
+        serde_yaml::Value::Number(n) => Some(n.to_string()),
+        serde_yaml::Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+fn yaml_list(value: &serde_yaml::Value) -> Vec<String> {
+    match value {
+        serde_yaml::Value::Sequence(items) => items.iter().filter_map(yaml_scalar).collect(),
+        serde_yaml::Value::String(s) => {
+            let items: Vec<&str> = if s.contains(',') {
+                s.split(',').collect()
+            } else {
+                s.split_whitespace().collect()
+            };
+            items
+                .into_iter()
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(str::to_string)
+                .collect()
+        }
+        _ => vec![],
+    }
+}
+
+fn metadata_from_mapping(mapping: &serde_yaml::Mapping) -> SkillMetadata {
+    let mut meta = SkillMetadata::default();
+    for (key, value) in mapping {
+        let Some(key) = yaml_scalar(key) else {
+            continue;
+        };
+        match key.as_str() {
+            "name" => meta.name = yaml_scalar(value),
+            "description" => meta.description = yaml_scalar(value),
+            "version" => meta.version = yaml_scalar(value),
+            "license" => meta.license = yaml_scalar(value),
+            "allowed-tools" | "allowed_tools" => meta.allowed_tools = yaml_list(value),
+            "tags" => meta.tags = yaml_list(value),
+            _ => {
+                if let Ok(json) = serde_json::to_value(value) {
+                    meta.extra.insert(key, json);
+                }
+            }
+        }
+    }
+    meta
+}
+
+/// Parses the frontmatter of a SKILL.md. `Ok(None)` when there is no
+/// frontmatter block, `Err` when the block is not a valid YAML mapping.
+pub fn parse_frontmatter(content: &str) -> Result<Option<SkillMetadata>, String> {
+    let Some((yaml, _)) = split_frontmatter(content) else {
+        return Ok(None);
+    };
+    if yaml.trim().is_empty() {
+        return Ok(Some(SkillMetadata::default()));
+    }
+    match serde_yaml::from_str::<serde_yaml::Value>(yaml) {
+        Ok(serde_yaml::Value::Mapping(mapping)) => Ok(Some(metadata_from_mapping(&mapping))),
+        Ok(serde_yaml::Value::Null) => Ok(Some(SkillMetadata::default())),
+        Ok(_) => Err("Frontmatter is not a YAML mapping".to_string()),
+        Err(e) => Err(format!("Invalid YAML frontmatter: {e}")),
+    }
+}
+
+/// Like [`parse_frontmatter`], but frontmatter that is not valid YAML (most
+/// often an unquoted `: ` inside a description) still yields its top-level
+/// `key: value` lines as plain strings.
+pub fn parse_frontmatter_lenient(content: &str) -> Option<SkillMetadata> {
+    if let Ok(meta) = parse_frontmatter(content) {
+        return meta;
+    }
+    let (yaml, _) = split_frontmatter(content)?;
+
+    let mut mapping = serde_yaml::Mapping::new();
+    for line in yaml.lines() {
+        if line.starts_with([' ', '\t', '#', '-']) {
+            continue;
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let mut value = value.trim();
+        if value.len() >= 2
+            && ((value.starts_with('"') && value.ends_with('"'))
+                || (value.starts_with('\'') && value.ends_with('\'')))
+        {
+            value = &value[1..value.len() - 1];
+        }
+        if value.is_empty() {
+            continue;
+        }
+        mapping.insert(key.trim().into(), value.into());
+    }
+    Some(metadata_from_mapping(&mapping))
+}
+
+/// Reads the metadata of the SKILL.md in `dir`, if there is one.
+pub fn read_skill_metadata(dir: &Path) -> Option<SkillMetadata> {
+    let content = fs::read_to_string(find_skill_md_path(dir)?).ok()?;
+    parse_frontmatter_lenient(&content)
+}
+
+pub fn extract_description_from_skill_md(content: &str) -> Option<String> {
+    let description = parse_frontmatter_lenient(content)?.description?;
+    let description = description.trim();
+    if description.is_empty() {
+        None
+    } else {
+        Some(description.to_string())
+    }
+}
+
+/// Returns `content` with a minimal `name:` frontmatter prepended, or `None`
+/// when it already starts with one.
+pub fn with_yaml_frontmatter(content: &str, skill_name: &str) -> Option<String> {
+    if skill_md_starts_with_yaml_frontmatter(content) {
+        return None;
+    }
+
+    let name = skill_name.trim();
+    let yaml_name = if name.is_empty() {
+        yaml_single_quote("skill")
+    } else {
+        yaml_single_quote(name)
+    };
+
+    Some(format!("---\nname: {yaml_name}\n---\n\n{content}"))
+}
+
+pub fn ensure_skill_md_has_yaml_frontmatter(path: &Path, skill_name: &str) -> Result<(), String> {
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    let Some(new_content) = with_yaml_frontmatter(&content, skill_name) else {
+        return Ok(());
+    };
+
+    // Replace rather than write through: a hardlinked copy shares its inode with the store.
+    write_file_atomic(path, new_content.as_bytes())
+}
+
+pub fn get_skill_metadata(
+    skill_name: &str,
+    storage_path: &str,
+) -> Result<Option<SkillMetadata>, String> {
+    let store_root = manager_store_root(storage_path)?;
+    let skill_dir = store_root.join(safe_skill_dir_name(skill_name));
+    let Some(path) = find_skill_md_path(&skill_dir) else {
+        return Ok(None);
+    };
+
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    Ok(parse_frontmatter_lenient(&content))
+}
+
+pub fn get_skill_description(skill_name: &str, storage_path: &str) -> Result<Option<String>, String> {
+    let store_root = manager_store_root(storage_path)?;
+    let skill_dir = store_root.join(safe_skill_dir_name(skill_name));
+    let Some(path) = find_skill_md_path(&skill_dir) else {
+        return Ok(None);
+    };
+
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    Ok(extract_description_from_skill_md(&content))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extract_description_from_yaml_frontmatter_single_line() {
+        let md = "---\nname: demo\ndescription: hello world\n---\n\n# demo\n";
+        assert_eq!(
+            extract_description_from_skill_md(md),
+            Some("hello world".to_string())
+        );
+    }
+
+    #[test]
+    fn extract_description_from_yaml_frontmatter_block_scalar() {
+        let md = "---\nname: demo\ndescription: |\n  line1\n  line2\n---\n\n# demo\n";
+        assert_eq!(
+            extract_description_from_skill_md(md),
+            Some("line1\nline2".to_string())
+        );
+    }
+
+    #[test]
+    fn extract_description_handles_flow_scalars_escapes_and_folding() {
+        let md = "---\nname: demo\ndescription: \"say \\\"hi\\\"\\tnow\"\n---\n";
+        assert_eq!(
+            extract_description_from_skill_md(md),
+            Some("say \"hi\"\tnow".to_string())
+        );
+
+        let md = "---\ndescription: first line\n  continues here\n---\n";
+        assert_eq!(
+            extract_description_from_skill_md(md),
+            Some("first line continues here".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_frontmatter_fills_metadata_and_keeps_unknown_keys() {
+        let md = "\u{feff}---\r\nname: pdf\r\ndescription: >-\r\n  Fill PDF\r\n  forms\r\nversion: 1.2\r\nlicense: MIT\r\nallowed-tools: Read, Grep\r\ntags: [docs, pdf]\r\nmetadata:\r\n  owner: team\r\n---\r\n# PDF\r\n";
+        let meta = parse_frontmatter(md).unwrap().unwrap();
+        assert_eq!(meta.name.as_deref(), Some("pdf"));
+        assert_eq!(meta.description.as_deref(), Some("Fill PDF forms"));
+        assert_eq!(meta.version.as_deref(), Some("1.2"));
+        assert_eq!(meta.license.as_deref(), Some("MIT"));
+        assert_eq!(meta.allowed_tools, vec!["Read", "Grep"]);
+        assert_eq!(meta.tags, vec!["docs", "pdf"]);
+        assert_eq!(meta.extra["metadata"], serde_json::json!({ "owner": "team" }));
+
+        assert_eq!(parse_frontmatter("# no frontmatter\n").unwrap(), None);
+        assert_eq!(parse_frontmatter("---\nname: unclosed\n").unwrap(), None);
+    }
+
+    #[test]
+    fn lenient_parse_recovers_from_invalid_yaml() {
+        let md = "---\nname: demo\ndescription: Use when: the user asks\n---\n";
+        assert!(parse_frontmatter(md).is_err());
+        let meta = parse_frontmatter_lenient(md).unwrap();
+        assert_eq!(meta.name.as_deref(), Some("demo"));
+        assert_eq!(meta.description.as_deref(), Some("Use when: the user asks"));
+    }
+}
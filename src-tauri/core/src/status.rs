@@ -0,0 +1,277 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::digest::{digest_dir, digest_dir_with};
+use crate::distributor::skill_md_overrides;
+use crate::manifest::load_manifest;
+use crate::models::{AgentInfo, Skill};
+use crate::utils::{agent_roots, manager_store_root, safe_skill_dir_name};
+
+/// What an agent root holds for a skill, compared with the store.
+///
+/// Variants are ordered by severity so an agent with several roots reports the worst one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DistributionState {
+    InSync,
+    /// The store changed after the last distribution and the target has not caught up.
+    Stale,
+    Missing,
+    /// The target was edited in the agent directory since the last distribution.
+    ModifiedLocally,
+    PresentButNotEnabled,
+}
+
+impl DistributionState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::InSync => "in_sync",
+            Self::Stale => "stale",
+            Self::Missing => "missing",
+            Self::ModifiedLocally => "modified_locally",
+            Self::PresentButNotEnabled => "present_but_not_enabled",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RootDistributionStatus {
+    pub path: String,
+    pub state: DistributionState,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentDistributionStatus {
+    pub agent_id: String,
+    pub state: DistributionState,
+    pub roots: Vec<RootDistributionStatus>,
+}
+
+/// One row of the status matrix. Agents that neither have the skill enabled
+/// nor hold a copy of it are left out.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillDistributionStatus {
+    pub skill: String,
+    pub agents: Vec<AgentDistributionStatus>,
+}
+
+fn links_to(dst: &Path, src: &Path) -> bool {
+    let is_link = fs::symlink_metadata(dst).is_ok_and(|meta| meta.file_type().is_symlink());
+    is_link
+        && match (fs::canonicalize(dst), fs::canonicalize(src)) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => false,
+        }
+}
+
+fn enabled_root_state(
+    dst: &Path,
+    src: &Path,
+    expected: &str,
+    verbatim: bool,
+    store_changed: bool,
+) -> DistributionState {
+    if fs::symlink_metadata(dst).is_err() {
+        return DistributionState::Missing;
+    }
+    if verbatim && links_to(dst, src) {
+        return DistributionState::InSync;
+    }
+    // Permission bits other than exec differ with the filesystem and umask.
+    match digest_dir_with(dst, false, &BTreeMap::new()) {
+        Ok(digest) if digest.content_root() == expected => DistributionState::InSync,
+        _ if store_changed => DistributionState::Stale,
+        _ => DistributionState::ModifiedLocally,
+    }
+}
+
+fn skill_status(
+    store_root: &Path,
+    skill: &Skill,
+    agents: &[AgentInfo],
+) -> Result<SkillDistributionStatus, String> {
+    let dir_name = safe_skill_dir_name(&skill.name);
+    let src = store_root.join(&dir_name);
+    let store_digest = digest_dir(&src)?.content_root();
+    // Without a recorded digest there is nothing to blame on the store.
+    let store_changed = skill
+        .last_sync_digest
+        .as_ref()
+        .is_some_and(|recorded| recorded != &store_digest);
+
+    let mut cells = vec![];
+    for agent in agents.iter().filter(|agent| agent.enabled) {
+        let enabled = skill.enabled_agents.iter().any(|id| id == &agent.id);
+        let overrides = skill_md_overrides(agent, &src, &skill.name);
+        let expected = if overrides.is_empty() {
+            store_digest.clone()
+        } else {
+            digest_dir_with(&src, true, &overrides)?.content_root()
+        };
+
+        let mut roots = vec![];
+        for root in agent_roots(agent) {
+            let dst = root.join(&dir_name);
+            let state = if enabled {
+                enabled_root_state(&dst, &src, &expected, overrides.is_empty(), store_changed)
+            } else if fs::symlink_metadata(&dst).is_ok() {
+                DistributionState::PresentButNotEnabled
+            } else {
+                continue;
+            };
+            roots.push(RootDistributionStatus {
+                path: dst.to_string_lossy().to_string(),
+                state,
+            });
+        }
+
+        let Some(state) = roots.iter().map(|root| root.state).max() else {
+            continue;
+        };
+        cells.push(AgentDistributionStatus {
+            agent_id: agent.id.clone(),
+            state,
+            roots,
+        });
+    }
+
+    Ok(SkillDistributionStatus {
+        skill: skill.name.clone(),
+        agents: cells,
+    })
+}
+
+/// Reads every agent root back and compares it with the store, for every skill
+/// in the manifest.
+pub fn get_distribution_status(
+    agents: &[AgentInfo],
+    storage_path: &str,
+) -> Result<Vec<SkillDistributionStatus>, String> {
+    let store_root = manager_store_root(storage_path)?;
+    load_manifest(&store_root)?
+        .skills
+        .iter()
+        .map(|skill| skill_status(&store_root, skill, agents))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distributor::sync_one_skill;
+    use crate::models::DistributionMode;
+    use crate::utils::{ensure_dir, generate_id};
+
+    fn agent(id: &str, root: &Path) -> AgentInfo {
+        AgentInfo {
+            id: id.to_string(),
+            name: id.to_string(),
+            default_path: root.to_string_lossy().to_string(),
+            current_path: root.to_string_lossy().to_string(),
+            enabled: true,
+            icon: "test".to_string(),
+            distribution_mode: DistributionMode::Copy,
+        }
+    }
+
+    fn state_of(store: &Path, agents: &[AgentInfo], agent_id: &str) -> Option<DistributionState> {
+        get_distribution_status(agents, &store.to_string_lossy())
+            .unwrap()
+            .into_iter()
+            .find(|row| row.skill == "alpha")?
+            .agents
+            .into_iter()
+            .find(|cell| cell.agent_id == agent_id)
+            .map(|cell| cell.state)
+    }
+
+    #[test]
+    fn status_distinguishes_every_state() {
+        let tmp = std::env::temp_dir().join(format!("skills-manager-status-{}", generate_id()));
+        let store = tmp.join("store");
+        let a_root = tmp.join("a");
+        let codex_root = tmp.join("codex");
+        let other_root = tmp.join("other");
+        ensure_dir(&store.join("alpha")).unwrap();
+        fs::write(store.join("alpha").join("SKILL.md"), "# alpha\n").unwrap();
+
+        let agents = vec![
+            agent("a", &a_root),
+            agent("codex", &codex_root),
+            agent("other", &other_root),
+        ];
+        let enabled = ["a".to_string(), "codex".to_string()];
+        sync_one_skill(&store, "alpha", &enabled, &agents).unwrap();
+        // Left behind by hand, e.g. a copy made outside the manager.
+        ensure_dir(&other_root.join("alpha")).unwrap();
+
+        assert_eq!(
+            state_of(&store, &agents, "a"),
+            Some(DistributionState::InSync)
+        );
+        assert_eq!(
+            state_of(&store, &agents, "codex"),
+            Some(DistributionState::InSync)
+        );
+        assert_eq!(
+            state_of(&store, &agents, "other"),
+            Some(DistributionState::PresentButNotEnabled)
+        );
+
+        fs::write(a_root.join("alpha").join("notes.md"), "mine\n").unwrap();
+        assert_eq!(
+            state_of(&store, &agents, "a"),
+            Some(DistributionState::ModifiedLocally)
+        );
+
+        sync_one_skill(&store, "alpha", &enabled, &agents).unwrap();
+        fs::write(store.join("alpha").join("SKILL.md"), "# alpha v2\n").unwrap();
+        assert_eq!(
+            state_of(&store, &agents, "a"),
+            Some(DistributionState::Stale)
+        );
+
+        fs::remove_dir_all(codex_root.join("alpha")).unwrap();
+        assert_eq!(
+            state_of(&store, &agents, "codex"),
+            Some(DistributionState::Missing)
+        );
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn permission_bits_other_than_exec_do_not_mark_a_copy_modified() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp = std::env::temp_dir().join(format!("skills-manager-status-{}", generate_id()));
+        let store = tmp.join("store");
+        let a_root = tmp.join("a");
+        ensure_dir(&store.join("alpha")).unwrap();
+        fs::write(store.join("alpha").join("SKILL.md"), "# alpha\n").unwrap();
+        let agents = vec![agent("a", &a_root)];
+        sync_one_skill(&store, "alpha", &["a".to_string()], &agents).unwrap();
+
+        let copy = a_root.join("alpha").join("SKILL.md");
+        fs::set_permissions(&copy, fs::Permissions::from_mode(0o600)).unwrap();
+        assert_eq!(
+            state_of(&store, &agents, "a"),
+            Some(DistributionState::InSync)
+        );
+
+        fs::set_permissions(&copy, fs::Permissions::from_mode(0o700)).unwrap();
+        assert_eq!(
+            state_of(&store, &agents, "a"),
+            Some(DistributionState::ModifiedLocally)
+        );
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+}
@@ -0,0 +1,470 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::journal::{recover_store, RecoveryReport};
+use crate::manifest::{load_manifest, manifest_exists, update_manifest};
+use crate::models::{AgentInfo, Skill};
+use crate::trash::{trash_skill, trash_store, TrashEntry};
+use crate::utils::{
+    copy_dir_all, ensure_dir, expand_tilde, is_same_path, manager_store_root, now_iso,
+    safe_skill_dir_name,
+};
+
+pub fn read_store_skill_names(store_root: &Path) -> Result<Vec<String>, String> {
+    let mut names: Vec<String> = vec![];
+
+    for entry in fs::read_dir(store_root)
+        .map_err(|e| format!("Failed to read manager store {}: {e}", store_root.display()))?
+    {
+        let entry = entry.map_err(|e| format!("Failed to read entry: {e}"))?;
+        let file_type = entry
+            .file_type()
+            .map_err(|e| format!("Failed to read file type: {e}"))?;
+        if !file_type.is_dir() {
+            continue;
+        }
+
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.is_empty() || name.starts_with('.') {
+            continue;
+        }
+
+        names.push(safe_skill_dir_name(&name));
+    }
+
+    names.sort();
+    names.dedup();
+    Ok(names)
+}
+
+/// The store's skill records on startup and what recovery settled first.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoreBootstrap {
+    pub skills: Vec<Skill>,
+    pub recovery: RecoveryReport,
+}
+
+/// Settles what an earlier run left unfinished in the store and the roots of
+/// `agents` (see [`recover_store`]), then returns the store's skill records.
+///
+/// Once the store has a manifest it is authoritative and `skills` is ignored;
+/// otherwise the records persisted by older frontends seed the manifest.
+pub fn bootstrap_skills_store(
+    skills: Vec<Skill>,
+    agents: &[AgentInfo],
+    storage_path: &str,
+) -> Result<StoreBootstrap, String> {
+    let dir = manager_store_root(storage_path)?;
+    let recovery = recover_store(&dir, agents)?;
+    let skills = hydrate_skills(&dir, skills)?;
+    Ok(StoreBootstrap { skills, recovery })
+}
+
+fn hydrate_skills(dir: &Path, skills: Vec<Skill>) -> Result<Vec<Skill>, String> {
+    if manifest_exists(dir) {
+        return Ok(load_manifest(dir)?.skills);
+    }
+
+    let store_has_skills = !read_store_skill_names(dir)?.is_empty();
+
+    // 兼容旧版本：仅在中心库为空时，才根据已持久化状态补齐目录。
+    if !store_has_skills {
+        for skill in &skills {
+            let skill_dir = dir.join(safe_skill_dir_name(&skill.name));
+            if skill_dir.exists() {
+                continue;
+            }
+            ensure_dir(&skill_dir)?;
+            let skill_md = format!("# {}\n", skill.name);
+            let _ = fs::write(skill_dir.join("SKILL.md"), skill_md);
+        }
+    }
+
+    let now = now_iso();
+    let existing_by_name: BTreeMap<String, Skill> = skills
+        .into_iter()
+        .map(|skill| (safe_skill_dir_name(&skill.name), skill))
+        .collect();
+
+    let mut merged: Vec<Skill> = vec![];
+    for name in read_store_skill_names(dir)? {
+        if let Some(existing) = existing_by_name.get(&name) {
+            let mut hydrated = existing.clone();
+            hydrated.id = name.clone();
+            hydrated.name = name;
+            if hydrated.last_sync.is_none() {
+                hydrated.last_sync = Some(now.clone());
+            }
+            if hydrated.last_update.is_none() {
+                hydrated.last_update = Some(now.clone());
+            }
+            merged.push(hydrated);
+            continue;
+        }
+
+        merged.push(Skill {
+            id: name.clone(),
+            name,
+            source_url: None,
+            enabled_agents: vec![],
+            last_sync: Some(now.clone()),
+            last_update: Some(now.clone()),
+            last_sync_digest: None,
+            source_commit: None,
+            source_subpath: None,
+            source_kind: None,
+            source_ref: None,
+            source_checksum: None,
+            installed_at: None,
+        });
+    }
+
+    let records = merged.clone();
+    update_manifest(dir, move |manifest| manifest.skills = records)?;
+    Ok(merged)
+}
+
+pub fn list_skills(storage_path: &str) -> Result<Vec<Skill>, String> {
+    let store_root = manager_store_root(storage_path)?;
+    Ok(load_manifest(&store_root)?.skills)
+}
+
+pub fn get_skill(skill_name: &str, storage_path: &str) -> Result<Option<Skill>, String> {
+    let store_root = manager_store_root(storage_path)?;
+    Ok(load_manifest(&store_root)?.get(skill_name).cloned())
+}
+
+/// Copies the store version of a skill to
+/// `<store>/.skillsm/snapshots/<skill>/<timestamp>/` before it is overwritten.
+pub fn snapshot_skill(store_root: &Path, skill_name: &str) -> Result<PathBuf, String> {
+    let name = safe_skill_dir_name(skill_name);
+    let src = store_root.join(&name);
+    let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%S%.3fZ");
+    let dst = store_root
+        .join(".skillsm")
+        .join("snapshots")
+        .join(&name)
+        .join(stamp.to_string());
+    copy_dir_all(&src, &dst)?;
+    Ok(dst)
+}
+
+/// Moves a skill and its distributed copies into the store's trash, from
+/// where [`crate::trash::restore_skill`] can bring them back.
+pub fn uninstall_skill(
+    skill_name: &str,
+    agents: &[AgentInfo],
+    storage_path: &str,
+) -> Result<TrashEntry, String> {
+    let store_root = manager_store_root(storage_path)?;
+    trash_skill(&store_root, skill_name, agents)
+}
+
+/// Moves every store skill into the trash and clears the rest of the store.
+/// Agent directories are left untouched.
+pub fn reset_store(storage_path: &str) -> Result<(), String> {
+    let root = expand_tilde(storage_path);
+    if !root.is_dir() {
+        return Ok(());
+    }
+    trash_store(&root)?;
+    Ok(())
+}
+
+fn canonicalize_if_possible(path: &Path) -> Option<PathBuf> {
+    fs::canonicalize(path).ok()
+}
+
+fn prevent_nested_move(from: &Path, to: &Path) -> Result<(), String> {
+    let from_canon = canonicalize_if_possible(from).unwrap_or_else(|| from.to_path_buf());
+    let to_canon = canonicalize_if_possible(to).unwrap_or_else(|| to.to_path_buf());
+
+    if to_canon.starts_with(&from_canon) && !is_same_path(&from_canon, &to_canon) {
+        return Err(format!(
+            "Destination is inside source directory (from: {}, to: {})",
+            from.display(),
+            to.display()
+        ));
+    }
+    Ok(())
+}
+
+fn ensure_directory_empty_or_no_conflicts(from: &Path, to: &Path) -> Result<(), String> {
+    if !to.exists() {
+        return Ok(());
+    }
+    if !to.is_dir() {
+        return Err(format!("Destination is not a directory: {}", to.display()));
+    }
+
+    let mut conflicts: Vec<String> = vec![];
+    for entry in fs::read_dir(from).map_err(|e| format!("Failed to read dir {}: {e}", from.display()))? {
+        let entry = entry.map_err(|e| format!("Failed to read entry: {e}"))?;
+        let name = entry.file_name();
+        let dst = to.join(&name);
+        if dst.exists() {
+            conflicts.push(name.to_string_lossy().to_string());
+        }
+    }
+
+    if !conflicts.is_empty() {
+        conflicts.sort();
+        return Err(format!(
+            "Destination already contains conflicting entries: {}",
+            conflicts.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+fn move_dir_contents(from: &Path, to: &Path) -> Result<(), String> {
+    if !from.exists() {
+        ensure_dir(to)?;
+        return Ok(());
+    }
+    if !from.is_dir() {
+        return Err(format!("Source is not a directory: {}", from.display()));
+    }
+
+    ensure_dir(to)?;
+    if is_same_path(from, to) {
+        return Ok(());
+    }
+
+    prevent_nested_move(from, to)?;
+    ensure_directory_empty_or_no_conflicts(from, to)?;
+
+    for entry in fs::read_dir(from).map_err(|e| format!("Failed to read dir {}: {e}", from.display()))? {
+        let entry = entry.map_err(|e| format!("Failed to read entry: {e}"))?;
+        let src = entry.path();
+        let dst = to.join(entry.file_name());
+        let file_type = entry
+            .file_type()
+            .map_err(|e| format!("Failed to read file type for {}: {e}", src.display()))?;
+
+        if let Err(_e) = fs::rename(&src, &dst) {
+            if file_type.is_dir() {
+                copy_dir_all(&src, &dst)?;
+                fs::remove_dir_all(&src).map_err(|e| {
+                    format!("Failed to remove source dir {} after copy: {e}", src.display())
+                })?;
+            } else {
+                fs::copy(&src, &dst).map_err(|e| {
+                    format!(
+                        "Failed to copy file {} -> {}: {e}",
+                        src.display(),
+                        dst.display()
+                    )
+                })?;
+                fs::remove_file(&src).map_err(|e| {
+                    format!("Failed to remove source file {} after copy: {e}", src.display())
+                })?;
+            }
+        }
+    }
+
+    let _ = fs::remove_dir(from);
+    Ok(())
+}
+
+pub fn migrate_manager_store(from_storage_path: &str, to_storage_path: &str) -> Result<(), String> {
+    let from = expand_tilde(from_storage_path);
+    let to = expand_tilde(to_storage_path);
+    move_dir_contents(&from, &to)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::generate_id;
+
+    fn temp_test_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("skills-manager-{name}-{}", generate_id()))
+    }
+
+    fn write_skill(store_root: &Path, name: &str) {
+        let dir = store_root.join(name);
+        ensure_dir(&dir).expect("create skill dir");
+        fs::write(dir.join("SKILL.md"), format!("# {name}\n")).expect("write SKILL.md");
+    }
+
+    #[test]
+    fn bootstrap_skills_store_hydrates_from_existing_store_first() {
+        let root = temp_test_dir("bootstrap-existing");
+        let _ = fs::remove_dir_all(&root);
+        ensure_dir(&root).unwrap();
+
+        write_skill(&root, "fastapi");
+
+        let existing_state = vec![Skill {
+            id: "old-id".to_string(),
+            name: "legacy-skill".to_string(),
+            source_url: Some("https://example.com/legacy".to_string()),
+            enabled_agents: vec!["codex".to_string()],
+            last_sync: None,
+            last_update: None,
+            last_sync_digest: None,
+            source_commit: None,
+            source_subpath: None,
+            source_kind: None,
+            source_ref: None,
+            source_checksum: None,
+            installed_at: None,
+        }];
+
+        let hydrated = bootstrap_skills_store(existing_state, &[], &root.to_string_lossy())
+            .unwrap()
+            .skills;
+
+        assert_eq!(hydrated.len(), 1);
+        assert_eq!(hydrated[0].name, "fastapi");
+        assert!(!root.join("legacy-skill").exists());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn bootstrap_skills_store_seeds_when_store_is_empty() {
+        let root = temp_test_dir("bootstrap-empty");
+        let _ = fs::remove_dir_all(&root);
+        ensure_dir(&root).unwrap();
+
+        let existing_state = vec![Skill {
+            id: "skill-1".to_string(),
+            name: "seeded-skill".to_string(),
+            source_url: None,
+            enabled_agents: vec![],
+            last_sync: None,
+            last_update: None,
+            last_sync_digest: None,
+            source_commit: None,
+            source_subpath: None,
+            source_kind: None,
+            source_ref: None,
+            source_checksum: None,
+            installed_at: None,
+        }];
+
+        let hydrated = bootstrap_skills_store(existing_state, &[], &root.to_string_lossy())
+            .unwrap()
+            .skills;
+
+        assert_eq!(hydrated.len(), 1);
+        assert_eq!(hydrated[0].name, "seeded-skill");
+        assert!(root.join("seeded-skill").join("SKILL.md").exists());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn bootstrap_skills_store_prefers_manifest_over_frontend_state() {
+        let root = temp_test_dir("bootstrap-manifest");
+        ensure_dir(&root).unwrap();
+        write_skill(&root, "fastapi");
+
+        update_manifest(&root, |m| {
+            m.get_mut("fastapi").unwrap().source_url = Some("https://github.com/foo/fastapi".to_string());
+        })
+        .unwrap();
+
+        let stale_state = vec![Skill {
+            id: "fastapi".to_string(),
+            name: "fastapi".to_string(),
+            source_url: None,
+            enabled_agents: vec!["codex".to_string()],
+            last_sync: None,
+            last_update: None,
+            last_sync_digest: None,
+            source_commit: None,
+            source_subpath: None,
+            source_kind: None,
+            source_ref: None,
+            source_checksum: None,
+            installed_at: None,
+        }];
+
+        let hydrated = bootstrap_skills_store(stale_state, &[], &root.to_string_lossy())
+            .unwrap()
+            .skills;
+
+        assert_eq!(hydrated.len(), 1);
+        assert_eq!(hydrated[0].source_url.as_deref(), Some("https://github.com/foo/fastapi"));
+        assert!(hydrated[0].enabled_agents.is_empty());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn bootstrap_skills_store_recovers_before_reading_the_store() {
+        let root = temp_test_dir("bootstrap-recover");
+        write_skill(&root, "fastapi");
+        write_skill(&root, ".tmp-install-orphan");
+
+        let bootstrap = bootstrap_skills_store(vec![], &[], &root.to_string_lossy()).unwrap();
+
+        assert_eq!(bootstrap.recovery.removed_temp_dirs, [".tmp-install-orphan"]);
+        assert!(!root.join(".tmp-install-orphan").exists());
+        assert_eq!(bootstrap.skills.len(), 1);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn uninstall_skill_drops_manifest_record() {
+        let root = temp_test_dir("uninstall-manifest");
+        ensure_dir(&root).unwrap();
+        write_skill(&root, "alpha");
+        write_skill(&root, "beta");
+        let storage_path = root.to_string_lossy().to_string();
+        bootstrap_skills_store(vec![], &[], &storage_path).unwrap();
+
+        uninstall_skill("alpha", &[], &storage_path).unwrap();
+
+        let names: Vec<String> = list_skills(&storage_path)
+            .unwrap()
+            .into_iter()
+            .map(|skill| skill.name)
+            .collect();
+        assert_eq!(names, vec!["beta".to_string()]);
+        assert!(get_skill("alpha", &storage_path).unwrap().is_none());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn move_dir_contents_moves_files_and_dirs() {
+        let root = temp_test_dir("storage-moves");
+        let from = root.join("from");
+        let to = root.join("to");
+        ensure_dir(&from).unwrap();
+        ensure_dir(&from.join("a")).unwrap();
+        fs::write(from.join("a").join("x.txt"), "hi").unwrap();
+        fs::write(from.join("b.txt"), "yo").unwrap();
+
+        move_dir_contents(&from, &to).unwrap();
+
+        assert!(to.join("a").join("x.txt").exists());
+        assert!(to.join("b.txt").exists());
+        assert!(!from.join("b.txt").exists());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn move_dir_contents_rejects_nested_destination() {
+        let root = temp_test_dir("storage-nested");
+        let from = root.join("from");
+        let to = from.join("inner");
+        ensure_dir(&from).unwrap();
+        ensure_dir(&to).unwrap();
+
+        let err = move_dir_contents(&from, &to).unwrap_err();
+        assert!(err.to_lowercase().contains("inside source"));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,492 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::journal::{Journal, JournalStep};
+use crate::manifest::{load_manifest, update_manifest};
+use crate::models::{AgentInfo, Skill};
+use crate::utils::{
+    agent_roots, ensure_dir, manager_store_root, move_dir, remove_dir_if_exists,
+    safe_skill_dir_name, symlink_dir, unique_skill_dir_name, write_file_atomic,
+};
+
+pub const TRASH_DIR: &str = ".trash";
+const ENTRY_FILE: &str = "entry.json";
+const SKILL_DIR: &str = "skill";
+const COPIES_DIR: &str = "copies";
+
+/// A distributed copy that was moved to the trash along with its skill.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashedCopy {
+    pub agent_id: String,
+    /// Where the copy lived, and where a restore puts it back.
+    pub original: String,
+    /// Set when the copy was a link to the store; only the target is kept.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link_target: Option<String>,
+}
+
+/// `entry.json` of `<store>/.trash/<timestamp>-<name>/`. The store copy sits
+/// next to it in `skill/`, distributed copies in `copies/<index>/`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashEntry {
+    pub id: String,
+    pub name: String,
+    pub deleted_at: String,
+    /// The manifest record at the time of deletion.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub record: Option<Skill>,
+    pub has_store_copy: bool,
+    #[serde(default)]
+    pub copies: Vec<TrashedCopy>,
+}
+
+/// What [`restore_skill`] brought back.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreReport {
+    pub skill: Skill,
+    /// Copies whose original location is taken again. They stay in the trash,
+    /// in an entry of their own under the same id.
+    pub skipped: Vec<TrashedCopy>,
+}
+
+fn write_entry(entry_dir: &Path, entry: &TrashEntry) -> Result<(), String> {
+    let text = serde_json::to_string_pretty(entry)
+        .map_err(|e| format!("Failed to serialize trash entry: {e}"))?;
+    write_file_atomic(&entry_dir.join(ENTRY_FILE), text.as_bytes())
+}
+
+fn read_entry(entry_dir: &Path) -> Result<TrashEntry, String> {
+    let path = entry_dir.join(ENTRY_FILE);
+    let text = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read trash entry {}: {e}", path.display()))?;
+    serde_json::from_str(&text)
+        .map_err(|e| format!("Failed to parse trash entry {}: {e}", path.display()))
+}
+
+/// Resolves a trash entry id, refusing anything that is not a plain entry
+/// directory name.
+fn entry_dir(store_root: &Path, id: &str) -> Result<PathBuf, String> {
+    if id.is_empty() || id.starts_with('.') || safe_skill_dir_name(id) != id {
+        return Err(format!("Invalid trash entry id: {id}"));
+    }
+    let dir = store_root.join(TRASH_DIR).join(id);
+    if !dir.join(ENTRY_FILE).is_file() {
+        return Err(format!("Trash entry not found: {id}"));
+    }
+    Ok(dir)
+}
+
+/// Moves the store copy of `skill_name` and every copy distributed to
+/// `agents` into a new trash entry, and drops its manifest record.
+///
+/// The entry is written even when a move fails part-way, so whatever was
+/// already moved can still be restored.
+pub(crate) fn trash_skill(
+    store_root: &Path,
+    skill_name: &str,
+    agents: &[AgentInfo],
+) -> Result<TrashEntry, String> {
+    let name = safe_skill_dir_name(skill_name);
+    let trash_root = store_root.join(TRASH_DIR);
+    let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%S%.3fZ");
+    let id = unique_skill_dir_name(&trash_root, &format!("{stamp}-{name}"));
+    let entry_dir = trash_root.join(&id);
+    ensure_dir(&entry_dir)?;
+
+    let mut entry = TrashEntry {
+        id,
+        name: name.clone(),
+        deleted_at: crate::utils::now_iso(),
+        record: load_manifest(store_root)?.get(&name).cloned(),
+        has_store_copy: false,
+        copies: vec![],
+    };
+    let moved = (|| {
+        let src = store_root.join(&name);
+        if src.is_dir() && !src.is_symlink() {
+            move_dir(&src, &entry_dir.join(SKILL_DIR))?;
+            entry.has_store_copy = true;
+        }
+        for agent in agents {
+            for root in agent_roots(agent) {
+                let dst = root.join(&name);
+                let Ok(meta) = fs::symlink_metadata(&dst) else {
+                    continue;
+                };
+                let mut copy = TrashedCopy {
+                    agent_id: agent.id.clone(),
+                    original: dst.to_string_lossy().to_string(),
+                    link_target: None,
+                };
+                if meta.file_type().is_symlink() {
+                    let target = fs::read_link(&dst)
+                        .map_err(|e| format!("Failed to read link {}: {e}", dst.display()))?;
+                    copy.link_target = Some(target.to_string_lossy().to_string());
+                    remove_dir_if_exists(&dst)?;
+                } else if meta.is_dir() {
+                    let stored = entry_dir
+                        .join(COPIES_DIR)
+                        .join(entry.copies.len().to_string());
+                    move_dir(&dst, &stored)?;
+                } else {
+                    continue;
+                }
+                entry.copies.push(copy);
+            }
+        }
+        Ok::<(), String>(())
+    })();
+    write_entry(&entry_dir, &entry)?;
+    moved?;
+
+    update_manifest(store_root, |manifest| manifest.remove(&name))?;
+    Ok(entry)
+}
+
+/// Moves every store skill into the trash without touching agent
+/// directories, then clears the rest of the store except the trash itself.
+pub(crate) fn trash_store(store_root: &Path) -> Result<Vec<TrashEntry>, String> {
+    let mut entries = vec![];
+    for name in crate::store::read_store_skill_names(store_root)? {
+        entries.push(trash_skill(store_root, &name, &[])?);
+    }
+    for entry in fs::read_dir(store_root)
+        .map_err(|e| format!("Failed to read manager store {}: {e}", store_root.display()))?
+    {
+        let entry = entry.map_err(|e| format!("Failed to read entry: {e}"))?;
+        if entry.file_name() == TRASH_DIR {
+            continue;
+        }
+        let path = entry.path();
+        if path.is_dir() && !path.is_symlink() {
+            remove_dir_if_exists(&path)?;
+        } else {
+            fs::remove_file(&path)
+                .map_err(|e| format!("Failed to remove {}: {e}", path.display()))?;
+        }
+    }
+    Ok(entries)
+}
+
+/// Lists the trash entries of the store, oldest first.
+pub fn list_trash(storage_path: &str) -> Result<Vec<TrashEntry>, String> {
+    let store_root = manager_store_root(storage_path)?;
+    let trash_root = store_root.join(TRASH_DIR);
+    if !trash_root.is_dir() {
+        return Ok(vec![]);
+    }
+    let mut entries = vec![];
+    for dir in fs::read_dir(&trash_root)
+        .map_err(|e| format!("Failed to read trash {}: {e}", trash_root.display()))?
+    {
+        let dir = dir
+            .map_err(|e| format!("Failed to read entry: {e}"))?
+            .path();
+        // Entries without a readable entry.json are left alone rather than guessed at.
+        if let Ok(entry) = read_entry(&dir) {
+            entries.push(entry);
+        }
+    }
+    entries.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(entries)
+}
+
+/// Moves a trashed skill back into the store and its copies back to where
+/// they were distributed, all or nothing. Copies whose original location is
+/// taken again stay in the trash and are reported as skipped.
+pub fn restore_skill(entry_id: &str, storage_path: &str) -> Result<RestoreReport, String> {
+    let store_root = manager_store_root(storage_path)?;
+    let entry_dir = entry_dir(&store_root, entry_id)?;
+    let entry = read_entry(&entry_dir)?;
+    let dest = store_root.join(&entry.name);
+    if entry.has_store_copy && fs::symlink_metadata(&dest).is_ok() {
+        return Err(format!("{} already exists in the store", entry.name));
+    }
+
+    let mut journal = Journal::begin(&store_root, "restore")?;
+    if entry.has_store_copy {
+        journal.move_dir(&entry_dir.join(SKILL_DIR), &dest)?;
+    }
+    let mut skipped = vec![];
+    for (idx, copy) in entry.copies.iter().enumerate() {
+        let original = PathBuf::from(&copy.original);
+        if fs::symlink_metadata(&original).is_ok() {
+            skipped.push((idx, copy.clone()));
+            continue;
+        }
+        match &copy.link_target {
+            Some(target) => {
+                if let Some(parent) = original.parent() {
+                    ensure_dir(parent)?;
+                }
+                journal.log(JournalStep::Create {
+                    path: original.clone(),
+                })?;
+                symlink_dir(Path::new(target), &original).map_err(|e| {
+                    format!("Failed to link {} to {target}: {e}", original.display())
+                })?;
+            }
+            None => {
+                journal.move_dir(&entry_dir.join(COPIES_DIR).join(idx.to_string()), &original)?
+            }
+        }
+    }
+
+    journal.log(JournalStep::Record {
+        name: entry.name.clone(),
+        before: load_manifest(&store_root)?
+            .get(&entry.name)
+            .cloned()
+            .map(Box::new),
+    })?;
+    let skill = update_manifest(&store_root, |manifest| {
+        if let Some(record) = entry.record.clone() {
+            manifest.upsert(record);
+        }
+        manifest.get(&entry.name).cloned()
+    })?
+    .ok_or_else(|| format!("Trash entry {entry_id} holds no files for {}", entry.name))?;
+
+    // The old entry is deleted on commit; the skipped copies move to a new one.
+    let old_dir = journal
+        .move_aside(&entry_dir, "trash")?
+        .ok_or_else(|| format!("Trash entry not found: {entry_id}"))?;
+    if !skipped.is_empty() {
+        journal.log(JournalStep::Create {
+            path: entry_dir.clone(),
+        })?;
+        ensure_dir(&entry_dir)?;
+        let mut left = TrashEntry {
+            record: None,
+            has_store_copy: false,
+            copies: vec![],
+            ..entry
+        };
+        for (idx, copy) in &skipped {
+            if copy.link_target.is_none() {
+                let stored = entry_dir
+                    .join(COPIES_DIR)
+                    .join(left.copies.len().to_string());
+                journal.move_dir(&old_dir.join(COPIES_DIR).join(idx.to_string()), &stored)?;
+            }
+            left.copies.push(copy.clone());
+        }
+        write_entry(&entry_dir, &left)?;
+    }
+    journal.commit()?;
+    Ok(RestoreReport {
+        skill,
+        skipped: skipped.into_iter().map(|(_, copy)| copy).collect(),
+    })
+}
+
+/// Permanently deletes one trash entry.
+pub fn delete_trash_entry(entry_id: &str, storage_path: &str) -> Result<(), String> {
+    let store_root = manager_store_root(storage_path)?;
+    remove_dir_if_exists(&entry_dir(&store_root, entry_id)?)
+}
+
+/// Permanently deletes every trash entry and returns how many there were.
+pub fn empty_trash(storage_path: &str) -> Result<usize, String> {
+    let entries = list_trash(storage_path)?;
+    for entry in &entries {
+        delete_trash_entry(&entry.id, storage_path)?;
+    }
+    Ok(entries.len())
+}
+
+/// Permanently deletes the entries trashed more than `retention_days` ago
+/// and returns their ids.
+pub fn purge_expired_trash(retention_days: u32, storage_path: &str) -> Result<Vec<String>, String> {
+    let cutoff = chrono::Utc::now() - chrono::Duration::days(i64::from(retention_days));
+    let mut purged = vec![];
+    for entry in list_trash(storage_path)? {
+        let Ok(deleted_at) = chrono::DateTime::parse_from_rfc3339(&entry.deleted_at) else {
+            continue;
+        };
+        if deleted_at < cutoff {
+            delete_trash_entry(&entry.id, storage_path)?;
+            purged.push(entry.id);
+        }
+    }
+    Ok(purged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distributor::sync_one_skill;
+    use crate::models::DistributionMode;
+    use crate::store::{bootstrap_skills_store, list_skills, reset_store, uninstall_skill};
+    use crate::utils::generate_id;
+
+    fn temp_test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("skills-manager-{name}-{}", generate_id()));
+        ensure_dir(&dir).expect("create temp dir");
+        dir
+    }
+
+    fn write_skill(store_root: &Path, name: &str) {
+        let dir = store_root.join(name);
+        ensure_dir(&dir).unwrap();
+        fs::write(dir.join("SKILL.md"), format!("# {name}\n")).unwrap();
+    }
+
+    fn agent(id: &str, root: &Path, mode: DistributionMode) -> AgentInfo {
+        AgentInfo {
+            id: id.to_string(),
+            name: id.to_string(),
+            default_path: root.to_string_lossy().to_string(),
+            current_path: root.to_string_lossy().to_string(),
+            enabled: true,
+            icon: "test".to_string(),
+            distribution_mode: mode,
+        }
+    }
+
+    #[test]
+    fn uninstall_moves_skill_and_copies_to_trash_and_restore_brings_them_back() {
+        let tmp = temp_test_dir("trash-restore");
+        let store = tmp.join("store");
+        let storage_path = store.to_string_lossy().to_string();
+        write_skill(&store, "alpha");
+        bootstrap_skills_store(vec![], &[], &storage_path).unwrap();
+        let agents = vec![
+            agent("copy", &tmp.join("copy"), DistributionMode::Copy),
+            agent("link", &tmp.join("link"), DistributionMode::Symlink),
+        ];
+        let enabled = vec!["copy".to_string(), "link".to_string()];
+        sync_one_skill(&store, "alpha", &enabled, &agents).unwrap();
+
+        let entry = uninstall_skill("alpha", &agents, &storage_path).unwrap();
+        assert!(!store.join("alpha").exists());
+        assert!(!tmp.join("copy").join("alpha").exists());
+        assert!(fs::symlink_metadata(tmp.join("link").join("alpha")).is_err());
+        assert!(list_skills(&storage_path).unwrap().is_empty());
+        assert!(entry.id.ends_with("-alpha"));
+        assert_eq!(entry.copies.len(), 2);
+        assert!(entry.copies[1].link_target.is_some());
+
+        let listed = list_trash(&storage_path).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].record.as_ref().unwrap().enabled_agents, enabled);
+
+        let restored = restore_skill(&entry.id, &storage_path).unwrap();
+        assert_eq!(restored.skill.enabled_agents, enabled);
+        assert!(restored.skipped.is_empty());
+        assert!(store.join("alpha").join("SKILL.md").is_file());
+        assert!(tmp.join("copy").join("alpha").join("SKILL.md").is_file());
+        assert!(tmp.join("link").join("alpha").is_symlink());
+        assert!(tmp.join("link").join("alpha").join("SKILL.md").is_file());
+        assert!(list_trash(&storage_path).unwrap().is_empty());
+
+        assert!(restore_skill("../alpha", &storage_path).is_err());
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn restore_keeps_copies_whose_place_is_taken_in_the_trash() {
+        let tmp = temp_test_dir("trash-taken");
+        let store = tmp.join("store");
+        let storage_path = store.to_string_lossy().to_string();
+        write_skill(&store, "alpha");
+        bootstrap_skills_store(vec![], &[], &storage_path).unwrap();
+        let agents = vec![
+            agent("a", &tmp.join("a"), DistributionMode::Copy),
+            agent("b", &tmp.join("b"), DistributionMode::Copy),
+        ];
+        let enabled = vec!["a".to_string(), "b".to_string()];
+        sync_one_skill(&store, "alpha", &enabled, &agents).unwrap();
+        fs::write(tmp.join("b").join("alpha").join("notes.md"), "mine\n").unwrap();
+        let entry = uninstall_skill("alpha", &agents, &storage_path).unwrap();
+
+        ensure_dir(&tmp.join("b").join("alpha")).unwrap();
+        let restored = restore_skill(&entry.id, &storage_path).unwrap();
+        assert_eq!(restored.skipped.len(), 1);
+        assert_eq!(restored.skipped[0].agent_id, "b");
+        assert!(store.join("alpha").join("SKILL.md").is_file());
+        assert!(tmp.join("a").join("alpha").join("SKILL.md").is_file());
+
+        let left = list_trash(&storage_path).unwrap();
+        assert_eq!(left.len(), 1);
+        assert_eq!(left[0].id, entry.id);
+        assert!(!left[0].has_store_copy);
+        assert_eq!(left[0].copies, restored.skipped);
+
+        fs::remove_dir_all(tmp.join("b").join("alpha")).unwrap();
+        let again = restore_skill(&entry.id, &storage_path).unwrap();
+        assert!(again.skipped.is_empty());
+        assert_eq!(
+            fs::read_to_string(tmp.join("b").join("alpha").join("notes.md")).unwrap(),
+            "mine\n"
+        );
+        assert!(list_trash(&storage_path).unwrap().is_empty());
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn failed_restore_leaves_the_entry_restorable() {
+        let tmp = temp_test_dir("trash-failed");
+        let store = tmp.join("store");
+        let storage_path = store.to_string_lossy().to_string();
+        write_skill(&store, "alpha");
+        bootstrap_skills_store(vec![], &[], &storage_path).unwrap();
+        let agents = vec![agent("a", &tmp.join("a"), DistributionMode::Copy)];
+        sync_one_skill(&store, "alpha", &["a".to_string()], &agents).unwrap();
+        let entry = uninstall_skill("alpha", &agents, &storage_path).unwrap();
+
+        // The agent root is now a file, so the copy cannot go back.
+        fs::remove_dir_all(tmp.join("a")).unwrap();
+        fs::write(tmp.join("a"), "not a dir").unwrap();
+        assert!(restore_skill(&entry.id, &storage_path).is_err());
+        assert!(!store.join("alpha").exists());
+        assert!(list_skills(&storage_path).unwrap().is_empty());
+        let kept = list_trash(&storage_path).unwrap();
+        assert_eq!(kept.len(), 1);
+        assert!(kept[0].has_store_copy);
+
+        fs::remove_file(tmp.join("a")).unwrap();
+        restore_skill(&entry.id, &storage_path).unwrap();
+        assert!(store.join("alpha").join("SKILL.md").is_file());
+        assert!(tmp.join("a").join("alpha").join("SKILL.md").is_file());
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn reset_keeps_trash_and_empty_and_purge_delete_entries() {
+        let tmp = temp_test_dir("trash-reset");
+        let store = tmp.join("store");
+        let storage_path = store.to_string_lossy().to_string();
+        write_skill(&store, "alpha");
+        write_skill(&store, "beta");
+        bootstrap_skills_store(vec![], &[], &storage_path).unwrap();
+
+        reset_store(&storage_path).unwrap();
+        let entries = list_trash(&storage_path).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert!(!store.join(".skillsm").exists());
+        assert!(!store.join("alpha").exists());
+
+        assert!(purge_expired_trash(1, &storage_path).unwrap().is_empty());
+        let mut old = read_entry(&store.join(TRASH_DIR).join(&entries[0].id)).unwrap();
+        old.deleted_at = "2000-01-01T00:00:00Z".to_string();
+        write_entry(&store.join(TRASH_DIR).join(&old.id), &old).unwrap();
+        assert_eq!(
+            purge_expired_trash(1, &storage_path).unwrap(),
+            vec![old.id.clone()]
+        );
+
+        assert_eq!(empty_trash(&storage_path).unwrap(), 1);
+        assert!(list_trash(&storage_path).unwrap().is_empty());
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+}
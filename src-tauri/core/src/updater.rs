@@ -0,0 +1,427 @@
+use std::fs;
+
+use serde::Serialize;
+
+use crate::distributor::sync_one_skill;
+use crate::download::{download_to_file, DownloadOptions};
+use crate::git;
+use crate::installer::{parse_install_source, source_subdir, stage_source, InstallSource};
+use crate::journal::{Journal, JournalStep};
+use crate::manifest::{load_manifest, update_manifest};
+use crate::models::{AgentInfo, InstallProgressLog, Skill, SourceKind};
+use crate::progress::{NoopProgress, ProgressReporter};
+use crate::store::snapshot_skill;
+use crate::utils::{copy_dir_all, ensure_dir, generate_id, manager_store_root, now_iso};
+
+/// A skill whose upstream has moved past the installed revision.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillUpdate {
+    pub id: String,
+    pub name: String,
+    pub kind: SourceKind,
+    pub source_url: String,
+    /// Commit sha for git sources, archive sha256 for zip sources.
+    pub old_revision: Option<String>,
+    pub new_revision: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateCheckFailure {
+    pub id: String,
+    pub name: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateCheckReport {
+    pub outdated: Vec<SkillUpdate>,
+    pub failed: Vec<UpdateCheckFailure>,
+}
+
+/// The source a skill was installed from, when it can be fetched again
+/// without npx.
+fn recorded_source(skill: &Skill) -> Option<InstallSource> {
+    let url = skill.source_url.as_deref()?;
+    match (skill.source_kind?, parse_install_source(url)) {
+        (SourceKind::Git, InstallSource::Git { url, .. }) => Some(InstallSource::Git {
+            url,
+            reference: skill.source_ref.clone(),
+            subpath: skill.source_subpath.clone(),
+        }),
+        (SourceKind::Zip, source @ InstallSource::Zip { .. }) => Some(source),
+        _ => None,
+    }
+}
+
+fn installed_revision(skill: &Skill) -> Option<&str> {
+    match skill.source_kind {
+        Some(SourceKind::Zip) => skill.source_checksum.as_deref(),
+        _ => skill.source_commit.as_deref(),
+    }
+}
+
+/// Resolves the revision `source` currently points at. Zip archives carry no
+/// revision, so they are downloaded and hashed.
+fn upstream_revision(source: &InstallSource) -> Result<String, String> {
+    match source {
+        InstallSource::Git { url, reference, .. } => git::resolve_remote(url, reference.as_deref()),
+        InstallSource::Zip { url } => {
+            let tmp_dir =
+                std::env::temp_dir().join(format!("skills-manager-check-{}", generate_id()));
+            ensure_dir(&tmp_dir)?;
+            let result = download_to_file(
+                url,
+                &tmp_dir.join("download.zip"),
+                &DownloadOptions::default(),
+                &NoopProgress,
+            )
+            .map(|summary| summary.sha256)
+            .map_err(String::from);
+            let _ = fs::remove_dir_all(&tmp_dir);
+            result
+        }
+    }
+}
+
+fn report_skill(
+    progress: &dyn ProgressReporter<InstallProgressLog>,
+    id: String,
+    label: String,
+    status: &str,
+    percent: f64,
+) {
+    progress.report(InstallProgressLog {
+        id,
+        label,
+        status: status.to_string(),
+        progress: percent,
+        bytes: None,
+        total_bytes: None,
+    });
+}
+
+/// Resolves the upstream revision of every skill installed from a git or zip
+/// source and lists the ones that are behind. Skills whose source cannot be
+/// reached are reported in `failed` instead of aborting the check.
+pub fn check_updates(
+    storage_path: &str,
+    progress: &dyn ProgressReporter<InstallProgressLog>,
+) -> Result<UpdateCheckReport, String> {
+    let store_root = manager_store_root(storage_path)?;
+    let candidates: Vec<(Skill, InstallSource)> = load_manifest(&store_root)?
+        .skills
+        .into_iter()
+        .filter_map(|skill| recorded_source(&skill).map(|source| (skill, source)))
+        .collect();
+    let total = candidates.len().max(1) as f64;
+
+    let mut report = UpdateCheckReport::default();
+    for (idx, (skill, source)) in candidates.into_iter().enumerate() {
+        let id = format!("check-{}", skill.id);
+        let label = format!("正在检查更新: {}", skill.name);
+        report_skill(
+            progress,
+            id.clone(),
+            label.clone(),
+            "loading",
+            idx as f64 / total * 100.0,
+        );
+        let done = (idx + 1) as f64 / total * 100.0;
+
+        match upstream_revision(&source) {
+            Ok(latest) => {
+                report_skill(progress, id, label, "success", done);
+                if installed_revision(&skill) != Some(latest.as_str()) {
+                    report.outdated.push(SkillUpdate {
+                        old_revision: installed_revision(&skill).map(str::to_string),
+                        new_revision: latest,
+                        kind: source.kind(),
+                        source_url: skill.source_url.clone().unwrap_or_default(),
+                        id: skill.id,
+                        name: skill.name,
+                    });
+                }
+            }
+            Err(error) => {
+                report_skill(progress, id, label, "error", done);
+                report.failed.push(UpdateCheckFailure {
+                    id: skill.id,
+                    name: skill.name,
+                    error,
+                });
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// Re-fetches the skills in `ids` from their recorded sources and replaces the
+/// store copies, then redistributes them to their enabled agents.
+///
+/// Every source is fetched before anything in the store changes, and the
+/// replacement is journaled, so after a failure or a crash the store ends up
+/// either fully updated or untouched. The previous versions are snapshotted.
+/// Returns the skills that changed.
+pub fn update_skills(
+    ids: &[String],
+    agents: &[AgentInfo],
+    storage_path: &str,
+    progress: &dyn ProgressReporter<InstallProgressLog>,
+) -> Result<Vec<Skill>, String> {
+    let store_root = manager_store_root(storage_path)?;
+    let manifest = load_manifest(&store_root)?;
+    let mut targets: Vec<(&Skill, InstallSource)> = vec![];
+    for id in ids {
+        let skill = manifest
+            .skills
+            .iter()
+            .find(|skill| &skill.id == id)
+            .ok_or_else(|| format!("Skill not found in store: {id}"))?;
+        if targets.iter().any(|(seen, _)| seen.id == skill.id) {
+            continue;
+        }
+        let source = recorded_source(skill)
+            .ok_or_else(|| format!("{} has no git or zip source to update from", skill.name))?;
+        targets.push((skill, source));
+    }
+    let total = targets.len().max(1) as f64;
+
+    let mut staged = vec![];
+    for (idx, (skill, source)) in targets.iter().enumerate() {
+        let id = format!("update-{}", skill.id);
+        let label = format!("正在更新技能: {}", skill.name);
+        report_skill(
+            progress,
+            id.clone(),
+            label,
+            "loading",
+            idx as f64 / total * 100.0,
+        );
+        let fetched = stage_source(source, &store_root, None, progress).and_then(|stage| {
+            let dir = source_subdir(&stage.staging, skill.source_subpath.as_deref())?;
+            Ok((stage, dir))
+        });
+        match fetched {
+            Ok((stage, dir)) => {
+                let unchanged = match source.kind() {
+                    SourceKind::Zip => stage.revision.checksum == skill.source_checksum,
+                    _ => stage.revision.commit == skill.source_commit,
+                };
+                if !unchanged {
+                    staged.push((*skill, stage, dir));
+                }
+            }
+            Err(err) => {
+                let label = format!("更新失败: {}（{err}）", skill.name);
+                report_skill(progress, id, label, "error", idx as f64 / total * 100.0);
+                return Err(format!("Failed to update {}: {err}", skill.name));
+            }
+        }
+    }
+
+    let mut journal = Journal::begin(&store_root, "update")?;
+    let now = now_iso();
+    for (skill, _, dir) in &staged {
+        let dest = store_root.join(&skill.name);
+        if dest.exists() {
+            snapshot_skill(&store_root, &skill.name)?;
+        }
+        journal.move_aside(&dest, "update")?;
+        journal.log(JournalStep::Create { path: dest.clone() })?;
+        // A linked subdirectory would dangle once staging is gone, so copy it.
+        if dir.is_symlink() || fs::rename(dir, &dest).is_err() {
+            copy_dir_all(dir, &dest)?;
+        }
+        journal.log(JournalStep::Record {
+            name: skill.name.clone(),
+            before: Some(Box::new((*skill).clone())),
+        })?;
+    }
+    update_manifest(&store_root, |manifest| {
+        for (skill, stage, _) in &staged {
+            if let Some(record) = manifest.get_mut(&skill.name) {
+                record.source_commit = stage.revision.commit.clone();
+                record.source_checksum = stage.revision.checksum.clone();
+                record.installed_at = Some(now.clone());
+                record.last_update = Some(now.clone());
+            }
+        }
+    })?;
+    journal.commit()?;
+
+    for (skill, _, _) in &staged {
+        sync_one_skill(&store_root, &skill.name, &skill.enabled_agents, agents)?;
+    }
+    for (idx, (skill, _)) in targets.iter().enumerate() {
+        report_skill(
+            progress,
+            format!("update-{}", skill.id),
+            format!("正在更新技能: {}", skill.name),
+            "success",
+            (idx + 1) as f64 / total * 100.0,
+        );
+    }
+
+    let manifest = load_manifest(&store_root)?;
+    let updated: Vec<Skill> = staged
+        .iter()
+        .filter_map(|(skill, _, _)| manifest.get(&skill.name).cloned())
+        .collect();
+    let names: Vec<&str> = updated.iter().map(|skill| skill.name.as_str()).collect();
+    report_skill(
+        progress,
+        "done".to_string(),
+        if names.is_empty() {
+            "已是最新版本".to_string()
+        } else {
+            format!("更新完成: {}", names.join(", "))
+        },
+        "success",
+        100.0,
+    );
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::{temp_test_dir, test_agent, write_upstream};
+    use crate::installer::install_skill;
+    use std::path::Path;
+
+    fn head_sha(dir: &Path) -> String {
+        let repo = git2::Repository::open(dir).unwrap();
+        let sha = repo.head().unwrap().peel_to_commit().unwrap().id();
+        sha.to_string()
+    }
+
+    #[test]
+    fn check_updates_reports_old_and_new_revisions() {
+        let tmp = temp_test_dir("check-updates");
+        let store = tmp.join("store");
+        let store_path = store.to_string_lossy().to_string();
+        let upstream = tmp.join("demo");
+        let url = write_upstream(&upstream, "---\nname: demo\n---\nv1\n");
+        let skill = install_skill(&url, &store_path).unwrap();
+
+        let report = check_updates(&store_path, &NoopProgress).unwrap();
+        assert_eq!(report, UpdateCheckReport::default());
+
+        let old = head_sha(&upstream);
+        write_upstream(&upstream, "---\nname: demo\n---\nv2\n");
+        let report = check_updates(&store_path, &NoopProgress).unwrap();
+        assert_eq!(
+            report.outdated,
+            vec![SkillUpdate {
+                id: skill.id.clone(),
+                name: "demo".to_string(),
+                kind: SourceKind::Git,
+                source_url: url.clone(),
+                old_revision: Some(old),
+                new_revision: head_sha(&upstream),
+            }]
+        );
+
+        fs::remove_dir_all(&upstream).unwrap();
+        let report = check_updates(&store_path, &NoopProgress).unwrap();
+        assert!(report.outdated.is_empty());
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].id, skill.id);
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn update_skills_replaces_store_copy_and_redistributes() {
+        let tmp = temp_test_dir("update-skills");
+        let store = tmp.join("store");
+        let store_path = store.to_string_lossy().to_string();
+        let agent_root = tmp.join("agent");
+        let agents = vec![test_agent(&agent_root)];
+        let upstream = tmp.join("demo");
+        let url = write_upstream(&upstream, "---\nname: demo\n---\nv1\n");
+        let skill = install_skill(&url, &store_path).unwrap();
+        let enabled = vec!["claude".to_string()];
+        sync_one_skill(&store, &skill.name, &enabled, &agents).unwrap();
+
+        write_upstream(&upstream, "---\nname: demo\n---\nv2\n");
+        let updated = update_skills(
+            std::slice::from_ref(&skill.id),
+            &agents,
+            &store_path,
+            &NoopProgress,
+        )
+        .unwrap();
+        assert_eq!(updated.len(), 1);
+        assert_eq!(updated[0].id, skill.id);
+        assert_eq!(updated[0].enabled_agents, enabled);
+        assert_eq!(updated[0].source_commit, Some(head_sha(&upstream)));
+        assert!(fs::read_to_string(store.join("demo").join("SKILL.md"))
+            .unwrap()
+            .contains("v2"));
+        assert!(fs::read_to_string(agent_root.join("demo").join("SKILL.md"))
+            .unwrap()
+            .contains("v2"));
+        assert!(store
+            .join(".skillsm")
+            .join("snapshots")
+            .join("demo")
+            .is_dir());
+
+        let again = update_skills(
+            std::slice::from_ref(&skill.id),
+            &agents,
+            &store_path,
+            &NoopProgress,
+        )
+        .unwrap();
+        assert!(again.is_empty());
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn update_skills_leaves_store_untouched_when_a_source_fails() {
+        let tmp = temp_test_dir("update-skills-fail");
+        let store = tmp.join("store");
+        let store_path = store.to_string_lossy().to_string();
+        let good = tmp.join("good");
+        let gone = tmp.join("gone");
+        let good_skill = install_skill(
+            &write_upstream(&good, "---\nname: good\n---\nv1\n"),
+            &store_path,
+        )
+        .unwrap();
+        let gone_skill = install_skill(
+            &write_upstream(&gone, "---\nname: gone\n---\nv1\n"),
+            &store_path,
+        )
+        .unwrap();
+
+        write_upstream(&good, "---\nname: good\n---\nv2\n");
+        fs::remove_dir_all(&gone).unwrap();
+        let ids = [good_skill.id.clone(), gone_skill.id.clone()];
+        let err = update_skills(&ids, &[], &store_path, &NoopProgress).unwrap_err();
+        assert!(err.contains("gone"), "{err}");
+
+        assert!(fs::read_to_string(store.join("good").join("SKILL.md"))
+            .unwrap()
+            .contains("v1"));
+        let manifest = load_manifest(&store).unwrap();
+        assert_eq!(
+            manifest.get("good").unwrap().source_commit,
+            good_skill.source_commit
+        );
+        let leftovers: Vec<_> = fs::read_dir(&store)
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with(".tmp-"))
+            .collect();
+        assert!(leftovers.is_empty());
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+}
@@ -0,0 +1,858 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use crate::digest::{digest_dir_with, DigestEntry};
+use crate::models::{AgentInfo, CopyStrategy};
+
+pub fn now_iso() -> String {
+    // ISO-ish; good enough for logs/UI.
+    // Example: 2026-01-23T02:15:34Z
+    let now = chrono::Utc::now();
+    now.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+}
+
+fn home_dir() -> Option<PathBuf> {
+    std::env::var("HOME")
+        .ok()
+        .map(PathBuf::from)
+        .or_else(|| std::env::var("USERPROFILE").ok().map(PathBuf::from))
+}
+
+pub fn expand_tilde(path: &str) -> PathBuf {
+    let trimmed = path.trim();
+    if trimmed == "~" {
+        return home_dir().unwrap_or_else(|| PathBuf::from("~"));
+    }
+    if let Some(rest) = trimmed.strip_prefix("~/") {
+        if let Some(home) = home_dir() {
+            return home.join(rest);
+        }
+    }
+    if let Some(rest) = trimmed.strip_prefix("~\\") {
+        if let Some(home) = home_dir() {
+            return home.join(rest);
+        }
+    }
+    PathBuf::from(trimmed)
+}
+
+pub fn manager_store_root(storage_path: &str) -> Result<PathBuf, String> {
+    let trimmed = storage_path.trim();
+    if trimmed.is_empty() {
+        return Err("storagePath is empty".to_string());
+    }
+    let root = expand_tilde(trimmed);
+    ensure_dir(&root)?;
+    Ok(root)
+}
+
+pub fn safe_skill_dir_name(name: &str) -> String {
+    let trimmed = name.trim();
+    if trimmed.is_empty() {
+        return "skill".to_string();
+    }
+    trimmed
+        .replace(['/', '\\'], "-")
+        .replace("..", "")
+        .trim()
+        .to_string()
+}
+
+pub fn unique_skill_dir_name(root: &Path, desired: &str) -> String {
+    let base = safe_skill_dir_name(desired);
+    let mut candidate = base.clone();
+    let mut idx = 2;
+    while root.join(&candidate).exists() {
+        candidate = format!("{base}-{idx}");
+        idx += 1;
+    }
+    candidate
+}
+
+pub fn ensure_dir(path: &Path) -> Result<(), String> {
+    fs::create_dir_all(path).map_err(|e| format!("Failed to create dir {}: {e}", path.display()))
+}
+
+pub fn remove_dir_if_exists(path: &Path) -> Result<(), String> {
+    let Ok(meta) = fs::symlink_metadata(path) else {
+        return Ok(());
+    };
+    // A linked skill dir points into the manager store: remove the link, never its target.
+    if meta.file_type().is_symlink() {
+        return fs::remove_file(path)
+            .or_else(|_| fs::remove_dir(path))
+            .map_err(|e| format!("Failed to remove symlink {}: {e}", path.display()));
+    }
+    fs::remove_dir_all(path).map_err(|e| format!("Failed to remove dir {}: {e}", path.display()))
+}
+
+pub fn is_same_path(a: &Path, b: &Path) -> bool {
+    if a == b {
+        return true;
+    }
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(ac), Ok(bc)) => ac == bc,
+        _ => false,
+    }
+}
+
+#[cfg(unix)]
+pub fn symlink_dir(src: &Path, dst: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(src, dst)
+}
+
+#[cfg(windows)]
+pub fn symlink_dir(src: &Path, dst: &Path) -> std::io::Result<()> {
+    std::os::windows::fs::symlink_dir(src, dst)
+}
+
+/// Files placed by [`copy_dir_all_with`], by the strategy that actually succeeded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CopyReport {
+    pub reflinked: usize,
+    pub hardlinked: usize,
+    pub copied: usize,
+}
+
+impl CopyReport {
+    /// The weakest strategy any file had to fall back to.
+    pub fn strategy(&self) -> CopyStrategy {
+        if self.copied > 0 {
+            CopyStrategy::Copy
+        } else if self.hardlinked > 0 {
+            CopyStrategy::Hardlink
+        } else if self.reflinked > 0 {
+            CopyStrategy::Reflink
+        } else {
+            CopyStrategy::Copy
+        }
+    }
+
+    pub fn merge(&mut self, other: CopyReport) {
+        self.reflinked += other.reflinked;
+        self.hardlinked += other.hardlinked;
+        self.copied += other.copied;
+    }
+
+    fn record(&mut self, used: CopyStrategy) {
+        match used {
+            CopyStrategy::Reflink => self.reflinked += 1,
+            CopyStrategy::Hardlink => self.hardlinked += 1,
+            CopyStrategy::Copy => self.copied += 1,
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn reflink_file(src: &Path, dst: &Path) -> std::io::Result<()> {
+    use std::os::fd::AsRawFd;
+
+    let src_file = fs::File::open(src)?;
+    let dst_file = fs::File::create(dst)?;
+    // SAFETY: both descriptors are open for the duration of the call.
+    let rc = unsafe { libc::ioctl(dst_file.as_raw_fd(), libc::FICLONE, src_file.as_raw_fd()) };
+    if rc != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn reflink_file(src: &Path, dst: &Path) -> std::io::Result<()> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let to_c = |p: &Path| {
+        CString::new(p.as_os_str().as_bytes())
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))
+    };
+    let (src_c, dst_c) = (to_c(src)?, to_c(dst)?);
+    // SAFETY: both arguments are valid NUL-terminated paths.
+    let rc = unsafe { libc::clonefile(src_c.as_ptr(), dst_c.as_ptr(), 0) };
+    if rc != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn reflink_file(_src: &Path, _dst: &Path) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "reflink is not supported on this platform",
+    ))
+}
+
+/// Places one file at `dst`, walking down reflink -> hardlink -> copy from `strategy`.
+fn copy_file_with(src: &Path, dst: &Path, strategy: CopyStrategy) -> Result<CopyStrategy, String> {
+    if strategy == CopyStrategy::Reflink {
+        if reflink_file(src, dst).is_ok() {
+            // The clone is created with default permissions; carry the mode over like fs::copy.
+            if let Ok(meta) = fs::metadata(src) {
+                let _ = fs::set_permissions(dst, meta.permissions());
+            }
+            return Ok(CopyStrategy::Reflink);
+        }
+        let _ = fs::remove_file(dst);
+    }
+    if matches!(strategy, CopyStrategy::Reflink | CopyStrategy::Hardlink)
+        && fs::hard_link(src, dst).is_ok()
+    {
+        return Ok(CopyStrategy::Hardlink);
+    }
+
+    fs::copy(src, dst).map_err(|e| {
+        format!(
+            "Failed to copy file {} -> {}: {e}",
+            src.display(),
+            dst.display()
+        )
+    })?;
+    Ok(CopyStrategy::Copy)
+}
+
+#[cfg(any(all(target_os = "linux", target_env = "gnu"), target_os = "macos"))]
+fn c_path(path: &Path) -> std::io::Result<std::ffi::CString> {
+    use std::os::unix::ffi::OsStrExt;
+
+    std::ffi::CString::new(path.as_os_str().as_bytes())
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))
+}
+
+/// Atomically swaps the entries at `a` and `b`, whatever their types.
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+fn exchange_paths(a: &Path, b: &Path) -> std::io::Result<()> {
+    let (a_c, b_c) = (c_path(a)?, c_path(b)?);
+    // SAFETY: both arguments are valid NUL-terminated paths.
+    let rc = unsafe {
+        libc::renameat2(
+            libc::AT_FDCWD,
+            a_c.as_ptr(),
+            libc::AT_FDCWD,
+            b_c.as_ptr(),
+            libc::RENAME_EXCHANGE,
+        )
+    };
+    if rc != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn exchange_paths(a: &Path, b: &Path) -> std::io::Result<()> {
+    let (a_c, b_c) = (c_path(a)?, c_path(b)?);
+    // SAFETY: both arguments are valid NUL-terminated paths.
+    let rc = unsafe { libc::renamex_np(a_c.as_ptr(), b_c.as_ptr(), libc::RENAME_SWAP) };
+    if rc != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(any(all(target_os = "linux", target_env = "gnu"), target_os = "macos")))]
+fn exchange_paths(_a: &Path, _b: &Path) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "atomic exchange is not supported on this platform",
+    ))
+}
+
+/// A fresh hidden sibling of `path` to build its replacement in.
+fn staging_sibling(path: &Path) -> PathBuf {
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    path.with_file_name(format!(".{file_name}.tmp-{}", generate_id()))
+}
+
+/// Whether `name` was made by [`staging_sibling`]: `.<name>.tmp-<id>`.
+pub(crate) fn is_staging_sibling(name: &str) -> bool {
+    let Some((head, id)) = name.rsplit_once(".tmp-") else {
+        return false;
+    };
+    let Some((stamp, seq)) = id.split_once('-') else {
+        return false;
+    };
+    head.len() > 1
+        && head.starts_with('.')
+        && [stamp, seq]
+            .iter()
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// Directories can only be fsynced on unix; elsewhere this does nothing.
+fn sync_dir(dir: &Path) {
+    #[cfg(unix)]
+    if let Ok(file) = fs::File::open(dir) {
+        let _ = file.sync_all();
+    }
+    #[cfg(not(unix))]
+    let _ = dir;
+}
+
+/// Flushes every file below `dir` to disk, so a tree is durable before it is
+/// swapped into place.
+fn sync_tree(dir: &Path) -> Result<(), String> {
+    for entry in
+        fs::read_dir(dir).map_err(|e| format!("Failed to read dir {}: {e}", dir.display()))?
+    {
+        let entry = entry.map_err(|e| format!("Failed to read entry: {e}"))?;
+        let file_type = entry
+            .file_type()
+            .map_err(|e| format!("Failed to read file type: {e}"))?;
+        let path = entry.path();
+        if file_type.is_dir() {
+            sync_tree(&path)?;
+        } else if file_type.is_file() {
+            let synced = fs::File::open(&path).and_then(|file| file.sync_all());
+            // Windows only flushes handles opened for writing, which read-only files refuse.
+            if cfg!(unix) {
+                synced.map_err(|e| format!("Failed to sync {}: {e}", path.display()))?;
+            }
+        }
+    }
+    sync_dir(dir);
+    Ok(())
+}
+
+/// Moves the tree built at `staged` to `to`. Whatever was at `to` stays there
+/// until the new tree has replaced it, so readers see either the old or the
+/// new version and a failure leaves the old one in place.
+fn swap_into_place(staged: &Path, to: &Path) -> Result<(), String> {
+    let swap_err = |e: std::io::Error| {
+        format!(
+            "Failed to move {} into place at {}: {e}",
+            staged.display(),
+            to.display()
+        )
+    };
+    if fs::symlink_metadata(to).is_err() {
+        fs::rename(staged, to).map_err(swap_err)?;
+    } else if exchange_paths(staged, to).is_ok() {
+        // `staged` now holds the previous version.
+        remove_path_if_exists(staged)?;
+    } else {
+        // Without an atomic exchange, `to` is briefly missing between the renames.
+        let previous = staging_sibling(to);
+        fs::rename(to, &previous)
+            .map_err(|e| format!("Failed to move aside {}: {e}", to.display()))?;
+        if let Err(e) = fs::rename(staged, to) {
+            let _ = fs::rename(&previous, to);
+            return Err(swap_err(e));
+        }
+        remove_path_if_exists(&previous)?;
+    }
+    if let Some(parent) = to.parent() {
+        sync_dir(parent);
+    }
+    Ok(())
+}
+
+/// Builds a replacement for `to` in a sibling with `build`, flushes it and
+/// swaps it in. On error the sibling is removed and `to` is left untouched.
+pub(crate) fn replace_atomically<T>(
+    to: &Path,
+    build: impl FnOnce(&Path) -> Result<T, String>,
+) -> Result<T, String> {
+    if let Some(parent) = to.parent() {
+        ensure_dir(parent)?;
+    }
+    let staged = staging_sibling(to);
+    let result = build(&staged)
+        .and_then(|out| {
+            if fs::symlink_metadata(&staged).is_ok_and(|meta| meta.is_dir()) {
+                sync_tree(&staged)?;
+            }
+            Ok(out)
+        })
+        .and_then(|out| swap_into_place(&staged, to).map(|_| out));
+    if result.is_err() {
+        let _ = remove_path_if_exists(&staged);
+    }
+    result
+}
+
+pub fn copy_dir_all(from: &Path, to: &Path) -> Result<(), String> {
+    copy_dir_all_with(from, to, CopyStrategy::Copy).map(|_| ())
+}
+
+/// Renames `src` to `dst`, copying instead when they are on different
+/// filesystems. Either way `dst` only appears once it is complete.
+pub(crate) fn move_dir(src: &Path, dst: &Path) -> Result<(), String> {
+    if let Some(parent) = dst.parent() {
+        ensure_dir(parent)?;
+    }
+    if fs::rename(src, dst).is_ok() {
+        return Ok(());
+    }
+    copy_dir_all(src, dst)?;
+    remove_dir_if_exists(src)
+}
+
+/// Replaces `to` with a copy of `from`, placing files with `strategy`.
+///
+/// The copy is built in a hidden sibling of `to` and swapped in once complete,
+/// so an agent reading `to` meanwhile never sees a partial tree.
+pub fn copy_dir_all_with(
+    from: &Path,
+    to: &Path,
+    strategy: CopyStrategy,
+) -> Result<CopyReport, String> {
+    fn copy_dir_all_inner(
+        from: &Path,
+        to: &Path,
+        strategy: CopyStrategy,
+        stack: &mut HashSet<PathBuf>,
+        report: &mut CopyReport,
+    ) -> Result<(), String> {
+        if !from.exists() {
+            return Err(format!("Source dir does not exist: {}", from.display()));
+        }
+        if !from.is_dir() {
+            return Err(format!("Source is not a dir: {}", from.display()));
+        }
+
+        let canon = fs::canonicalize(from).map_err(|e| {
+            format!("Failed to canonicalize {}: {e}", from.display())
+        })?;
+        if !stack.insert(canon.clone()) {
+            return Err(format!("Symlink cycle detected at {}", from.display()));
+        }
+
+        ensure_dir(to)?;
+
+        for entry in fs::read_dir(from)
+            .map_err(|e| format!("Failed to read dir {}: {e}", from.display()))?
+        {
+            let entry = entry.map_err(|e| format!("Failed to read entry: {e}"))?;
+            let file_type = entry
+                .file_type()
+                .map_err(|e| format!("Failed to read file type: {e}"))?;
+            let src_path = entry.path();
+            let dst_path = to.join(entry.file_name());
+
+            if file_type.is_dir() {
+                copy_dir_all_inner(&src_path, &dst_path, strategy, stack, report)?;
+                continue;
+            }
+            if file_type.is_file() {
+                report.record(copy_file_with(&src_path, &dst_path, strategy)?);
+                continue;
+            }
+
+            // Dereference symlinks and copy the target content (copy-based distribution; no symlinks in output).
+            if file_type.is_symlink() {
+                let meta = fs::metadata(&src_path).map_err(|e| {
+                    format!("Failed to stat symlink target {}: {e}", src_path.display())
+                })?;
+                if meta.is_dir() {
+                    copy_dir_all_inner(&src_path, &dst_path, strategy, stack, report)?;
+                } else if meta.is_file() {
+                    // hard_link() would link the symlink itself, so resolve it first.
+                    let target = fs::canonicalize(&src_path).map_err(|e| {
+                        format!("Failed to resolve symlink {}: {e}", src_path.display())
+                    })?;
+                    report.record(copy_file_with(&target, &dst_path, strategy)?);
+                }
+            }
+        }
+
+        stack.remove(&canon);
+        Ok(())
+    }
+
+    replace_atomically(to, |staged| {
+        let mut stack: HashSet<PathBuf> = HashSet::new();
+        let mut report = CopyReport::default();
+        copy_dir_all_inner(from, staged, strategy, &mut stack, &mut report)?;
+        Ok(report)
+    })
+}
+
+/// What [`sync_dir_all_with`] had to do to bring a target up to date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetSync {
+    Unchanged,
+    Updated,
+    Created,
+}
+
+pub(crate) fn remove_path_if_exists(path: &Path) -> Result<(), String> {
+    match fs::symlink_metadata(path) {
+        Ok(meta) if meta.is_dir() => remove_dir_if_exists(path),
+        Ok(_) => fs::remove_file(path)
+            .or_else(|_| fs::remove_dir(path))
+            .map_err(|e| format!("Failed to remove {}: {e}", path.display())),
+        Err(_) => Ok(()),
+    }
+}
+
+fn write_override(from: &Path, to: &Path, rel: &str, bytes: &[u8]) -> Result<(), String> {
+    let dst = to.join(rel);
+    write_file_atomic(&dst, bytes)?;
+    if let Ok(meta) = fs::metadata(from.join(rel)) {
+        fs::set_permissions(&dst, meta.permissions())
+            .map_err(|e| format!("Failed to set permissions on {}: {e}", dst.display()))?;
+    }
+    Ok(())
+}
+
+/// Brings `to` in line with `from` (plus `overrides`, keyed by relative path).
+///
+/// A target that differs is rebuilt in a hidden sibling and swapped in, so
+/// readers never see a partial tree and a failure leaves it as it was. Files
+/// whose digest already matches are carried over from the target rather than
+/// copied again, and are not counted in the report.
+pub fn sync_dir_all_with(
+    from: &Path,
+    to: &Path,
+    strategy: CopyStrategy,
+    overrides: &BTreeMap<String, Vec<u8>>,
+) -> Result<(TargetSync, CopyReport), String> {
+    let wanted = digest_dir_with(from, true, overrides)?;
+
+    let target_meta = fs::symlink_metadata(to).ok();
+    let current = if target_meta.as_ref().is_some_and(|meta| meta.is_dir()) {
+        let current = digest_dir_with(to, false, &BTreeMap::new())?;
+        if current.content_root() == wanted.content_root() {
+            return Ok((TargetSync::Unchanged, CopyReport::default()));
+        }
+        current.entries
+    } else {
+        BTreeMap::new()
+    };
+
+    let report = replace_atomically(to, |staged| {
+        ensure_dir(staged)?;
+        let mut report = CopyReport::default();
+        // Parents sort before their children, so every directory exists
+        // before the files inside it are placed.
+        for (rel, entry) in &wanted.entries {
+            let dst = staged.join(rel);
+            match entry {
+                DigestEntry::Dir => ensure_dir(&dst)?,
+                DigestEntry::File { .. } => {
+                    if current.get(rel) == Some(entry) {
+                        let existing = to.join(rel);
+                        if fs::hard_link(&existing, &dst).is_ok()
+                            || fs::copy(&existing, &dst).is_ok()
+                        {
+                            continue;
+                        }
+                    }
+                    if let Some(bytes) = overrides.get(rel) {
+                        write_override(from, staged, rel, bytes)?;
+                        report.record(CopyStrategy::Copy);
+                        continue;
+                    }
+                    // hard_link() would link a symlink itself, so resolve the source first.
+                    let src = fs::canonicalize(from.join(rel)).map_err(|e| {
+                        format!("Failed to resolve {}: {e}", from.join(rel).display())
+                    })?;
+                    report.record(copy_file_with(&src, &dst, strategy)?);
+                }
+                DigestEntry::Link => {}
+            }
+        }
+        Ok(report)
+    })?;
+
+    let outcome = if target_meta.is_some() {
+        TargetSync::Updated
+    } else {
+        TargetSync::Created
+    };
+    Ok((outcome, report))
+}
+
+/// Writes `contents` to a sibling temp file and renames it over `path`.
+///
+/// Readers never observe a partial file, and a hardlinked `path` is replaced
+/// rather than written through.
+pub fn write_file_atomic(path: &Path, contents: &[u8]) -> Result<(), String> {
+    let tmp = staging_sibling(path);
+
+    let write_tmp = || -> std::io::Result<()> {
+        let mut file = fs::File::create(&tmp)?;
+        file.write_all(contents)?;
+        file.sync_all()
+    };
+    if let Err(e) = write_tmp() {
+        let _ = fs::remove_file(&tmp);
+        return Err(format!("Failed to write {}: {e}", tmp.display()));
+    }
+    fs::rename(&tmp, path).map_err(|e| {
+        let _ = fs::remove_file(&tmp);
+        format!("Failed to replace {}: {e}", path.display())
+    })
+}
+
+/// An id no other call returns: the clock and pid tell processes apart, and a
+/// counter tells apart the ids one process makes within a millisecond.
+pub fn generate_id() -> String {
+    static NEXT: AtomicU64 = AtomicU64::new(0);
+    let ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    let pid = std::process::id();
+    let seq = NEXT.fetch_add(1, Ordering::Relaxed);
+    format!("{ms:x}{pid:x}-{seq:x}")
+}
+
+pub fn agent_roots(agent: &AgentInfo) -> Vec<PathBuf> {
+    let mut roots: Vec<PathBuf> = vec![];
+
+    let mut push = |candidate: &str| {
+        let trimmed = candidate.trim();
+        if trimmed.is_empty() {
+            return;
+        }
+        let p = expand_tilde(trimmed);
+        if roots.iter().any(|v| v == &p) {
+            return;
+        }
+        roots.push(p);
+    };
+
+    push(&agent.current_path);
+    push(&agent.default_path);
+
+    roots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unique_test_root(label: &str) -> PathBuf {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        std::env::temp_dir().join(format!(
+            "skills-manager-test-{label}-{nanos}-{}",
+            std::process::id()
+        ))
+    }
+
+    #[cfg(unix)]
+    fn symlink_file(src: &Path, dst: &Path) -> std::io::Result<()> {
+        std::os::unix::fs::symlink(src, dst)
+    }
+
+    #[cfg(windows)]
+    fn symlink_file(src: &Path, dst: &Path) -> std::io::Result<()> {
+        std::os::windows::fs::symlink_file(src, dst)
+    }
+
+    #[test]
+    fn copy_dir_all_dereferences_symlinks() {
+        let root = unique_test_root("dereference");
+        let src = root.join("src");
+        let dst = root.join("dst");
+
+        ensure_dir(&src).unwrap();
+        ensure_dir(&src.join("real")).unwrap();
+        fs::write(src.join("real").join("hello.txt"), "hi").unwrap();
+
+        if symlink_dir(&src.join("real"), &src.join("linkdir")).is_err() {
+            let _ = fs::remove_dir_all(&root);
+            return;
+        }
+        if symlink_file(&src.join("real").join("hello.txt"), &src.join("linkfile")).is_err() {
+            let _ = fs::remove_dir_all(&root);
+            return;
+        }
+
+        copy_dir_all(&src, &dst).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(dst.join("linkdir").join("hello.txt")).unwrap(),
+            "hi"
+        );
+        assert_eq!(fs::read_to_string(dst.join("linkfile")).unwrap(), "hi");
+
+        let md_file = fs::symlink_metadata(dst.join("linkfile")).unwrap();
+        assert!(!md_file.file_type().is_symlink());
+
+        let md_dir = fs::symlink_metadata(dst.join("linkdir")).unwrap();
+        assert!(!md_dir.file_type().is_symlink());
+        assert!(dst.join("linkdir").is_dir());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn remove_dir_if_exists_removes_link_but_keeps_target() {
+        let root = unique_test_root("remove-link");
+        let target = root.join("target");
+        let link = root.join("link");
+        ensure_dir(&target).unwrap();
+        fs::write(target.join("hello.txt"), "hi").unwrap();
+
+        if symlink_dir(&target, &link).is_err() {
+            let _ = fs::remove_dir_all(&root);
+            return;
+        }
+
+        remove_dir_if_exists(&link).unwrap();
+
+        assert!(fs::symlink_metadata(&link).is_err());
+        assert_eq!(fs::read_to_string(target.join("hello.txt")).unwrap(), "hi");
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn copy_dir_all_detects_symlink_cycles() {
+        let root = unique_test_root("cycle");
+        let src = root.join("src");
+        let dst = root.join("dst");
+        ensure_dir(&src).unwrap();
+        ensure_dir(&src.join("a")).unwrap();
+        fs::write(src.join("a").join("hello.txt"), "hi").unwrap();
+
+        if symlink_dir(&src.join("a"), &src.join("a").join("loop")).is_err() {
+            let _ = fs::remove_dir_all(&root);
+            return;
+        }
+
+        let err = copy_dir_all(&src, &dst).unwrap_err();
+        assert!(err.to_lowercase().contains("symlink cycle"));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    // tmpfs hardlinks but does not reflink, which pins down the fallback.
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn copy_dir_all_with_falls_back_instead_of_copying() {
+        use std::os::unix::fs::MetadataExt;
+
+        let shm = Path::new("/dev/shm");
+        let root = shm.join(unique_test_root("copy-strategy").file_name().unwrap());
+        if !shm.is_dir() || ensure_dir(&root).is_err() {
+            return;
+        }
+        let src = root.join("src");
+        let dst = root.join("dst");
+        ensure_dir(&src.join("nested")).unwrap();
+        fs::write(src.join("SKILL.md"), "# skill\n").unwrap();
+        fs::write(src.join("nested").join("a.txt"), "a\n").unwrap();
+
+        let report = copy_dir_all_with(&src, &dst, CopyStrategy::Reflink).unwrap();
+        assert_eq!((report.reflinked, report.hardlinked, report.copied), (0, 2, 0));
+        assert_eq!(report.strategy(), CopyStrategy::Hardlink);
+        assert_eq!(fs::read_to_string(dst.join("nested").join("a.txt")).unwrap(), "a\n");
+
+        let report = copy_dir_all_with(&src, &dst, CopyStrategy::Copy).unwrap();
+        assert_eq!(report.copied, 2);
+        assert_eq!(report.strategy(), CopyStrategy::Copy);
+
+        // Neither links across filesystems, so both fall through to a plain copy.
+        let other = unique_test_root("copy-strategy-other");
+        ensure_dir(&other).unwrap();
+        if fs::metadata(&other).unwrap().dev() != fs::metadata(&root).unwrap().dev() {
+            let report =
+                copy_dir_all_with(&src, &other.join("dst"), CopyStrategy::Reflink).unwrap();
+            assert_eq!((report.reflinked, report.hardlinked, report.copied), (0, 0, 2));
+            assert_eq!(report.strategy(), CopyStrategy::Copy);
+            assert_eq!(
+                fs::read_to_string(other.join("dst").join("nested").join("a.txt")).unwrap(),
+                "a\n"
+            );
+        }
+
+        let _ = fs::remove_dir_all(&other);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn failed_replacement_keeps_previous_target() {
+        let root = unique_test_root("atomic-replace");
+        let src = root.join("src");
+        let dst = root.join("dst");
+        ensure_dir(&src.join("a")).unwrap();
+        fs::write(src.join("a").join("hello.txt"), "new\n").unwrap();
+        ensure_dir(&dst).unwrap();
+        fs::write(dst.join("hello.txt"), "old\n").unwrap();
+
+        if symlink_dir(&src.join("a"), &src.join("a").join("loop")).is_err() {
+            let _ = fs::remove_dir_all(&root);
+            return;
+        }
+
+        assert!(copy_dir_all(&src, &dst).is_err());
+        assert!(sync_dir_all_with(&src, &dst, CopyStrategy::Copy, &BTreeMap::new()).is_err());
+        assert_eq!(fs::read_to_string(dst.join("hello.txt")).unwrap(), "old\n");
+        let leftovers: Vec<_> = fs::read_dir(&root)
+            .unwrap()
+            .flatten()
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with('.'))
+            .collect();
+        assert!(leftovers.is_empty());
+
+        fs::remove_file(src.join("a").join("loop")).unwrap();
+        let (outcome, _) =
+            sync_dir_all_with(&src, &dst, CopyStrategy::Copy, &BTreeMap::new()).unwrap();
+        assert_eq!(outcome, TargetSync::Updated);
+        assert_eq!(fs::read_to_string(dst.join("a").join("hello.txt")).unwrap(), "new\n");
+        assert!(!dst.join("hello.txt").exists());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn sync_dir_all_with_ignores_permission_bits_other_than_exec() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let root = unique_test_root("sync-modes");
+        let src = root.join("src");
+        let dst = root.join("dst");
+        ensure_dir(&src).unwrap();
+        fs::write(src.join("run.sh"), "echo hi\n").unwrap();
+        sync_dir_all_with(&src, &dst, CopyStrategy::Copy, &BTreeMap::new()).unwrap();
+
+        let set_mode = |mode| {
+            fs::set_permissions(dst.join("run.sh"), fs::Permissions::from_mode(mode)).unwrap()
+        };
+        set_mode(0o600);
+        let (outcome, _) =
+            sync_dir_all_with(&src, &dst, CopyStrategy::Copy, &BTreeMap::new()).unwrap();
+        assert_eq!(outcome, TargetSync::Unchanged);
+
+        set_mode(0o700);
+        let (outcome, _) =
+            sync_dir_all_with(&src, &dst, CopyStrategy::Copy, &BTreeMap::new()).unwrap();
+        assert_eq!(outcome, TargetSync::Updated);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn write_file_atomic_does_not_write_through_hardlinks() {
+        let root = unique_test_root("atomic-hardlink");
+        ensure_dir(&root).unwrap();
+        let original = root.join("original.md");
+        let linked = root.join("linked.md");
+        fs::write(&original, "old\n").unwrap();
+        fs::hard_link(&original, &linked).unwrap();
+
+        write_file_atomic(&linked, b"new\n").unwrap();
+        assert_eq!(fs::read_to_string(&linked).unwrap(), "new\n");
+        assert_eq!(fs::read_to_string(&original).unwrap(), "old\n");
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -1,15 +1,26 @@
-mod models;
+mod progress;
 mod services;
-mod utils;
 
 use services::skill_service::{
-    bootstrap_skills_store, install_skill, install_skill_cli, reinstall_skill, reset_store,
-    uninstall_skill,
+    bootstrap_skills_store, check_updates, delete_trash_entry, empty_trash, export_lock, get_skill,
+    inspect_source, install_from_lock, install_from_source, install_skill, install_skill_cli,
+    install_skill_with_progress, lint_skill, lint_store, list_skills, list_trash,
+    plan_uninstall_skill, purge_expired_trash, reinstall_skill, reset_store, restore_skill,
+    uninstall_skill, update_skills,
+};
+use services::storage_service::{
+    export_backup, get_store_settings, import_backup, migrate_manager_store, preview_backup,
+    select_manager_store_directory, set_store_settings,
 };
-use services::storage_service::{migrate_manager_store, select_manager_store_directory};
 use services::sync_service::{
     detect_startup_untracked_skills,
+    diff_skill,
+    get_distribution_status,
+    plan_sync_all_skills_distribution,
+    plan_sync_all_to_manager_store,
     get_skill_description,
+    get_skill_metadata,
+    pull_skill_from_agent,
     sync_selected_skills_to_manager_store,
     sync_all_skills_distribution, sync_all_skills_distribution_with_progress,
     sync_all_to_manager_store, sync_all_to_manager_store_with_progress, sync_skill_distribution,
@@ -21,11 +32,27 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .invoke_handler(tauri::generate_handler![
             bootstrap_skills_store,
+            list_skills,
+            get_skill,
             install_skill,
             install_skill_cli,
+            install_skill_with_progress,
+            inspect_source,
+            install_from_source,
             reinstall_skill,
+            check_updates,
+            update_skills,
+            export_lock,
+            install_from_lock,
+            lint_skill,
+            lint_store,
             select_manager_store_directory,
             migrate_manager_store,
+            get_store_settings,
+            set_store_settings,
+            export_backup,
+            preview_backup,
+            import_backup,
             sync_skill_distribution,
             sync_all_skills_distribution,
             sync_all_skills_distribution_with_progress,
@@ -34,7 +61,19 @@ pub fn run() {
             detect_startup_untracked_skills,
             sync_selected_skills_to_manager_store,
             get_skill_description,
+            get_skill_metadata,
+            get_distribution_status,
+            plan_sync_all_skills_distribution,
+            plan_sync_all_to_manager_store,
+            diff_skill,
+            pull_skill_from_agent,
             uninstall_skill,
+            plan_uninstall_skill,
+            list_trash,
+            restore_skill,
+            delete_trash_entry,
+            empty_trash,
+            purge_expired_trash,
             reset_store,
         ])
         .run(tauri::generate_context!())
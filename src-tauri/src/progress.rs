@@ -0,0 +1,24 @@
+use serde::Serialize;
+use skills_manager_core::progress::ProgressReporter;
+use tauri::Emitter;
+
+/// Forwards core progress logs to the frontend as events on a fixed channel.
+#[derive(Clone)]
+pub(crate) struct EventProgress {
+    app: Option<tauri::AppHandle>,
+    event: &'static str,
+}
+
+impl EventProgress {
+    pub(crate) fn new(app: Option<tauri::AppHandle>, event: &'static str) -> Self {
+        Self { app, event }
+    }
+}
+
+impl<T: Serialize + Clone> ProgressReporter<T> for EventProgress {
+    fn report(&self, payload: T) {
+        if let Some(app) = &self.app {
+            let _ = app.emit(self.event, payload);
+        }
+    }
+}
@@ -1,261 +1,90 @@
-use std::collections::BTreeMap;
-use std::fs;
-use std::path::{Path, PathBuf};
-use std::process::Command;
-
-use crate::models::{AgentInfo, Skill};
-use crate::utils::{
-    agent_roots, copy_dir_all, ensure_dir, expand_tilde, generate_id, manager_store_root, now_iso,
-    remove_dir_if_exists, safe_skill_dir_name, unique_skill_dir_name,
-};
-
-fn normalize_install_url(input: &str) -> String {
-    let trimmed = input.trim().trim_end_matches('/');
-    if trimmed.starts_with("http://") || trimmed.starts_with("https://") {
-        return trimmed.to_string();
-    }
-    if trimmed.starts_with("github.com/") {
-        return format!("https://{trimmed}");
-    }
-    if trimmed.matches('/').count() == 1 && !trimmed.contains(' ') {
-        return format!("https://github.com/{trimmed}");
-    }
-    trimmed.to_string()
-}
-
-fn parse_name_from_dir(dir: &Path, fallback_name: &str) -> String {
-    let mut name = fallback_name.to_string();
-
-    let candidates = [
-        "SKILL.md",
-        "skill.md",
-        "README.md",
-        "README.MD",
-        "readme.md",
-    ];
-    let mut content: Option<String> = None;
-    for file in candidates {
-        let path = dir.join(file);
-        if path.exists() {
-            if let Ok(text) = fs::read_to_string(&path) {
-                content = Some(text);
-                break;
-            }
-        }
-    }
-
-    if let Some(text) = content {
-        for line in text.lines() {
-            let t = line.trim();
-            if t.starts_with("# ") {
-                name = t.trim_start_matches("# ").trim().to_string();
-                break;
-            }
-        }
-    }
-
-    name
-}
-
-fn run_cmd(mut cmd: Command, label: &str) -> Result<(), String> {
-    let status = cmd
-        .status()
-        .map_err(|e| format!("Failed to run {label}: {e}"))?;
-    if !status.success() {
-        return Err(format!("{label} exited with status: {status}"));
-    }
-    Ok(())
+use skills_manager_core::installer::{self, SourceInspection};
+use skills_manager_core::lint::{self, LintReport};
+use skills_manager_core::lockfile::{self, LockExport, LockInstallReport};
+use skills_manager_core::models::{AgentInfo, Skill};
+use skills_manager_core::plan::{self, PlannedOperation};
+use skills_manager_core::store::{self, StoreBootstrap};
+use skills_manager_core::trash::{self, RestoreReport, TrashEntry};
+use skills_manager_core::updater::{self, UpdateCheckReport};
+use skills_manager_core::utils::expand_tilde;
+
+use crate::progress::EventProgress;
+
+const INSTALL_SKILL_EVENT: &str = "install_skill:progress";
+const UPDATE_SKILLS_EVENT: &str = "update_skills:progress";
+const INSTALL_FROM_LOCK_EVENT: &str = "install_from_lock:progress";
+
+/// Settles operations an earlier run left unfinished, sweeps stale staging
+/// siblings from the store and `agents`, then returns the store's skills.
+#[tauri::command]
+pub(crate) async fn bootstrap_skills_store(
+    skills: Vec<Skill>,
+    agents: Vec<AgentInfo>,
+    storage_path: String,
+) -> Result<StoreBootstrap, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        store::bootstrap_skills_store(skills, &agents, &storage_path)
+    })
+    .await
+    .map_err(|e| format!("bootstrap_skills_store task join error: {e}"))?
 }
 
-fn install_zip(url: &str, dest: &Path) -> Result<(), String> {
-    let tmp_dir = std::env::temp_dir().join(format!("skills-manager-zip-{}", generate_id()));
-    ensure_dir(&tmp_dir)?;
-    let zip_path = tmp_dir.join("download.zip");
-    let extract_dir = tmp_dir.join("extract");
-    ensure_dir(&extract_dir)?;
-
-    let mut curl = Command::new("curl");
-    curl.arg("-L").arg("-o").arg(&zip_path).arg(url);
-    run_cmd(curl, "curl")?;
-
-    let mut unzip = Command::new("unzip");
-    unzip.arg("-q").arg(&zip_path).arg("-d").arg(&extract_dir);
-    run_cmd(unzip, "unzip")?;
-
-    let mut top_dirs: Vec<PathBuf> = vec![];
-    for entry in
-        fs::read_dir(&extract_dir).map_err(|e| format!("Failed to read extract dir: {e}"))?
-    {
-        let entry = entry.map_err(|e| format!("Failed to read entry: {e}"))?;
-        let p = entry.path();
-        if p.is_dir() {
-            top_dirs.push(p);
-        }
-    }
-
-    let root = if top_dirs.len() == 1 {
-        top_dirs.remove(0)
-    } else {
-        extract_dir
-    };
-
-    copy_dir_all(&root, dest)?;
-    let _ = fs::remove_dir_all(&tmp_dir);
-    Ok(())
+#[tauri::command]
+pub(crate) fn list_skills(storage_path: String) -> Result<Vec<Skill>, String> {
+    store::list_skills(&storage_path)
 }
 
-fn install_git(url: &str, dest: &Path) -> Result<(), String> {
-    let clone_url = if url.ends_with(".git") {
-        url.to_string()
-    } else {
-        format!("{url}.git")
-    };
-
-    let mut git = Command::new("git");
-    git.arg("clone")
-        .arg("--depth")
-        .arg("1")
-        .arg(&clone_url)
-        .arg(dest);
-    run_cmd(git, "git clone")?;
-
-    let _ = fs::remove_dir_all(dest.join(".git"));
-    Ok(())
+#[tauri::command]
+pub(crate) fn get_skill(skill_name: String, storage_path: String) -> Result<Option<Skill>, String> {
+    store::get_skill(&skill_name, &storage_path)
 }
 
-fn candidate_post_install_sources(skill_dir_name: &str) -> Vec<PathBuf> {
-    [
-        "~/.agents/skills", // npx skills add -g installs here
-    ]
-    .iter()
-    .map(|root| expand_tilde(root).join(skill_dir_name))
-    .collect()
+#[tauri::command]
+pub(crate) fn install_skill(repo_url: String, storage_path: String) -> Result<Skill, String> {
+    installer::install_skill(&repo_url, &storage_path)
 }
 
-fn read_store_skill_names(store_root: &Path) -> Result<Vec<String>, String> {
-    let mut names: Vec<String> = vec![];
-
-    for entry in fs::read_dir(store_root)
-        .map_err(|e| format!("Failed to read manager store {}: {e}", store_root.display()))?
-    {
-        let entry = entry.map_err(|e| format!("Failed to read entry: {e}"))?;
-        let file_type = entry
-            .file_type()
-            .map_err(|e| format!("Failed to read file type: {e}"))?;
-        if !file_type.is_dir() {
-            continue;
-        }
-
-        let name = entry.file_name().to_string_lossy().to_string();
-        if name.is_empty() || name.starts_with('.') {
-            continue;
-        }
-
-        names.push(safe_skill_dir_name(&name));
-    }
-
-    names.sort();
-    names.dedup();
-    Ok(names)
+#[tauri::command]
+pub(crate) async fn install_skill_with_progress(
+    app: tauri::AppHandle,
+    repo_url: String,
+    storage_path: String,
+) -> Result<Skill, String> {
+    let progress = EventProgress::new(Some(app), INSTALL_SKILL_EVENT);
+    tauri::async_runtime::spawn_blocking(move || {
+        installer::install_skill_with_progress(&repo_url, &storage_path, &progress)
+    })
+    .await
+    .map_err(|e| format!("install_skill_with_progress task join error: {e}"))?
 }
 
 #[tauri::command]
-pub(crate) fn bootstrap_skills_store(
-    skills: Vec<Skill>,
+pub(crate) async fn inspect_source(
+    app: tauri::AppHandle,
+    url: String,
     storage_path: String,
-) -> Result<Vec<Skill>, String> {
-    let dir = manager_store_root(&storage_path)?;
-
-    let store_has_skills = !read_store_skill_names(&dir)?.is_empty();
-
-    // 兼容旧版本：仅在中心库为空时，才根据已持久化状态补齐目录。
-    if !store_has_skills {
-        for skill in &skills {
-            let skill_dir = dir.join(safe_skill_dir_name(&skill.name));
-            if skill_dir.exists() {
-                continue;
-            }
-            ensure_dir(&skill_dir)?;
-            let skill_md = format!("# {}\n", skill.name);
-            let _ = fs::write(skill_dir.join("SKILL.md"), skill_md);
-        }
-    }
-
-    let now = now_iso();
-    let existing_by_name: BTreeMap<String, Skill> = skills
-        .into_iter()
-        .map(|skill| (safe_skill_dir_name(&skill.name), skill))
-        .collect();
-
-    let mut merged: Vec<Skill> = vec![];
-    for name in read_store_skill_names(&dir)? {
-        if let Some(existing) = existing_by_name.get(&name) {
-            let mut hydrated = existing.clone();
-            hydrated.id = name.clone();
-            hydrated.name = name;
-            if hydrated.last_sync.is_none() {
-                hydrated.last_sync = Some(now.clone());
-            }
-            if hydrated.last_update.is_none() {
-                hydrated.last_update = Some(now.clone());
-            }
-            merged.push(hydrated);
-            continue;
-        }
-
-        merged.push(Skill {
-            id: name.clone(),
-            name,
-            source_url: None,
-            enabled_agents: vec![],
-            last_sync: Some(now.clone()),
-            last_update: Some(now.clone()),
-        });
-    }
-
-    Ok(merged)
+) -> Result<SourceInspection, String> {
+    let progress = EventProgress::new(Some(app), INSTALL_SKILL_EVENT);
+    tauri::async_runtime::spawn_blocking(move || {
+        installer::inspect_source(&url, &storage_path, &progress)
+    })
+    .await
+    .map_err(|e| format!("inspect_source task join error: {e}"))?
 }
 
 #[tauri::command]
-pub(crate) fn install_skill(repo_url: String, storage_path: String) -> Result<Skill, String> {
-    let url = normalize_install_url(&repo_url);
-    let lower = url.to_lowercase();
-    let skill_id = generate_id();
-    let store_dir = manager_store_root(&storage_path)?;
-    let temp_dest = store_dir.join(format!(".tmp-install-{skill_id}"));
-    let _ = remove_dir_if_exists(&temp_dest);
-
-    if lower.ends_with(".zip") || lower.contains(".zip?") {
-        install_zip(&url, &temp_dest)?;
-    } else {
-        install_git(&url, &temp_dest)?;
-    }
-
-    let fallback_name = url
-        .split('/')
-        .last()
-        .unwrap_or("skill")
-        .trim_end_matches(".git")
-        .trim_end_matches(".zip");
-    let meta_name = parse_name_from_dir(&temp_dest, fallback_name);
-    let dir_name = unique_skill_dir_name(&store_dir, &meta_name);
-    let final_dest = store_dir.join(&dir_name);
-
-    if let Err(_e) = fs::rename(&temp_dest, &final_dest) {
-        copy_dir_all(&temp_dest, &final_dest)?;
-        let _ = fs::remove_dir_all(&temp_dest);
-    }
-
-    let now = now_iso();
-
-    Ok(Skill {
-        id: skill_id,
-        name: dir_name,
-        source_url: Some(repo_url),
-        enabled_agents: vec![],
-        last_sync: Some(now.clone()),
-        last_update: Some(now),
+pub(crate) async fn install_from_source(
+    app: tauri::AppHandle,
+    url: String,
+    selected: Vec<String>,
+    storage_path: String,
+) -> Result<Vec<Skill>, String> {
+    let progress = EventProgress::new(Some(app), INSTALL_SKILL_EVENT);
+    tauri::async_runtime::spawn_blocking(move || {
+        installer::install_from_source(&url, &selected, &storage_path, &progress)
     })
+    .await
+    .map_err(|e| format!("install_from_source task join error: {e}"))?
 }
 
 #[tauri::command]
@@ -267,70 +96,65 @@ pub(crate) async fn reinstall_skill(
     storage_path: String,
 ) -> Result<Skill, String> {
     tauri::async_runtime::spawn_blocking(move || {
-        if repo_url.trim().is_empty() {
-            return Err("repoUrl is empty".to_string());
-        }
-
-        let store_dir = manager_store_root(&storage_path)?;
-        ensure_dir(&store_dir)?;
-
-        let safe_name = safe_skill_dir_name(&skill_name);
-        if safe_name.trim().is_empty() {
-            return Err("skillName is empty".to_string());
-        }
-
-        let url = normalize_install_url(&repo_url);
-
-        let mut npx = Command::new("npx");
-        npx.arg("skills")
-            .arg("add")
-            .arg(&url)
-            .arg("--skill")
-            .arg(&safe_name)
-            .arg("-g")
-            .arg("-y");
-        run_cmd(npx, "npx skills add")?;
-
-        let temp_dest = store_dir.join(format!(".tmp-reinstall-{}", generate_id()));
-        let _ = remove_dir_if_exists(&temp_dest);
-
-        let mut copied = false;
-        for src in candidate_post_install_sources(&safe_name) {
-            if src.exists() && src.is_dir() {
-                copy_dir_all(&src, &temp_dest)?;
-                copied = true;
-                break;
-            }
-        }
-
-        if !copied {
-            return Err(format!(
-                "Installed skill directory not found under known locations (expected ~/.agents/skills/{0})",
-                safe_name
-            ));
-        }
+        installer::reinstall_skill(
+            &skill_id,
+            &skill_name,
+            &repo_url,
+            enabled_agents,
+            &storage_path,
+        )
+    })
+    .await
+    .map_err(|e| format!("reinstall_skill task join error: {e}"))?
+}
 
-        let final_dest = store_dir.join(&safe_name);
-        let _ = remove_dir_if_exists(&final_dest);
+#[tauri::command]
+pub(crate) async fn check_updates(
+    app: tauri::AppHandle,
+    storage_path: String,
+) -> Result<UpdateCheckReport, String> {
+    let progress = EventProgress::new(Some(app), UPDATE_SKILLS_EVENT);
+    tauri::async_runtime::spawn_blocking(move || updater::check_updates(&storage_path, &progress))
+        .await
+        .map_err(|e| format!("check_updates task join error: {e}"))?
+}
 
-        if let Err(_e) = fs::rename(&temp_dest, &final_dest) {
-            copy_dir_all(&temp_dest, &final_dest)?;
-            let _ = fs::remove_dir_all(&temp_dest);
-        }
+#[tauri::command]
+pub(crate) async fn update_skills(
+    app: tauri::AppHandle,
+    ids: Vec<String>,
+    agents: Vec<AgentInfo>,
+    storage_path: String,
+) -> Result<Vec<Skill>, String> {
+    let progress = EventProgress::new(Some(app), UPDATE_SKILLS_EVENT);
+    tauri::async_runtime::spawn_blocking(move || {
+        updater::update_skills(&ids, &agents, &storage_path, &progress)
+    })
+    .await
+    .map_err(|e| format!("update_skills task join error: {e}"))?
+}
 
-        let now = now_iso();
+#[tauri::command]
+pub(crate) fn export_lock(path: String, storage_path: String) -> Result<LockExport, String> {
+    let export = lockfile::export_lock(&storage_path)?;
+    lockfile::write_lock_file(&expand_tilde(&path), &export.lock)?;
+    Ok(export)
+}
 
-        Ok(Skill {
-            id: skill_id,
-            name: safe_name,
-            source_url: Some(repo_url),
-            enabled_agents,
-            last_sync: Some(now.clone()),
-            last_update: Some(now),
-        })
+#[tauri::command]
+pub(crate) async fn install_from_lock(
+    app: tauri::AppHandle,
+    path: String,
+    agents: Vec<AgentInfo>,
+    storage_path: String,
+) -> Result<LockInstallReport, String> {
+    let progress = EventProgress::new(Some(app), INSTALL_FROM_LOCK_EVENT);
+    tauri::async_runtime::spawn_blocking(move || {
+        let lock = lockfile::read_lock_file(&expand_tilde(&path))?;
+        lockfile::install_from_lock(&lock, &agents, &storage_path, &progress)
     })
     .await
-    .map_err(|e| format!("reinstall_skill task join error: {e}"))?
+    .map_err(|e| format!("install_from_lock task join error: {e}"))?
 }
 
 #[tauri::command]
@@ -340,52 +164,7 @@ pub(crate) async fn install_skill_cli(
     storage_path: String,
 ) -> Result<Skill, String> {
     tauri::async_runtime::spawn_blocking(move || {
-        if skill_name.trim().is_empty() {
-            return Err("skillName is empty".to_string());
-        }
-
-        let url = normalize_install_url(&repo_url);
-        let desired_name = safe_skill_dir_name(&skill_name);
-
-        let mut npx = Command::new("npx");
-        npx.arg("skills")
-            .arg("add")
-            .arg(&url)
-            .arg("--skill")
-            .arg(&desired_name)
-            .arg("-g")
-            .arg("-y");
-        run_cmd(npx, "npx skills add")?;
-
-        let store_root = manager_store_root(&storage_path)?;
-        let store_dest = store_root.join(&desired_name);
-
-        let mut copied = false;
-        for src in candidate_post_install_sources(&desired_name) {
-            if src.exists() && src.is_dir() {
-                copy_dir_all(&src, &store_dest)?;
-                copied = true;
-                break;
-            }
-        }
-
-        if !copied {
-            return Err(format!(
-                "Installed skill directory not found under known locations (expected ~/.agents/skills/{0})",
-                desired_name
-            ));
-        }
-
-        let now = now_iso();
-
-        Ok(Skill {
-            id: generate_id(),
-            name: desired_name,
-            source_url: Some(repo_url),
-            enabled_agents: vec![],
-            last_sync: Some(now.clone()),
-            last_update: Some(now),
-        })
+        installer::install_skill_cli(&repo_url, &skill_name, &storage_path)
     })
     .await
     .map_err(|e| format!("install_skill_cli task join error: {e}"))?
@@ -397,117 +176,68 @@ pub(crate) fn uninstall_skill(
     skill_name: String,
     agents: Vec<AgentInfo>,
     storage_path: String,
-) -> Result<(), String> {
+) -> Result<TrashEntry, String> {
     let _ = skill_id;
-    let store_root = expand_tilde(&storage_path);
-    let src = store_root.join(safe_skill_dir_name(&skill_name));
-    let _ = remove_dir_if_exists(&src);
-
-    for agent in agents {
-        for root in agent_roots(&agent) {
-            let dst = root.join(safe_skill_dir_name(&skill_name));
-            let _ = remove_dir_if_exists(&dst);
-        }
-    }
-
-    Ok(())
+    store::uninstall_skill(&skill_name, &agents, &storage_path)
 }
 
 #[tauri::command]
-pub(crate) fn reset_store(storage_path: String) -> Result<(), String> {
-    let root = expand_tilde(&storage_path);
-    remove_dir_if_exists(&root)
+pub(crate) fn plan_uninstall_skill(
+    skill_name: String,
+    agents: Vec<AgentInfo>,
+    storage_path: String,
+) -> Result<Vec<PlannedOperation>, String> {
+    plan::plan_uninstall_skill(&skill_name, &agents, &storage_path)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    fn temp_test_dir(name: &str) -> PathBuf {
-        std::env::temp_dir().join(format!("skills-manager-{name}-{}", generate_id()))
-    }
-
-    fn write_skill(store_root: &Path, name: &str) {
-        let dir = store_root.join(name);
-        ensure_dir(&dir).expect("create skill dir");
-        fs::write(dir.join("SKILL.md"), format!("# {name}\n")).expect("write SKILL.md");
-    }
-
-    #[test]
-    fn normalize_install_url_normalizes_github_urls() {
-        assert_eq!(
-            normalize_install_url("github.com/foo/bar"),
-            "https://github.com/foo/bar"
-        );
-        assert_eq!(
-            normalize_install_url("foo/bar"),
-            "https://github.com/foo/bar"
-        );
-        assert_eq!(
-            normalize_install_url("https://github.com/foo/bar/"),
-            "https://github.com/foo/bar"
-        );
-        assert_eq!(
-            normalize_install_url("https://github.com/affaan-m/everything-claude-code/tree/main/skills/security-review/"),
-            "https://github.com/affaan-m/everything-claude-code/tree/main/skills/security-review"
-        );
-        assert_eq!(normalize_install_url("http://example.com/x"), "http://example.com/x");
-    }
-
-    #[test]
-    fn candidate_post_install_sources_prefers_agents_dir_first() {
-        let sources = candidate_post_install_sources("demo-skill");
-        assert_eq!(sources.len(), 1);
-        assert!(sources[0].to_string_lossy().contains("/.agents/skills/demo-skill"));
-    }
-
-    #[test]
-    fn bootstrap_skills_store_hydrates_from_existing_store_first() {
-        let root = temp_test_dir("bootstrap-existing");
-        let _ = fs::remove_dir_all(&root);
-        ensure_dir(&root).unwrap();
-
-        write_skill(&root, "fastapi");
-
-        let existing_state = vec![Skill {
-            id: "old-id".to_string(),
-            name: "legacy-skill".to_string(),
-            source_url: Some("https://example.com/legacy".to_string()),
-            enabled_agents: vec!["codex".to_string()],
-            last_sync: None,
-            last_update: None,
-        }];
-
-        let hydrated = bootstrap_skills_store(existing_state, root.to_string_lossy().to_string()).unwrap();
+#[tauri::command]
+pub(crate) fn list_trash(storage_path: String) -> Result<Vec<TrashEntry>, String> {
+    trash::list_trash(&storage_path)
+}
 
-        assert_eq!(hydrated.len(), 1);
-        assert_eq!(hydrated[0].name, "fastapi");
-        assert!(root.join("legacy-skill").exists() == false);
+#[tauri::command]
+pub(crate) fn restore_skill(
+    entry_id: String,
+    storage_path: String,
+) -> Result<RestoreReport, String> {
+    trash::restore_skill(&entry_id, &storage_path)
+}
 
-        let _ = fs::remove_dir_all(&root);
-    }
+#[tauri::command]
+pub(crate) fn delete_trash_entry(entry_id: String, storage_path: String) -> Result<(), String> {
+    trash::delete_trash_entry(&entry_id, &storage_path)
+}
 
-    #[test]
-    fn bootstrap_skills_store_seeds_when_store_is_empty() {
-        let root = temp_test_dir("bootstrap-empty");
-        let _ = fs::remove_dir_all(&root);
-        ensure_dir(&root).unwrap();
+#[tauri::command]
+pub(crate) fn empty_trash(storage_path: String) -> Result<usize, String> {
+    trash::empty_trash(&storage_path)
+}
 
-        let existing_state = vec![Skill {
-            id: "skill-1".to_string(),
-            name: "seeded-skill".to_string(),
-            source_url: None,
-            enabled_agents: vec![],
-            last_sync: None,
-            last_update: None,
-        }];
+#[tauri::command]
+pub(crate) fn purge_expired_trash(
+    retention_days: u32,
+    storage_path: String,
+) -> Result<Vec<String>, String> {
+    trash::purge_expired_trash(retention_days, &storage_path)
+}
 
-        let hydrated = bootstrap_skills_store(existing_state, root.to_string_lossy().to_string()).unwrap();
+#[tauri::command]
+pub(crate) fn reset_store(storage_path: String) -> Result<(), String> {
+    store::reset_store(&storage_path)
+}
 
-        assert_eq!(hydrated.len(), 1);
-        assert_eq!(hydrated[0].name, "seeded-skill");
-        assert!(root.join("seeded-skill").join("SKILL.md").exists());
+#[tauri::command]
+pub(crate) fn lint_skill(
+    skill_name: String,
+    storage_path: String,
+    fix: bool,
+) -> Result<LintReport, String> {
+    lint::lint_skill(&skill_name, &storage_path, fix)
+}
 
-        let _ = fs::remove_dir_all(&root);
-    }
+#[tauri::command]
+pub(crate) async fn lint_store(storage_path: String, fix: bool) -> Result<Vec<LintReport>, String> {
+    tauri::async_runtime::spawn_blocking(move || lint::lint_store(&storage_path, fix))
+        .await
+        .map_err(|e| format!("lint_store task join error: {e}"))?
 }
@@ -31,10 +31,29 @@ const App: React.FC = () => {
         }
 
         const storage = settings.storagePath;
+        const agents = getEffectiveAgents(useAgentStore.getState().agents);
         const currentSkills = useSkillStore.getState().skills;
-        const hydratedSkills = await bootstrapSkillsStore(currentSkills);
+        const { skills: hydratedSkills, recovery } = await bootstrapSkillsStore(currentSkills, agents);
         if (cancelled) return;
 
+        const settled = [...recovery.rolledBack, ...recovery.completed];
+        if (settled.length > 0) {
+          useSkillStore.getState().addLog({
+            action: 'sync',
+            skillId: '中断恢复',
+            status: 'success',
+            message: `已恢复上次中断的操作: ${settled.join('、')}`,
+          });
+        }
+        for (const failure of recovery.failed) {
+          useSkillStore.getState().addLog({
+            action: 'sync',
+            skillId: '中断恢复',
+            status: 'error',
+            message: `无法恢复中断的操作: ${failure}`,
+          });
+        }
+
         useSkillStore.getState().setSkills(hydratedSkills);
         const bootstrapSample = hydratedSkills.slice(0, 8).map((skill) => skill.name).join('、');
         console.info('[startup-detect] bootstrap', {
@@ -49,7 +68,6 @@ const App: React.FC = () => {
           message: `中心库加载完成: storage=${storage}; loaded=${hydratedSkills.length}${bootstrapSample ? `; sample=${bootstrapSample}` : ''}`,
         });
 
-        const agents = getEffectiveAgents(useAgentStore.getState().agents);
         const agentSummary = agents
           .map((agent) => `${agent.id}:${agent.currentPath}`)
           .join(' | ');
@@ -6,7 +6,8 @@ import { useUIStore } from '../stores/useUIStore';
 import { useSkillStore } from '../stores/useSkillStore';
 import { useAgentStore } from '../stores/useAgentStore';
 import { useSettingsStore } from '../stores/useSettingsStore';
-import { syncAllToManagerStoreWithProgress } from '../services/syncService';
+import { planSyncAllToManagerStore, syncAllToManagerStoreWithProgress } from '../services/syncService';
+import type { PlannedOperation } from '../types';
 
 interface SyncLog {
   id: string;
@@ -28,17 +29,21 @@ const SyncModal: React.FC = () => {
   const [activeLogs, setActiveLogs] = useState<SyncLog[]>([]);
   const [isFinished, setIsFinished] = useState(false);
   const [hasError, setHasError] = useState(false);
+  const [plan, setPlan] = useState<PlannedOperation[] | null>(null);
+  const [isConfirmed, setIsConfirmed] = useState(false);
   const scrollRef = useRef<HTMLDivElement>(null);
   const runIdRef = useRef(0);
 
   useEffect(() => {
     if (isSyncModalOpen) {
       const runId = ++runIdRef.current;
-      void startSync(runId);
+      void loadPlan(runId);
       return;
     }
 
     runIdRef.current++;
+    setPlan(null);
+    setIsConfirmed(false);
     setProgress(0);
     setActiveLogs([]);
     setIsFinished(false);
@@ -51,6 +56,22 @@ const SyncModal: React.FC = () => {
     }
   }, [activeLogs]);
 
+  const loadPlan = async (runId: number) => {
+    try {
+      const ops = await planSyncAllToManagerStore(agents);
+      if (runId === runIdRef.current) setPlan(ops);
+    } catch (e) {
+      // The preview is advisory; without it the user can still confirm the sync.
+      console.error(e);
+      if (runId === runIdRef.current) setPlan([]);
+    }
+  };
+
+  const confirmSync = () => {
+    setIsConfirmed(true);
+    void startSync(runIdRef.current);
+  };
+
   const startSync = async (runId: number) => {
     setProgress(0);
     setActiveLogs([]);
@@ -112,7 +133,7 @@ const SyncModal: React.FC = () => {
         <div className="p-6 border-b border-[#eaeaea] bg-[#fafafa]">
           <div className="flex items-center gap-3">
             <div className="p-2 rounded-lg bg-black text-white shrink-0">
-              <RefreshCw size={20} className={!isFinished ? 'animate-spin' : ''} />
+              <RefreshCw size={20} className={isConfirmed && !isFinished ? 'animate-spin' : ''} />
             </div>
             <div>
               <h3 className="text-[16px] font-bold text-black tracking-tight">资产汇总同步</h3>
@@ -130,66 +151,121 @@ const SyncModal: React.FC = () => {
           />
         </div>
 
-        <div
-          ref={scrollRef}
-          className="p-8 h-[200px] overflow-y-auto scroll-smooth flex flex-col gap-4 scrollbar-hide relative"
-        >
-          {activeLogs.slice(-4).map((log, index, array) => {
-            const isLast = index === array.length - 1;
-            const isSecondLast = index === array.length - 2;
-
-            return (
-              <div
-                key={log.id}
-                className={`flex items-center gap-3 transition-all duration-500 ease-out
-                  ${isLast ? 'opacity-100 translate-y-0' : isSecondLast ? 'opacity-40 -translate-y-1' : 'opacity-10 -translate-y-2 scale-95'}
-                `}
-              >
-                <div className="shrink-0">
-                  {log.status === 'success' ? (
-                    <CheckCircle2 size={16} className="text-green-500" />
-                  ) : log.status === 'error' ? (
-                    <XCircle size={16} className="text-red-500" />
-                  ) : (
-                    <Loader2 size={16} className="text-black animate-spin" />
-                  )}
-                </div>
-                <div className="flex items-center gap-2">
-                  {!isLast && <ChevronRight size={12} className="text-slate-300" />}
-                  <span
-                    className={`text-[13px] tracking-tight ${isLast ? 'text-black font-bold' : 'text-slate-500 font-medium'}`}
-                  >
-                    {log.label}
+        {!isConfirmed ? (
+          <div className="p-6 h-[200px] overflow-y-auto flex flex-col gap-2">
+            {plan === null ? (
+              <div className="flex flex-col items-center justify-center h-full text-slate-300 animate-pulse">
+                <Loader2 size={24} className="mb-2 animate-spin" />
+                <span className="text-[12px] font-medium">正在生成同步计划...</span>
+              </div>
+            ) : plan.length === 0 ? (
+              <div className="flex flex-col items-center justify-center h-full text-slate-400">
+                <CheckCircle2 size={24} className="mb-2" />
+                <span className="text-[12px] font-medium">中心库已是最新，无需写入</span>
+              </div>
+            ) : (
+              plan.map((op) => (
+                <div key={`${op.action}-${op.source ?? ''}-${op.target}`} className="flex flex-col">
+                  <span className="text-[13px] font-bold text-black tracking-tight">
+                    <span className="mono text-slate-400 mr-2">{op.action}</span>
+                    {op.skill}
                   </span>
+                  <span className="text-[11px] text-slate-500 font-medium">{op.reason}</span>
                 </div>
-              </div>
-            );
-          })}
+              ))
+            )}
+          </div>
+        ) : (
+          <div
+            ref={scrollRef}
+            className="p-8 h-[200px] overflow-y-auto scroll-smooth flex flex-col gap-4 scrollbar-hide relative"
+          >
+            {activeLogs.slice(-4).map((log, index, array) => {
+              const isLast = index === array.length - 1;
+              const isSecondLast = index === array.length - 2;
 
-          {activeLogs.length === 0 && (
-            <div className="flex flex-col items-center justify-center h-full text-slate-300 animate-pulse">
-              <RefreshCw size={24} className="mb-2" />
-              <span className="text-[12px] font-medium">准备同步任务...</span>
-            </div>
-          )}
-        </div>
+              return (
+                <div
+                  key={log.id}
+                  className={`flex items-center gap-3 transition-all duration-500 ease-out
+                    ${isLast ? 'opacity-100 translate-y-0' : isSecondLast ? 'opacity-40 -translate-y-1' : 'opacity-10 -translate-y-2 scale-95'}
+                  `}
+                >
+                  <div className="shrink-0">
+                    {log.status === 'success' ? (
+                      <CheckCircle2 size={16} className="text-green-500" />
+                    ) : log.status === 'error' ? (
+                      <XCircle size={16} className="text-red-500" />
+                    ) : (
+                      <Loader2 size={16} className="text-black animate-spin" />
+                    )}
+                  </div>
+                  <div className="flex items-center gap-2">
+                    {!isLast && <ChevronRight size={12} className="text-slate-300" />}
+                    <span
+                      className={`text-[13px] tracking-tight ${isLast ? 'text-black font-bold' : 'text-slate-500 font-medium'}`}
+                    >
+                      {log.label}
+                    </span>
+                  </div>
+                </div>
+              );
+            })}
+
+            {activeLogs.length === 0 && (
+              <div className="flex flex-col items-center justify-center h-full text-slate-300 animate-pulse">
+                <RefreshCw size={24} className="mb-2" />
+                <span className="text-[12px] font-medium">准备同步任务...</span>
+              </div>
+            )}
+          </div>
+        )}
 
         <div className="p-4 bg-[#fafafa] border-t border-[#eaeaea] flex justify-between items-center px-6">
           <div className="text-[12px] text-slate-400 font-bold mono">
-            {hasError ? 'SYNC_FAILED' : isFinished ? 'SYNC_COMPLETE' : `SYNCING_READY_${Math.round(progress)}%`}
+            {!isConfirmed
+              ? `PLAN_${plan?.length ?? 0}_OPS`
+              : hasError
+                ? 'SYNC_FAILED'
+                : isFinished
+                  ? 'SYNC_COMPLETE'
+                  : `SYNCING_READY_${Math.round(progress)}%`}
           </div>
-          <button
-            onClick={() => setSyncModalOpen(false)}
-            disabled={!isFinished}
-            className={`
-              px-6 py-2 rounded-lg text-[13px] font-bold transition-all
-              ${isFinished
-                ? 'bg-black text-white hover:bg-slate-800 shadow-lg shadow-black/5 active:scale-95'
-                : 'bg-slate-100 text-slate-400 cursor-not-allowed'}
-            `}
-          >
-            {isFinished ? '完成' : '请稍候...'}
-          </button>
+          {!isConfirmed ? (
+            <div className="flex gap-2">
+              <button
+                onClick={() => setSyncModalOpen(false)}
+                className="px-4 py-2 rounded-lg text-[13px] font-bold text-slate-500 hover:text-black transition-all"
+              >
+                取消
+              </button>
+              <button
+                onClick={confirmSync}
+                disabled={plan === null}
+                className={`
+                  px-6 py-2 rounded-lg text-[13px] font-bold transition-all
+                  ${plan !== null
+                    ? 'bg-black text-white hover:bg-slate-800 shadow-lg shadow-black/5 active:scale-95'
+                    : 'bg-slate-100 text-slate-400 cursor-not-allowed'}
+                `}
+              >
+                确认同步
+              </button>
+            </div>
+          ) : (
+            <button
+              onClick={() => setSyncModalOpen(false)}
+              disabled={!isFinished}
+              className={`
+                px-6 py-2 rounded-lg text-[13px] font-bold transition-all
+                ${isFinished
+                  ? 'bg-black text-white hover:bg-slate-800 shadow-lg shadow-black/5 active:scale-95'
+                  : 'bg-slate-100 text-slate-400 cursor-not-allowed'}
+              `}
+            >
+              {isFinished ? '完成' : '请稍候...'}
+            </button>
+          )}
         </div>
       </div>
     </div>
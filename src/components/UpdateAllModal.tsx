@@ -1,6 +1,8 @@
 import React, { useEffect, useRef, useState } from 'react';
 import { CheckCircle2, ChevronRight, Loader2, RefreshCw, XCircle } from 'lucide-react';
 
+import { checkUpdates, updateSkillsWithProgress } from '../services/skillService';
+import { useAgentStore } from '../stores/useAgentStore';
 import { useUIStore } from '../stores/useUIStore';
 import { useSkillStore } from '../stores/useSkillStore';
 import { useToastStore } from '../stores/useToastStore';
@@ -18,11 +20,16 @@ const isSystemInstalledSkill = (skill: Skill) => {
   return installSource === 'platform' && Boolean(skill.sourceUrl);
 };
 
+// Skills installed from git or a zip can be checked and updated in place;
+// the rest can only be re-run through npx.
+const hasRecordedSource = (skill: Skill) => skill.sourceKind === 'git' || skill.sourceKind === 'zip';
+
 const UpdateAllModal: React.FC = () => {
   const isOpen = useUIStore((state) => state.isUpdateAllModalOpen);
   const setOpen = useUIStore((state) => state.setUpdateAllModalOpen);
 
   const reInstallSkill = useSkillStore((state) => state.reInstallSkill);
+  const updateSkill = useSkillStore((state) => state.updateSkill);
   const addToast = useToastStore((state) => state.addToast);
 
   const [progress, setProgress] = useState(0);
@@ -93,14 +100,71 @@ const UpdateAllModal: React.FC = () => {
 
     let anyError = false;
     const total = Math.max(1, targets.length);
+    const sourced = targets.filter(hasRecordedSource);
+    const legacy = targets.filter((skill) => !hasRecordedSource(skill));
+    const sourcedShare = sourced.length / total;
+
+    if (sourced.length > 0) {
+      upsertLog({ id: 'check', label: '正在检查上游版本...', status: 'loading' });
+      try {
+        const report = await checkUpdates();
+        if (runId !== runIdRef.current) return;
+        for (const failure of report.failed) {
+          anyError = true;
+          upsertLog({
+            id: `check-${failure.id}`,
+            label: `检查失败: ${failure.name}（${failure.error}）`,
+            status: 'error',
+          });
+        }
+        upsertLog({
+          id: 'check',
+          label: `发现 ${report.outdated.length} 个可更新技能`,
+          status: 'success',
+        });
+
+        if (report.outdated.length > 0) {
+          const updated = await updateSkillsWithProgress(
+            report.outdated.map((update) => update.id),
+            useAgentStore.getState().agents,
+            (log) => {
+              if (runId !== runIdRef.current || !log.id.startsWith('update-')) return;
+              upsertLog({ id: log.id, label: log.label, status: log.status });
+              setProgress(Math.max(0, Math.min(100, log.progress * sourcedShare)));
+            },
+          );
+          if (runId !== runIdRef.current) return;
+          for (const next of updated) {
+            const existing = useSkillStore.getState().skills.find((s) => s.name === next.name);
+            if (!existing) continue;
+            updateSkill(existing.id, {
+              sourceCommit: next.sourceCommit,
+              sourceChecksum: next.sourceChecksum,
+              installedAt: next.installedAt,
+              lastSync: next.lastSync ?? existing.lastSync,
+              lastUpdate: next.lastUpdate ?? existing.lastUpdate,
+            });
+          }
+        }
+      } catch (e) {
+        anyError = true;
+        if (runId !== runIdRef.current) return;
+        upsertLog({
+          id: 'check',
+          label: `更新失败（${e instanceof Error ? e.message : String(e)}）`,
+          status: 'error',
+        });
+      }
+      setProgress(Math.max(0, Math.min(100, sourcedShare * 100)));
+    }
 
-    for (let idx = 0; idx < targets.length; idx++) {
-      const skill = targets[idx]!;
+    for (let idx = 0; idx < legacy.length; idx++) {
+      const skill = legacy[idx]!;
       if (runId !== runIdRef.current) return;
 
       const id = `update-${skill.id}`;
       upsertLog({ id, label: `正在更新技能: ${skill.name}`, status: 'loading' });
-      setProgress(Math.max(0, Math.min(100, (idx / total) * 100)));
+      setProgress(Math.max(0, Math.min(100, ((sourced.length + idx) / total) * 100)));
 
       try {
         await reInstallSkill(skill.id);
@@ -116,7 +180,7 @@ const UpdateAllModal: React.FC = () => {
         });
       } finally {
         if (runId !== runIdRef.current) return;
-        setProgress(Math.max(0, Math.min(100, ((idx + 1) / total) * 100)));
+        setProgress(Math.max(0, Math.min(100, ((sourced.length + idx + 1) / total) * 100)));
       }
     }
 
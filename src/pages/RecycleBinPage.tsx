@@ -3,6 +3,7 @@ import { Trash2, RotateCcw, AlertTriangle, Calendar, Clock } from 'lucide-react'
 
 import { useSkillStore } from '../stores/useSkillStore';
 import { useSettingsStore } from '../stores/useSettingsStore';
+import { useToastStore } from '../stores/useToastStore';
 import { addDaysByRetentionPolicy, formatLocalDateTimeToMinute, isValidDate } from '../lib/datetime';
 import {
   AlertDialog,
@@ -22,6 +23,16 @@ const RecycleBinPage: React.FC = () => {
   const permanentlyDeleteSkill = useSkillStore((state) => state.permanentlyDeleteSkill);
   const emptyRecycleBin = useSkillStore((state) => state.emptyRecycleBin);
   const retentionDays = useSettingsStore((state) => state.recycleBinRetentionDays);
+  const addToast = useToastStore((state) => state.addToast);
+
+  const handleRestore = async (skillId: string, name: string) => {
+    try {
+      await restoreSkill(skillId);
+      addToast(`"${name}" 已还原`, 'success');
+    } catch (e) {
+      addToast(`还原失败: ${e instanceof Error ? e.message : String(e)}`, 'error');
+    }
+  };
 
   return (
     <div className="space-y-10 animate-in fade-in duration-500">
@@ -110,7 +121,7 @@ const RecycleBinPage: React.FC = () => {
 
               <div className="flex gap-2 items-center">
                 <button
-                  onClick={() => restoreSkill(skill.id)}
+                  onClick={() => void handleRestore(skill.id, skill.name)}
                   className="flex-1 flex items-center justify-center gap-2 py-2 bg-slate-50 hover:bg-slate-100 border border-[#eaeaea] rounded-lg text-[12px] font-bold text-slate-700 transition-colors"
                 >
                   <RotateCcw size={14} />
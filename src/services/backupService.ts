@@ -0,0 +1,15 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { AgentInfo, BackupPreview, BackupSummary, ImportReport, ImportStrategy } from '../types';
+import { storagePath } from './storagePath';
+
+export const exportBackup = async (path: string, agents: AgentInfo[]): Promise<BackupSummary> => {
+  return await invoke<BackupSummary>('export_backup', { path, agents, storagePath: storagePath() });
+};
+
+export const previewBackup = async (path: string): Promise<BackupPreview> => {
+  return await invoke<BackupPreview>('preview_backup', { path, storagePath: storagePath() });
+};
+
+export const importBackup = async (path: string, strategy: ImportStrategy): Promise<ImportReport> => {
+  return await invoke<ImportReport>('import_backup', { path, strategy, storagePath: storagePath() });
+};
@@ -1,4 +1,5 @@
 import { invoke } from '@tauri-apps/api/core';
+import type { SkillMetadata } from '../types';
 import { storagePath } from './storagePath';
 
 export const getSkillDescriptionFromMd = async (skillName: string): Promise<string | null> => {
@@ -10,3 +11,10 @@ export const getSkillDescriptionFromMd = async (skillName: string): Promise<stri
   return typeof res === 'string' && res.trim() ? res : null;
 };
 
+
+export const getSkillMetadata = async (skillName: string): Promise<SkillMetadata | null> => {
+  return await invoke<SkillMetadata | null>('get_skill_metadata', {
+    skillName,
+    storagePath: storagePath(),
+  });
+};
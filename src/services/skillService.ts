@@ -1,15 +1,111 @@
 import { invoke } from '@tauri-apps/api/core';
-import type { AgentInfo, Skill } from '../types';
+import { listen } from '@tauri-apps/api/event';
+import type {
+  AgentInfo,
+  InstallProgressLog,
+  LintReport,
+  LockExport,
+  LockInstallReport,
+  PlannedOperation,
+  RestoreReport,
+  Skill,
+  SourceInspection,
+  StoreBootstrap,
+  TrashEntry,
+  UpdateCheckReport,
+} from '../types';
 import { storagePath } from './storagePath';
 
-export const bootstrapSkillsStore = async (skills: Skill[]): Promise<Skill[]> => {
-  return await invoke<Skill[]>('bootstrap_skills_store', { skills, storagePath: storagePath() });
+export const bootstrapSkillsStore = async (
+  skills: Skill[],
+  agents: AgentInfo[],
+): Promise<StoreBootstrap> => {
+  return await invoke<StoreBootstrap>('bootstrap_skills_store', {
+    skills,
+    agents,
+    storagePath: storagePath(),
+  });
+};
+
+export const listSkills = async (): Promise<Skill[]> => {
+  return await invoke<Skill[]>('list_skills', { storagePath: storagePath() });
+};
+
+export const getSkill = async (skillName: string): Promise<Skill | null> => {
+  return await invoke<Skill | null>('get_skill', { skillName, storagePath: storagePath() });
 };
 
 export const installSkill = async (repoUrl: string): Promise<Skill> => {
   return await invoke<Skill>('install_skill', { repoUrl, storagePath: storagePath() });
 };
 
+export const installSkillWithProgress = async (
+  repoUrl: string,
+  onProgress: (log: InstallProgressLog) => void,
+): Promise<Skill> => {
+  const unlisten = await listen<InstallProgressLog>('install_skill:progress', (event) =>
+    onProgress(event.payload),
+  );
+
+  try {
+    return await invoke<Skill>('install_skill_with_progress', { repoUrl, storagePath: storagePath() });
+  } finally {
+    unlisten();
+  }
+};
+
+export const inspectSource = async (url: string): Promise<SourceInspection> => {
+  return await invoke<SourceInspection>('inspect_source', { url, storagePath: storagePath() });
+};
+
+export const installFromSource = async (url: string, selected: string[]): Promise<Skill[]> => {
+  return await invoke<Skill[]>('install_from_source', { url, selected, storagePath: storagePath() });
+};
+
+export const checkUpdates = async (): Promise<UpdateCheckReport> => {
+  return await invoke<UpdateCheckReport>('check_updates', { storagePath: storagePath() });
+};
+
+export const updateSkillsWithProgress = async (
+  ids: string[],
+  agents: AgentInfo[],
+  onProgress: (log: InstallProgressLog) => void,
+): Promise<Skill[]> => {
+  const unlisten = await listen<InstallProgressLog>('update_skills:progress', (event) =>
+    onProgress(event.payload),
+  );
+
+  try {
+    return await invoke<Skill[]>('update_skills', { ids, agents, storagePath: storagePath() });
+  } finally {
+    unlisten();
+  }
+};
+
+export const exportLock = async (path: string): Promise<LockExport> => {
+  return await invoke<LockExport>('export_lock', { path, storagePath: storagePath() });
+};
+
+export const installFromLock = async (
+  path: string,
+  agents: AgentInfo[],
+  onProgress: (log: InstallProgressLog) => void,
+): Promise<LockInstallReport> => {
+  const unlisten = await listen<InstallProgressLog>('install_from_lock:progress', (event) =>
+    onProgress(event.payload),
+  );
+
+  try {
+    return await invoke<LockInstallReport>('install_from_lock', {
+      path,
+      agents,
+      storagePath: storagePath(),
+    });
+  } finally {
+    unlisten();
+  }
+};
+
 export const installSkillCli = async (repoUrl: string, skillName: string): Promise<Skill> => {
   return await invoke<Skill>('install_skill_cli', { repoUrl, skillName, storagePath: storagePath() });
 };
@@ -30,8 +126,8 @@ export const reinstallSkill = async (
   });
 };
 
-export const uninstallSkill = async (skill: Skill, agents: AgentInfo[]) => {
-  await invoke('uninstall_skill', {
+export const uninstallSkill = async (skill: Skill, agents: AgentInfo[]): Promise<TrashEntry> => {
+  return await invoke<TrashEntry>('uninstall_skill', {
     skillId: skill.id,
     skillName: skill.name,
     agents,
@@ -39,6 +135,45 @@ export const uninstallSkill = async (skill: Skill, agents: AgentInfo[]) => {
   });
 };
 
+export const planUninstallSkill = async (skill: Skill, agents: AgentInfo[]): Promise<PlannedOperation[]> => {
+  return await invoke<PlannedOperation[]>('plan_uninstall_skill', {
+    skillName: skill.name,
+    agents,
+    storagePath: storagePath(),
+  });
+};
+
+export const listTrash = async (): Promise<TrashEntry[]> => {
+  return await invoke<TrashEntry[]>('list_trash', { storagePath: storagePath() });
+};
+
+export const restoreTrashedSkill = async (entryId: string): Promise<RestoreReport> => {
+  return await invoke<RestoreReport>('restore_skill', { entryId, storagePath: storagePath() });
+};
+
+export const deleteTrashEntry = async (entryId: string) => {
+  await invoke('delete_trash_entry', { entryId, storagePath: storagePath() });
+};
+
+export const emptyTrash = async (): Promise<number> => {
+  return await invoke<number>('empty_trash', { storagePath: storagePath() });
+};
+
+export const purgeExpiredTrash = async (retentionDays: number): Promise<string[]> => {
+  return await invoke<string[]>('purge_expired_trash', {
+    retentionDays,
+    storagePath: storagePath(),
+  });
+};
+
 export const resetStore = async () => {
   await invoke('reset_store', { storagePath: storagePath() });
 };
+
+export const lintSkill = async (skillName: string, fix = false): Promise<LintReport> => {
+  return await invoke<LintReport>('lint_skill', { skillName, storagePath: storagePath(), fix });
+};
+
+export const lintStore = async (fix = false): Promise<LintReport[]> => {
+  return await invoke<LintReport[]>('lint_store', { storagePath: storagePath(), fix });
+};
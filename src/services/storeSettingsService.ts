@@ -0,0 +1,11 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { StoreSettings } from '../types';
+import { storagePath } from './storagePath';
+
+export const getStoreSettings = async (): Promise<StoreSettings> => {
+  return await invoke<StoreSettings>('get_store_settings', { storagePath: storagePath() });
+};
+
+export const setStoreSettings = async (settings: StoreSettings) => {
+  await invoke('set_store_settings', { storagePath: storagePath(), settings });
+};
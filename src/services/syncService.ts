@@ -1,10 +1,23 @@
 import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
-import type { AgentInfo, Skill, StartupDetectedSkill } from '../types';
+import type {
+  AgentInfo,
+  DiffAgainst,
+  DirDiff,
+  DistributionReport,
+  PlannedOperation,
+  PullReport,
+  Skill,
+  SkillDistributionStatus,
+  StartupDetectedSkill,
+} from '../types';
 import { storagePath } from './storagePath';
 
-export const syncSkillDistribution = async (skill: Skill, agents: AgentInfo[]) => {
-  await invoke('sync_skill_distribution', {
+export const syncSkillDistribution = async (
+  skill: Skill,
+  agents: AgentInfo[],
+): Promise<DistributionReport> => {
+  return await invoke<DistributionReport>('sync_skill_distribution', {
     skillId: skill.id,
     skillName: skill.name,
     enabledAgents: skill.enabledAgents,
@@ -13,8 +26,15 @@ export const syncSkillDistribution = async (skill: Skill, agents: AgentInfo[]) =
   });
 };
 
-export const syncAllSkillsDistribution = async (skills: Skill[], agents: AgentInfo[]) => {
-  await invoke('sync_all_skills_distribution', { skills, agents, storagePath: storagePath() });
+export const syncAllSkillsDistribution = async (
+  skills: Skill[],
+  agents: AgentInfo[],
+): Promise<DistributionReport> => {
+  return await invoke<DistributionReport>('sync_all_skills_distribution', {
+    skills,
+    agents,
+    storagePath: storagePath(),
+  });
 };
 
 export type SyncAllSkillsDistributionProgressLog = {
@@ -28,19 +48,69 @@ export const syncAllSkillsDistributionWithProgress = async (
   skills: Skill[],
   agents: AgentInfo[],
   onProgress: (log: SyncAllSkillsDistributionProgressLog) => void,
-): Promise<void> => {
+): Promise<DistributionReport> => {
   const unlisten = await listen<SyncAllSkillsDistributionProgressLog>(
     'sync_all_skills_distribution:progress',
     (event) => onProgress(event.payload),
   );
 
   try {
-    await invoke('sync_all_skills_distribution_with_progress', { skills, agents, storagePath: storagePath() });
+    return await invoke<DistributionReport>('sync_all_skills_distribution_with_progress', {
+      skills,
+      agents,
+      storagePath: storagePath(),
+    });
   } finally {
     unlisten();
   }
 };
 
+export const getDistributionStatus = async (agents: AgentInfo[]): Promise<SkillDistributionStatus[]> => {
+  return await invoke<SkillDistributionStatus[]>('get_distribution_status', {
+    agents,
+    storagePath: storagePath(),
+  });
+};
+
+export const planSyncAllSkillsDistribution = async (
+  skills: Skill[],
+  agents: AgentInfo[],
+): Promise<PlannedOperation[]> => {
+  return await invoke<PlannedOperation[]>('plan_sync_all_skills_distribution', {
+    skills,
+    agents,
+    storagePath: storagePath(),
+  });
+};
+
+export const planSyncAllToManagerStore = async (agents: AgentInfo[]): Promise<PlannedOperation[]> => {
+  return await invoke<PlannedOperation[]>('plan_sync_all_to_manager_store', {
+    agents,
+    storagePath: storagePath(),
+  });
+};
+
+export const diffSkill = async (
+  skillName: string,
+  against: DiffAgainst,
+  agents: AgentInfo[],
+): Promise<DirDiff> => {
+  return await invoke<DirDiff>('diff_skill', { skillName, against, agents, storagePath: storagePath() });
+};
+
+export const pullSkillFromAgent = async (
+  skillName: string,
+  agentId: string,
+  agents: AgentInfo[],
+): Promise<PullReport> => {
+  return await invoke<PullReport>('pull_skill_from_agent', {
+    skillName,
+    agentId,
+    agents,
+    storagePath: storagePath(),
+  });
+};
+
 export const syncAllToManagerStore = async (agents: AgentInfo[]): Promise<Skill[]> => {
   return await invoke<Skill[]>('sync_all_to_manager_store', { agents, storagePath: storagePath() });
 };
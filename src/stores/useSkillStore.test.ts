@@ -1,6 +1,13 @@
 import { beforeEach, describe, expect, it, vi, afterEach } from 'vitest';
 
-import { reinstallSkill, uninstallSkill } from '../services/skillService';
+import {
+  deleteTrashEntry,
+  emptyTrash,
+  purgeExpiredTrash,
+  reinstallSkill,
+  restoreTrashedSkill,
+  uninstallSkill,
+} from '../services/skillService';
 import {
   syncAllSkillsDistribution,
   syncAllSkillsDistributionWithProgress,
@@ -18,7 +25,24 @@ vi.mock('../services/syncService', () => ({
 }));
 
 vi.mock('../services/skillService', () => ({
-  uninstallSkill: vi.fn(() => Promise.resolve()),
+  uninstallSkill: vi.fn(() =>
+    Promise.resolve({
+      id: 'entry-1',
+      name: 'Skill One',
+      deletedAt: '2026-01-24T00:00:00Z',
+      hasStoreCopy: true,
+      copies: [],
+    }),
+  ),
+  restoreTrashedSkill: vi.fn(() =>
+    Promise.resolve({
+      skill: { id: 's1', name: 'Skill One', enabledAgents: [AgentId.CODEX] },
+      skipped: [],
+    }),
+  ),
+  deleteTrashEntry: vi.fn(() => Promise.resolve()),
+  emptyTrash: vi.fn(() => Promise.resolve(0)),
+  purgeExpiredTrash: vi.fn(() => Promise.resolve([])),
   reinstallSkill: vi.fn(() =>
     Promise.resolve({
       id: 's1',
@@ -56,6 +80,10 @@ describe('useSkillStore (recycle bin)', () => {
     vi.mocked(syncAllSkillsDistributionWithProgress).mockClear();
     vi.mocked(syncSkillDistribution).mockClear();
     vi.mocked(uninstallSkill).mockClear();
+    vi.mocked(restoreTrashedSkill).mockClear();
+    vi.mocked(deleteTrashEntry).mockClear();
+    vi.mocked(emptyTrash).mockClear();
+    vi.mocked(purgeExpiredTrash).mockClear();
     vi.mocked(reinstallSkill).mockClear();
   });
 
@@ -63,7 +91,7 @@ describe('useSkillStore (recycle bin)', () => {
     vi.useRealTimers();
   });
 
-  it('removeSkill moves skill to recycleBin and removes from agents via sync(empty)', () => {
+  it('removeSkill moves skill to recycleBin and uninstalls it into the trash', async () => {
     const skill: Skill = {
       id: 's1',
       name: 'Skill One',
@@ -79,30 +107,72 @@ describe('useSkillStore (recycle bin)', () => {
     expect(state.recycleBin).toHaveLength(1);
     expect(state.recycleBin[0]).toMatchObject({ id: 's1', name: 'Skill One' });
     expect(state.recycleBin[0].deletedAt).toBeTypeOf('string');
-
-    expect(vi.mocked(syncSkillDistribution)).toHaveBeenCalledTimes(1);
-    const [syncedSkill, syncedAgents] = vi.mocked(syncSkillDistribution).mock.calls[0];
-    expect(syncedSkill).toMatchObject({ id: 's1', name: 'Skill One', enabledAgents: [] });
-    expect(syncedAgents).toEqual(TEST_AGENTS);
+    expect(vi.mocked(uninstallSkill)).toHaveBeenCalledWith(skill, TEST_AGENTS);
+
+    await vi.waitFor(() =>
+      expect(useSkillStore.getState().recycleBin[0]).toMatchObject({
+        trashEntryId: 'entry-1',
+        deletedAt: '2026-01-24T00:00:00Z',
+      }),
+    );
   });
 
-  it('restoreSkill moves skill back to skills and re-syncs distribution', () => {
+  it('restoreSkill restores the trash entry and moves skill back to skills', async () => {
     const skillInTrash: Skill = {
       id: 's1',
       name: 'Skill One',
       enabledAgents: [AgentId.CODEX],
       deletedAt: '2026-01-23T00:00:00Z',
+      trashEntryId: 'entry-1',
     };
     useSkillStore.setState({ skills: [], recycleBin: [skillInTrash] });
 
-    useSkillStore.getState().restoreSkill('s1');
+    await useSkillStore.getState().restoreSkill('s1');
 
     const state = useSkillStore.getState();
+    expect(vi.mocked(restoreTrashedSkill)).toHaveBeenCalledWith('entry-1');
+    expect(vi.mocked(syncSkillDistribution)).not.toHaveBeenCalled();
     expect(state.recycleBin).toHaveLength(0);
     expect(state.skills).toHaveLength(1);
     expect(state.skills[0]).toMatchObject({ id: 's1', name: 'Skill One', enabledAgents: [AgentId.CODEX] });
     expect('deletedAt' in state.skills[0]).toBe(false);
+    expect('trashEntryId' in state.skills[0]).toBe(false);
+  });
 
+  it('restoreSkill keeps the skill in recycleBin when the restore fails', async () => {
+    vi.mocked(restoreTrashedSkill).mockRejectedValueOnce('Skill already exists in store: Skill One');
+    const skillInTrash: Skill = {
+      id: 's1',
+      name: 'Skill One',
+      enabledAgents: [],
+      deletedAt: '2026-01-23T00:00:00Z',
+      trashEntryId: 'entry-1',
+    };
+    useSkillStore.setState({ skills: [], recycleBin: [skillInTrash] });
+
+    await expect(useSkillStore.getState().restoreSkill('s1')).rejects.toBe(
+      'Skill already exists in store: Skill One',
+    );
+
+    expect(useSkillStore.getState().recycleBin).toEqual([skillInTrash]);
+    expect(useSkillStore.getState().skills).toHaveLength(0);
+  });
+
+  it('restoreSkill re-syncs distribution for entries that never reached the trash', async () => {
+    const skillInTrash: Skill = {
+      id: 's1',
+      name: 'Skill One',
+      enabledAgents: [AgentId.CODEX],
+      deletedAt: '2026-01-23T00:00:00Z',
+    };
+    useSkillStore.setState({ skills: [], recycleBin: [skillInTrash] });
+
+    await useSkillStore.getState().restoreSkill('s1');
+
+    const state = useSkillStore.getState();
+    expect(state.recycleBin).toHaveLength(0);
+    expect(state.skills).toHaveLength(1);
+    expect(vi.mocked(restoreTrashedSkill)).not.toHaveBeenCalled();
     expect(vi.mocked(syncSkillDistribution)).toHaveBeenCalledTimes(1);
     const [syncedSkill, syncedAgents] = vi.mocked(syncSkillDistribution).mock.calls[0];
     expect(syncedSkill).toMatchObject({ id: 's1', name: 'Skill One', enabledAgents: [AgentId.CODEX] });
@@ -110,36 +180,58 @@ describe('useSkillStore (recycle bin)', () => {
     expect(syncedSkill).not.toHaveProperty('deletedAt');
   });
 
-  it('permanentlyDeleteSkill uninstalls and removes from recycleBin', () => {
+  it('permanentlyDeleteSkill deletes the trash entry and removes from recycleBin', async () => {
     const skillInTrash: Skill = {
       id: 's1',
       name: 'Skill One',
       enabledAgents: [],
       deletedAt: '2026-01-23T00:00:00Z',
+      trashEntryId: 'entry-1',
     };
     useSkillStore.setState({ recycleBin: [skillInTrash] });
 
     useSkillStore.getState().permanentlyDeleteSkill('s1');
 
     expect(useSkillStore.getState().recycleBin).toHaveLength(0);
-    expect(vi.mocked(uninstallSkill)).toHaveBeenCalledTimes(1);
+    await vi.waitFor(() => expect(vi.mocked(deleteTrashEntry)).toHaveBeenCalledWith('entry-1'));
+    expect(vi.mocked(uninstallSkill)).not.toHaveBeenCalled();
+  });
+
+  it('permanentlyDeleteSkill trashes entries that never reached the trash before deleting them', async () => {
+    const skillInTrash: Skill = {
+      id: 's1',
+      name: 'Skill One',
+      enabledAgents: [],
+      deletedAt: '2026-01-23T00:00:00Z',
+    };
+    useSkillStore.setState({ recycleBin: [skillInTrash] });
+
+    useSkillStore.getState().permanentlyDeleteSkill('s1');
+
     expect(vi.mocked(uninstallSkill)).toHaveBeenCalledWith(skillInTrash, TEST_AGENTS);
+    await vi.waitFor(() => expect(vi.mocked(deleteTrashEntry)).toHaveBeenCalledWith('entry-1'));
   });
 
-  it('emptyRecycleBin uninstalls all and clears recycleBin', () => {
+  it('emptyRecycleBin empties the trash and clears recycleBin', async () => {
     const a: Skill = { id: 'a', name: 'A', enabledAgents: [], deletedAt: '2026-01-23T00:00:00Z' };
-    const b: Skill = { id: 'b', name: 'B', enabledAgents: [], deletedAt: '2026-01-23T00:00:00Z' };
+    const b: Skill = {
+      id: 'b',
+      name: 'B',
+      enabledAgents: [],
+      deletedAt: '2026-01-23T00:00:00Z',
+      trashEntryId: 'entry-b',
+    };
     useSkillStore.setState({ recycleBin: [a, b] });
 
     useSkillStore.getState().emptyRecycleBin();
 
     expect(useSkillStore.getState().recycleBin).toHaveLength(0);
-    expect(vi.mocked(uninstallSkill)).toHaveBeenCalledTimes(2);
-    expect(vi.mocked(uninstallSkill)).toHaveBeenNthCalledWith(1, a, TEST_AGENTS);
-    expect(vi.mocked(uninstallSkill)).toHaveBeenNthCalledWith(2, b, TEST_AGENTS);
+    expect(vi.mocked(uninstallSkill)).toHaveBeenCalledTimes(1);
+    expect(vi.mocked(uninstallSkill)).toHaveBeenCalledWith(a, TEST_AGENTS);
+    await vi.waitFor(() => expect(vi.mocked(emptyTrash)).toHaveBeenCalledTimes(1));
   });
 
-  it('cleanExpiredTrash removes expired entries and uninstalls them', () => {
+  it('cleanExpiredTrash removes expired entries and purges the trash', async () => {
     vi.useFakeTimers();
     vi.setSystemTime(new Date('2026-01-24T00:00:00Z'));
     useSettingsStore.setState({ recycleBinRetentionDays: 1 });
@@ -155,6 +247,7 @@ describe('useSkillStore (recycle bin)', () => {
       name: 'Kept',
       enabledAgents: [],
       deletedAt: '2026-01-23T12:00:00Z',
+      trashEntryId: 'entry-kept',
     };
     useSkillStore.setState({ recycleBin: [expired, kept] });
 
@@ -163,6 +256,7 @@ describe('useSkillStore (recycle bin)', () => {
     expect(useSkillStore.getState().recycleBin).toEqual([kept]);
     expect(vi.mocked(uninstallSkill)).toHaveBeenCalledTimes(1);
     expect(vi.mocked(uninstallSkill)).toHaveBeenCalledWith(expired, TEST_AGENTS);
+    await vi.waitFor(() => expect(vi.mocked(purgeExpiredTrash)).toHaveBeenCalledWith(1));
   });
 });
 
@@ -2,7 +2,14 @@
 import { create } from 'zustand';
 import { persist } from 'zustand/middleware';
 import { Skill, AgentId, OperationLog } from '../types';
-import { reinstallSkill, uninstallSkill } from '../services/skillService';
+import {
+  deleteTrashEntry,
+  emptyTrash,
+  purgeExpiredTrash,
+  reinstallSkill,
+  restoreTrashedSkill,
+  uninstallSkill,
+} from '../services/skillService';
 import {
   type SyncAllSkillsDistributionProgressLog,
   syncAllSkillsDistribution,
@@ -20,7 +27,7 @@ interface SkillState {
   mergeSkills: (skills: Skill[]) => void;
   addSkill: (skill: Skill) => void;
   removeSkill: (skillId: string) => void;
-  restoreSkill: (skillId: string) => void;
+  restoreSkill: (skillId: string) => Promise<void>;
   permanentlyDeleteSkill: (skillId: string) => void;
   emptyRecycleBin: () => void;
   cleanExpiredTrash: () => void;
@@ -44,6 +51,14 @@ const REMOVED_DEMO_SKILL_NAMES = new Set([
 ]);
 const REMOVED_DEMO_SKILL_IDS = new Set(['1', '2', '3']);
 
+// Recycle bin entries from before the on-disk trash never left the store; move
+// them into the trash first so there is an entry to delete.
+const deleteTrashed = async (skill: Skill) => {
+  const entryId = skill.trashEntryId
+    ?? (await uninstallSkill(skill, useAgentStore.getState().agents)).id;
+  await deleteTrashEntry(entryId);
+};
+
 export const useSkillStore = create<SkillState>()(
   persist(
     (set, get) => ({
@@ -101,48 +116,67 @@ export const useSkillStore = create<SkillState>()(
             ],
           };
         }),
-      removeSkill: (skillId) => set((state) => {
-        const skill = state.skills.find((s) => s.id === skillId);
-        if (!skill) return state;
-
-        void syncSkillDistribution(
-          { ...skill, enabledAgents: [] },
-          useAgentStore.getState().agents,
-        ).catch(console.error);
+      removeSkill: (skillId) => {
+        const skill = get().skills.find((s) => s.id === skillId);
+        if (!skill) return;
 
-        return {
+        set((state) => ({
           skills: state.skills.filter((s) => s.id !== skillId),
           recycleBin: [...state.recycleBin, { ...skill, deletedAt: new Date().toISOString() }],
-        };
-      }),
-      restoreSkill: (skillId) => set((state) => {
-        const skill = state.recycleBin.find((s) => s.id === skillId);
-        if (!skill) return state;
+        }));
+
+        // The store copy and every agent copy go to the on-disk trash; the
+        // entry id is what restoring or deleting it later refers to.
+        void uninstallSkill(skill, useAgentStore.getState().agents)
+          .then((entry) =>
+            set((state) => ({
+              recycleBin: state.recycleBin.map((s) =>
+                s.id === skillId ? { ...s, deletedAt: entry.deletedAt, trashEntryId: entry.id } : s,
+              ),
+            })),
+          )
+          .catch(console.error);
+      },
+      restoreSkill: async (skillId) => {
+        const skill = get().recycleBin.find((s) => s.id === skillId);
+        if (!skill) return;
 
-        const restored: Skill = { ...skill };
+        let restored: Skill = { ...skill };
         delete restored.deletedAt;
+        delete restored.trashEntryId;
 
-        void syncSkillDistribution(restored, useAgentStore.getState().agents).catch(console.error);
+        if (skill.trashEntryId) {
+          const report = await restoreTrashedSkill(skill.trashEntryId);
+          restored = { ...restored, ...report.skill };
+          for (const copy of report.skipped) {
+            get().addLog({
+              action: 'sync',
+              skillId: skill.name,
+              status: 'error',
+              message: `${copy.original} 已被占用，${copy.agentId} 的副本仍留在垃圾箱中`,
+            });
+          }
+        } else {
+          void syncSkillDistribution(restored, useAgentStore.getState().agents).catch(console.error);
+        }
 
-        return {
+        set((state) => ({
           recycleBin: state.recycleBin.filter((s) => s.id !== skillId),
           skills: [...state.skills, restored],
-        };
-      }),
+        }));
+      },
       permanentlyDeleteSkill: (skillId) => {
         const skill = get().recycleBin.find((s) => s.id === skillId);
         if (skill) {
-          void uninstallSkill(skill, useAgentStore.getState().agents).catch(console.error);
+          void deleteTrashed(skill).catch(console.error);
         }
         set((state) => ({ recycleBin: state.recycleBin.filter((s) => s.id !== skillId) }));
       },
       emptyRecycleBin: () => {
-        const recycleBin = get().recycleBin;
-        if (recycleBin.length > 0) {
-          for (const skill of recycleBin) {
-            void uninstallSkill(skill, useAgentStore.getState().agents).catch(console.error);
-          }
-        }
+        const legacy = get().recycleBin.filter((skill) => !skill.trashEntryId);
+        void Promise.all(legacy.map(deleteTrashed))
+          .then(() => emptyTrash())
+          .catch(console.error);
         set({ recycleBin: [] });
       },
       cleanExpiredTrash: () => {
@@ -160,11 +194,12 @@ export const useSkillStore = create<SkillState>()(
           return !isExpired;
         });
 
-        if (expired.length > 0) {
-          for (const skill of expired) {
-            void uninstallSkill(skill, useAgentStore.getState().agents).catch(console.error);
-          }
-        }
+        // The purge drops expired entries from the on-disk trash under the same
+        // retention; older entries are deleted one by one.
+        const legacy = expired.filter((skill) => !skill.trashEntryId);
+        void Promise.all(legacy.map(deleteTrashed))
+          .then(() => purgeExpiredTrash(retentionDays))
+          .catch(console.error);
 
         set({ recycleBin });
       },
@@ -21,6 +21,82 @@ export enum AgentId {
   WINDSURF = 'windsurf',
 }
 
+export type CopyStrategy = 'copy' | 'hardlink' | 'reflink';
+
+export interface StoreSettings {
+  copyStrategy: CopyStrategy;
+  syncConcurrency?: number;
+}
+
+export interface CopyReport {
+  reflinked: number;
+  hardlinked: number;
+  copied: number;
+}
+
+export type DistributionState =
+  | 'in_sync'
+  | 'modified_locally'
+  | 'missing'
+  | 'present_but_not_enabled'
+  | 'stale';
+
+export interface AgentDistributionStatus {
+  agentId: string;
+  state: DistributionState;
+  roots: { path: string; state: DistributionState }[];
+}
+
+export interface SkillDistributionStatus {
+  skill: string;
+  agents: AgentDistributionStatus[];
+}
+
+export type PlannedAction = 'copy' | 'replace' | 'link' | 'remove' | 'rewrite_frontmatter';
+
+export interface PlannedOperation {
+  action: PlannedAction;
+  skill: string;
+  agentId?: string;
+  source?: string;
+  target: string;
+  reason: string;
+}
+
+export type DiffAgainst = { kind: 'agent'; id: string } | { kind: 'skill'; id: string };
+
+export interface FileDiff {
+  path: string;
+  change: 'added' | 'removed' | 'modified';
+  unified?: string;
+  binary?: {
+    oldSize: number | null;
+    newSize: number | null;
+    oldSha256: string | null;
+    newSha256: string | null;
+  };
+}
+
+export interface DirDiff {
+  oldPath: string;
+  newPath: string;
+  files: FileDiff[];
+}
+
+export interface DistributionReport {
+  unchanged: number;
+  updated: number;
+  created: number;
+  files: CopyReport;
+  blocked: { skill: string; agentId: string; rules: string[] }[];
+}
+
+export interface PullReport {
+  skill: Skill;
+  snapshot: string | null;
+  distribution: DistributionReport;
+}
+
 export interface AgentInfo {
   id: AgentId;
   name: string;
@@ -30,12 +106,21 @@ export interface AgentInfo {
   icon: string;
   projectPath?: string;
   globalPath?: string;
+  distributionMode?: 'copy' | 'symlink';
 }
 
+export type SourceKind = 'git' | 'zip' | 'npx';
+
 export interface Skill {
   id: string;
   name: string;
   sourceUrl?: string;
+  sourceCommit?: string;
+  sourceSubpath?: string;
+  sourceKind?: SourceKind;
+  sourceRef?: string;
+  sourceChecksum?: string;
+  installedAt?: string;
   installSource?: 'platform' | 'external';
   isAdopted?: boolean;
   description?: string;
@@ -43,7 +128,166 @@ export interface Skill {
   enabledAgents: AgentId[];
   lastSync?: string;
   lastUpdate?: string;
+  lastSyncDigest?: string;
   deletedAt?: string;
+  trashEntryId?: string;
+}
+
+export interface SkillMetadata {
+  name: string | null;
+  description: string | null;
+  version: string | null;
+  license: string | null;
+  allowedTools: string[];
+  tags: string[];
+  extra: Record<string, unknown>;
+}
+
+export interface LintDiagnostic {
+  rule: string;
+  severity: 'error' | 'warning';
+  file: string;
+  line: number | null;
+  message: string;
+  fixable: boolean;
+}
+
+export interface LintReport {
+  skill: string;
+  diagnostics: LintDiagnostic[];
+  fixed: string[];
+}
+
+export interface InstallProgressLog {
+  id: string;
+  label: string;
+  status: 'loading' | 'success' | 'error';
+  progress: number;
+  bytes?: number;
+  totalBytes?: number;
+}
+
+export interface SourceSkill {
+  /** Relative to the inspected URL; empty when the source itself is the skill. */
+  subpath: string;
+  name: string;
+  metadata: SkillMetadata | null;
+}
+
+export interface SourceInspection {
+  url: string;
+  commit: string | null;
+  skills: SourceSkill[];
+}
+
+export interface SkillUpdate {
+  id: string;
+  name: string;
+  kind: SourceKind;
+  sourceUrl: string;
+  oldRevision: string | null;
+  newRevision: string;
+}
+
+export interface UpdateCheckFailure {
+  id: string;
+  name: string;
+  error: string;
+}
+
+export interface UpdateCheckReport {
+  outdated: SkillUpdate[];
+  failed: UpdateCheckFailure[];
+}
+
+export interface LockedSkill {
+  name: string;
+  kind: SourceKind;
+  sourceUrl: string;
+  reference?: string;
+  revision?: string;
+  subpath?: string;
+  contentHash: string;
+  enabledAgents: AgentId[];
+}
+
+export interface SkillLock {
+  version: number;
+  skills: LockedSkill[];
+}
+
+export interface LockExport {
+  lock: SkillLock;
+  skipped: string[];
+}
+
+export interface LockMismatch {
+  name: string;
+  kind: 'revision' | 'content';
+  expected: string;
+  actual: string | null;
+}
+
+export interface LockInstallReport {
+  installed: string[];
+  unchanged: string[];
+  mismatched: LockMismatch[];
+  failed: { name: string; error: string }[];
+  distribution: DistributionReport;
+}
+
+export interface BackupSummary {
+  skills: number;
+  files: number;
+}
+
+export interface BackupPreview {
+  createdAt: string;
+  skills: string[];
+  conflicts: string[];
+  agents: AgentInfo[];
+}
+
+export type ImportStrategy = 'skip' | 'overwrite' | 'keep_both';
+
+export interface ImportReport {
+  imported: string[];
+  overwritten: string[];
+  renamed: { from: string; to: string }[];
+  skipped: string[];
+  agents: AgentInfo[];
+}
+
+export interface TrashedCopy {
+  agentId: string;
+  original: string;
+  linkTarget?: string;
+}
+
+export interface TrashEntry {
+  id: string;
+  name: string;
+  deletedAt: string;
+  record?: Skill;
+  hasStoreCopy: boolean;
+  copies: TrashedCopy[];
+}
+
+export interface RestoreReport {
+  skill: Skill;
+  skipped: TrashedCopy[];
+}
+
+export interface RecoveryReport {
+  rolledBack: string[];
+  completed: string[];
+  removedTempDirs: string[];
+  failed: string[];
+}
+
+export interface StoreBootstrap {
+  skills: Skill[];
+  recovery: RecoveryReport;
 }
 
 export interface StartupDetectedSkill {